@@ -90,6 +90,16 @@ delete_file "$SCRIPT_DIR/tui-game"
 delete_dir "$SCRIPT_DIR/assets"
 delete_dir "$SCRIPT_DIR/scripts"
 delete_dir "$SCRIPT_DIR/tui-game-data"
+delete_dir "$HOME/Library/Application Support/tui-game"
+delete_dir "$HOME/Library/Caches/tui-game"
+if [ -n "${TUI_GAME_DATA_DIR:-}" ]; then
+    delete_dir "$TUI_GAME_DATA_DIR/profiles"
+    delete_dir "$TUI_GAME_DATA_DIR/games"
+    for name in leaderboards.json scan_cache.json updater_cache.json last_profile.txt; do
+        delete_file "$TUI_GAME_DATA_DIR/$name"
+    done
+    rmdir "$TUI_GAME_DATA_DIR" >/dev/null 2>&1 || true
+fi
 
 if [ "$INTEGRATION_CLEAN_OK" -ne 1 ]; then
     echo "[WARNING] Current environment variable was not cleaned. Manual cleanup is recommended."
@@ -56,6 +56,16 @@ call :delete_file "!EXE_PATH!"
 call :delete_dir "!SCRIPT_DIR!\assets"
 call :delete_dir "!SCRIPT_DIR!\scripts"
 call :delete_dir "!SCRIPT_DIR!\tui-game-data"
+call :delete_dir "%APPDATA%\tui-game"
+call :delete_dir "%LOCALAPPDATA%\tui-game"
+if defined TUI_GAME_DATA_DIR (
+    call :delete_dir "%TUI_GAME_DATA_DIR%\profiles"
+    call :delete_dir "%TUI_GAME_DATA_DIR%\games"
+    for %%F in (leaderboards.json scan_cache.json updater_cache.json last_profile.txt) do (
+        call :delete_file "%TUI_GAME_DATA_DIR%\%%F"
+    )
+    rd "%TUI_GAME_DATA_DIR%" >nul 2>&1
+)
 
 if "!ENV_CLEANED!"=="0" (
     echo [WARNING] Current environment variable was not cleaned. Manual cleanup is recommended.
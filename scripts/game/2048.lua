@@ -2,7 +2,18 @@
 -- 游戏元数据，供启动器显示
 GAME_META = {
     name = "2048",
-    description = "Merge equal tiles to reach 131072!"
+    description = "Merge equal tiles to reach 131072!",
+    min_size = { width = 36, height = 26 },
+    tags = { "puzzle" },
+    actions = {
+        { name = "move_up", keys = { "up" } },
+        { name = "move_down", keys = { "down" } },
+        { name = "move_left", keys = { "left" } },
+        { name = "move_right", keys = { "right" } },
+        { name = "restart", keys = { "r" } },
+        { name = "save", keys = { "s" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 -- 游戏常量定义
@@ -1039,6 +1050,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
@@ -1091,7 +1103,7 @@ end
 local function game_loop()
     while true do
         -- 非阻塞获取按键
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil))
 
         -- 检查终端尺寸
         if ensure_terminal_size_ok() then
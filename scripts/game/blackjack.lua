@@ -1,7 +1,25 @@
 ﻿-- 21点游戏元数据
 GAME_META = {
     name = "Blackjack",
-    description = "Play against the dealer and manage your bets to win with 21."
+    description = "Play against the dealer and manage your bets to win with 21.",
+    min_size = { width = 110, height = 30 },
+    tags = { "cards", "casual" },
+    records = {
+        { name = "high_score", label = "game_selection.label.high_net_profit" }
+    },
+    actions = {
+        { name = "bet_up", keys = { "+", "=" } },
+        { name = "bet_down", keys = { "-" } },
+        { name = "hand_left", keys = { "left" } },
+        { name = "hand_right", keys = { "right" } },
+        { name = "hit", keys = { "space" } },
+        { name = "stand", keys = { "enter" } },
+        { name = "double", keys = { "z" } },
+        { name = "split", keys = { "x" } },
+        { name = "insurance", keys = { "c" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 -- 游戏常量定义
@@ -1349,6 +1367,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
     if term_w >= min_w and term_h >= min_h then
         local resized = (term_w ~= state.last_term_w) or (term_h ~= state.last_term_h)
         state.last_term_w = term_w
@@ -1392,7 +1411,7 @@ end
 -- 主游戏循环
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil))
         if ensure_terminal_size_ok() then
             local action = handle_input(key)
             if action == "exit" then
@@ -1,7 +1,19 @@
 -- 颜色记忆游戏元数据
 GAME_META = {
     name = "Color Memory",
-    description = "Repeat the color sequence exactly as the system presents it."
+    description = "Repeat the color sequence exactly as the system presents it.",
+    min_size = { width = 50, height = 19 },
+    tags = { "memory" },
+    actions = {
+        { name = "color_1", keys = { "1" } },
+        { name = "color_2", keys = { "2" } },
+        { name = "color_3", keys = { "3" } },
+        { name = "color_4", keys = { "4" } },
+        { name = "submit", keys = { "enter" } },
+        { name = "delete", keys = { "backspace", "delete" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 -- 游戏常量定义
@@ -340,6 +352,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         local resized = (term_w ~= state.last_term_w) or (term_h ~= state.last_term_h)
@@ -823,7 +836,7 @@ end
 -- 主游戏循环
 local function game_loop()
     while state.running do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil))
         local action = handle_input(key)
         if action == "exit" then
             return
@@ -1,7 +1,26 @@
 ﻿-- 关灯游戏元数据
 GAME_META = {
     name = "Lights Out",
-    description = "Light all tiles by toggling cross patterns."
+    description = "Light all tiles by toggling cross patterns.",
+    min_size = { width = 26, height = 17 },
+    tags = { "puzzle" },
+    records = {
+        { name = "max_size", label = "game.lights_out.best_size", unit = "size", better = "higher" },
+        { name = "min_steps", label = "game.lights_out.best_steps", unit = "moves", better = "lower", dimension = "size" },
+        { name = "min_time", label = "game.lights_out.best_time", unit = "seconds", better = "lower", dimension = "size" }
+    },
+    actions = {
+        { name = "move_up", keys = { "up" } },
+        { name = "move_down", keys = { "down" } },
+        { name = "move_left", keys = { "left" } },
+        { name = "move_right", keys = { "right" } },
+        { name = "toggle", keys = { "space" } },
+        { name = "board_size", keys = { "p" } },
+        { name = "jump", keys = { "d" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 -- 游戏常量定义
@@ -278,6 +297,17 @@ local function load_best_record()
     }
 end
 
+-- 向宿主提交成绩记录，同一尺寸分别记最少步数和最短时间
+local function submit_records(record)
+    if type(submit_record) ~= "function" or record == nil then
+        return
+    end
+    local size = tostring(record.max_size) .. "x" .. tostring(record.max_size)
+    pcall(submit_record, "max_size", record.max_size)
+    pcall(submit_record, "min_steps", record.min_steps, size)
+    pcall(submit_record, "min_time", record.min_time_sec, size)
+end
+
 -- 判断是否应该替换最佳记录
 local function should_replace_best(old, new)
     if old == nil then
@@ -313,6 +343,7 @@ local function commit_best_if_needed()
         min_steps = state.steps,
         min_time_sec = elapsed_seconds()
     }
+    submit_records(record)
     if should_replace_best(state.best, record) then
         state.best = record
         save_best_record(record)
@@ -477,6 +508,7 @@ local function init_game()
     end
     state.last_term_w, state.last_term_h = w, h
     state.best = load_best_record()
+    submit_records(state.best)
     state.launch_mode = read_launch_mode()
     if state.launch_mode == "continue" then
         if not load_game_state() then
@@ -792,6 +824,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
@@ -1097,7 +1130,7 @@ end
 -- 主游戏循环
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil or state.input_mode ~= nil))
 
         if ensure_terminal_size_ok() then
             local action = handle_input(key)
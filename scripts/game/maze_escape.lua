@@ -1,7 +1,23 @@
 -- 迷宫逃脱游戏元数据
 GAME_META = {
     name = "Maze Escape",
-    description = "Find the fastest route out of a randomly generated maze."
+    description = "Find the fastest route out of a randomly generated maze.",
+    min_size = { width = 30, height = 16 },
+    tags = { "puzzle" },
+    records = {
+        { name = "max_mode", label = "game.maze_escape.best_max_mode", unit = "score", better = "higher" },
+        { name = "fastest", label = "game.maze_escape.best_fastest", unit = "seconds", better = "lower" }
+    },
+    actions = {
+        { name = "move_up", keys = { "up" } },
+        { name = "move_down", keys = { "down" } },
+        { name = "move_left", keys = { "left" } },
+        { name = "move_right", keys = { "right" } },
+        { name = "config", keys = { "p" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 -- 游戏常量定义
@@ -348,6 +364,18 @@ local function load_best_record()
     end
 end
 
+-- 向宿主提交成绩记录
+local function submit_records()
+    -- 还没有通关过时不提交
+    if type(submit_record) ~= "function" or state.best.max_area <= 0 then
+        return
+    end
+    pcall(submit_record, "max_mode", state.best.max_mode)
+    if state.best.min_time_sec ~= nil then
+        pcall(submit_record, "fastest", state.best.min_time_sec)
+    end
+end
+
 -- 保存最佳记录
 local function save_best_record()
     if type(save_data) ~= "function" then
@@ -897,6 +925,7 @@ local function commit_result_if_needed()
             state.best.min_time_sec = duration
         end
         save_best_record()
+        submit_records()
     end
     if type(update_game_stats) == "function" then
         pcall(update_game_stats, "maze_escape", score, duration)
@@ -1147,6 +1176,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
@@ -1452,6 +1482,7 @@ local function init_game()
     state.last_term_h = h
     state.launch_mode = read_launch_mode()
     load_best_record()
+    submit_records()
     if state.launch_mode == "continue" then
         if not load_game_state() then
             build_maze(DEFAULT_COLS, DEFAULT_ROWS, DEFAULT_MODE)
@@ -1465,7 +1496,7 @@ end
 -- 主游戏循环
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil or state.input_mode ~= nil))
 
         if ensure_terminal_size_ok() then
             local action = handle_input(key)
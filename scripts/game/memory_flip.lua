@@ -1,7 +1,26 @@
 ﻿-- 记忆翻牌游戏元数据
 GAME_META = {
     name = "Memory Flip",
-    description = "Flip cards and match identical pairs with memory."
+    description = "Flip cards and match identical pairs with memory.",
+    min_size = { width = 26, height = 17 },
+    tags = { "memory" },
+    records = {
+        { name = "max_difficulty", label = "game.memory_flip.best_difficulty", unit = "score", better = "higher" },
+        { name = "min_steps", label = "game.memory_flip.best_steps", unit = "moves", better = "lower", dimension = "difficulty" },
+        { name = "min_time", label = "game.memory_flip.best_time", unit = "seconds", better = "lower", dimension = "difficulty" }
+    },
+    actions = {
+        { name = "move_up", keys = { "up" } },
+        { name = "move_down", keys = { "down" } },
+        { name = "move_left", keys = { "left" } },
+        { name = "move_right", keys = { "right" } },
+        { name = "flip", keys = { "space" } },
+        { name = "difficulty", keys = { "p" } },
+        { name = "jump", keys = { "d" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 -- 难度设置
@@ -512,6 +531,16 @@ local function load_best_record()
     }
 end
 
+-- 向宿主提交成绩记录，每个难度分别记最少步数和最短时间
+local function submit_records(record)
+    if type(submit_record) ~= "function" or record == nil then
+        return
+    end
+    pcall(submit_record, "max_difficulty", record.difficulty)
+    pcall(submit_record, "min_steps", record.min_steps, tostring(record.difficulty))
+    pcall(submit_record, "min_time", record.min_time_sec, tostring(record.difficulty))
+end
+
 -- 判断是否应该替换最佳记录
 local function should_replace_best(old, new)
     if old == nil then
@@ -547,6 +576,7 @@ local function commit_best_if_needed()
         min_steps = state.steps,
         min_time_sec = elapsed_seconds()
     }
+    submit_records(record)
     if should_replace_best(state.best, record) then
         state.best = record
         save_best_record(record)
@@ -608,6 +638,7 @@ local function init_game()
     end
     state.last_term_w, state.last_term_h = w, h
     state.best = load_best_record()
+    submit_records(state.best)
     state.launch_mode = read_launch_mode()
     if state.launch_mode == "continue" then
         if not load_game_state() then
@@ -954,6 +985,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
@@ -1326,7 +1358,7 @@ end
 -- 主游戏循环
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil or state.input_mode ~= nil))
 
         if ensure_terminal_size_ok() then
             hide_pending_pair_if_needed()
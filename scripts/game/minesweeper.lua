@@ -1,7 +1,27 @@
 ﻿-- 扫雷游戏元数据
 GAME_META = {
     name = "Minesweeper",
-    description = "Reveal safe cells and mark all hidden mines."
+    description = "Reveal safe cells and mark all hidden mines.",
+    min_size = { width = 28, height = 15 },
+    tags = { "puzzle", "strategy" },
+    records = {
+        { name = "best_time", unit = "seconds", better = "lower", dimension = "difficulty" }
+    },
+    leaderboard = { unit = "seconds", better = "lower", dimension = "difficulty" },
+    actions = {
+        { name = "move_up", keys = { "up" } },
+        { name = "move_down", keys = { "down" } },
+        { name = "move_left", keys = { "left" } },
+        { name = "move_right", keys = { "right" } },
+        { name = "open", keys = { "space" } },
+        { name = "mark_mine", keys = { "z" } },
+        { name = "mark_suspect", keys = { "x" } },
+        { name = "config", keys = { "p" } },
+        { name = "jump", keys = { "d" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 -- 官方标准难度配置
@@ -312,6 +332,14 @@ local function load_best_record()
     return out
 end
 
+-- 向宿主提交某个难度的成绩记录
+local function submit_best_time(difficulty, seconds)
+    if type(submit_record) ~= "function" or seconds == nil then
+        return
+    end
+    pcall(submit_record, "best_time", seconds, tostring(difficulty))
+end
+
 -- 保存最佳记录
 local function save_best_record()
     if type(save_data) ~= "function" then
@@ -333,6 +361,7 @@ local function commit_best_if_needed()
     end
     if state.difficulty >= MIN_DIFFICULTY and state.difficulty <= MAX_DIFFICULTY then
         local elapsed = elapsed_seconds()
+        submit_best_time(state.difficulty, elapsed)
         local old = state.best[state.difficulty]
         if old == nil or elapsed < old then
             state.best[state.difficulty] = elapsed
@@ -547,6 +576,9 @@ local function init_game()
     end
     state.last_term_w, state.last_term_h = w, h
     state.best = load_best_record()
+    for d = MIN_DIFFICULTY, MAX_DIFFICULTY do
+        submit_best_time(d, state.best[d])
+    end
     state.launch_mode = read_launch_mode()
     if state.launch_mode == "continue" then
         if not load_game_state() then
@@ -832,6 +864,19 @@ local function force_full_refresh()
     state.dirty = true
 end
 
+-- 标准难度获胜时按用时提交排行榜,能上榜时宿主会弹出输入名字的框
+local function submit_leaderboard_result()
+    if type(submit_leaderboard) ~= "function" then
+        return
+    end
+    if state.difficulty < MIN_DIFFICULTY or state.difficulty > MAX_DIFFICULTY then
+        return
+    end
+    local elapsed = elapsed_seconds()
+    pcall(submit_leaderboard, { score = elapsed, time = elapsed, mode = tostring(state.difficulty) })
+    force_full_refresh()
+end
+
 -- 主渲染函数
 local function render()
     local x, y, frame_w, frame_h = board_geometry()
@@ -917,6 +962,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
@@ -1037,6 +1083,7 @@ local function check_victory()
         state.end_frame = state.frame
         state.confirm_mode = nil
         commit_best_if_needed()
+        submit_leaderboard_result()
         state.dirty = true
         return true
     end
@@ -1417,7 +1464,7 @@ end
 -- 主游戏循环
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil or state.input_mode ~= nil))
 
         if ensure_terminal_size_ok() then
             local action = handle_input(key)
@@ -1,7 +1,20 @@
 ﻿-- 吃豆人游戏元数据
 GAME_META = {
     name = "Pac-Man",
-    description = "Collect pellets while avoiding roaming ghosts."
+    description = "Collect pellets while avoiding roaming ghosts.",
+    min_size = { width = 36, height = 32 },
+    tags = { "arcade" },
+    records = {
+        { name = "high_score" }
+    },
+    actions = {
+        { name = "move_up", keys = { "up" } },
+        { name = "move_down", keys = { "down" } },
+        { name = "move_left", keys = { "left" } },
+        { name = "move_right", keys = { "right" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 -- 帧率控制
@@ -1507,6 +1520,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
             clear()
@@ -1593,7 +1607,7 @@ end
 -- 主游戏循环
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil))
         if ensure_terminal_size_ok() then
             update_logic(key)
             sync_resize()
@@ -1,7 +1,19 @@
 -- 石头剪刀布游戏元数据
 GAME_META = {
     name = "Rock Paper Scissors",
-    description = "Challenge the computer in classic rock-paper-scissors rounds."
+    description = "Challenge the computer in classic rock-paper-scissors rounds.",
+    min_size = { width = 26, height = 10 },
+    tags = { "casual" },
+    records = {
+        { name = "high_score", label = "game.rock_paper_scissors.best_streak" }
+    },
+    actions = {
+        { name = "scissors", keys = { "1" } },
+        { name = "rock", keys = { "2" } },
+        { name = "paper", keys = { "3" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 -- 帧率控制
@@ -271,6 +283,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
@@ -410,7 +423,7 @@ end
 -- 主游戏循环
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false))
 
         if ensure_terminal_size_ok() then
             local action = handle_input(key)
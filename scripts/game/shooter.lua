@@ -1,6 +1,18 @@
 GAME_META = {
     name = "Air Shooter",
-    description = "Pilot a fighter and dodge enemy fire."
+    description = "Pilot a fighter and dodge enemy fire.",
+    min_size = { width = 66, height = 24 },
+    tags = { "arcade" },
+    actions = {
+        { name = "move_left", keys = { "left" } },
+        { name = "move_right", keys = { "right" } },
+        { name = "fire_mode", keys = { "z" } },
+        { name = "fire", keys = { "space" } },
+        { name = "nuke", keys = { "x" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 local FPS = 60
@@ -1544,6 +1556,7 @@ end
 local function ensure_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then clear(); state.last_area = nil; state.dirty = true end
@@ -1599,7 +1612,9 @@ local function handle_input(key)
 
     if state.phase == "lost" then
         if key == "r" then reset_run(); return end
-        if key == "q" or key == "esc" then commit_result_once(); exit_game(); return end
+        if key == "q" or key == "esc" then
+            commit_result_once(); exit_game({ outcome = "lost", score = state.score }); return
+        end
         return
     end
 
@@ -1643,7 +1658,7 @@ end
 
 local function loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil))
 
         if ensure_size_ok() then
             handle_input(key)
@@ -1,6 +1,18 @@
 GAME_META = {
     name = "Number Sliding Puzzle",
-    description = "Slide numbered tiles into ascending order."
+    description = "Slide numbered tiles into ascending order.",
+    min_size = { width = 36, height = 26 },
+    tags = { "puzzle" },
+    actions = {
+        { name = "move_up", keys = { "up" } },
+        { name = "move_down", keys = { "down" } },
+        { name = "move_left", keys = { "left" } },
+        { name = "move_right", keys = { "right" } },
+        { name = "move_mode", keys = { "x" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 local SIZE = 4
@@ -849,6 +861,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
@@ -899,7 +912,7 @@ end
 
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil))
 
         if ensure_terminal_size_ok() then
             local action = handle_input(key)
@@ -1,6 +1,17 @@
 GAME_META = {
     name = "Snake",
-    description = "Control the snake, eat food, and avoid biting yourself."
+    description = "Control the snake, eat food, and avoid biting yourself.",
+    min_size = { width = 34, height = 20 },
+    tags = { "arcade" },
+    actions = {
+        { name = "move_up", keys = { "up", "k" } },
+        { name = "move_down", keys = { "down", "j" } },
+        { name = "move_left", keys = { "left", "h" } },
+        { name = "move_right", keys = { "right", "l" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 local GRID_W = 24
@@ -709,6 +720,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
@@ -884,7 +896,7 @@ end
 
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil))
 
         if ensure_terminal_size_ok() then
             if key ~= "" and not should_debounce(key) then
@@ -1,7 +1,28 @@
 
 GAME_META = {
     name = "Solitaire",
-    description = "Play FreeCell, Klondike, or Spider Solitaire in one game."
+    description = "Play FreeCell, Klondike, or Spider Solitaire in one game.",
+    min_size = { width = 70, height = 31 },
+    tags = { "cards" },
+    records = {
+        { name = "best_time", unit = "seconds", better = "lower", dimension = "mode" }
+    },
+    actions = {
+        { name = "column_left", keys = { "left" } },
+        { name = "column_right", keys = { "right" } },
+        { name = "pick_more", keys = { "up" } },
+        { name = "pick_less", keys = { "down" } },
+        { name = "select", keys = { "space" } },
+        { name = "move", keys = { "enter" } },
+        { name = "cancel", keys = { "z" } },
+        { name = "special", keys = { "x" } },
+        { name = "take", keys = { "c" } },
+        { name = "switch_mode", keys = { "p" } },
+        { name = "undo", keys = { "a" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 local FPS = 60
@@ -478,6 +499,11 @@ local function pop_undo()
     return true
 end
 
+local function submit_best_time(key, seconds)
+    if type(submit_record) ~= "function" or seconds <= 0 then return end
+    pcall(submit_record, "best_time", seconds, key)
+end
+
 local function load_best_record()
     state.best.freecell = 0
     state.best.klondike = 0
@@ -494,6 +520,9 @@ local function load_best_record()
     state.best.spider1 = math.max(0, math.floor(tonumber(data.spider1) or 0))
     state.best.spider2 = math.max(0, math.floor(tonumber(data.spider2) or 0))
     state.best.spider3 = math.max(0, math.floor(tonumber(data.spider3) or 0))
+    for _, k in ipairs({ "freecell", "klondike", "spider1", "spider2", "spider3" }) do
+        submit_best_time(k, state.best[k])
+    end
 end
 
 local function save_best_record()
@@ -524,6 +553,7 @@ local function update_best_if_needed()
     if not state.won then return end
     local elapsed = elapsed_seconds()
     local k = mode_key()
+    submit_best_time(k, elapsed)
     local old = state.best[k] or 0
     if old <= 0 or elapsed < old then
         state.best[k] = elapsed
@@ -1320,6 +1350,7 @@ end
 local function render()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_size()
+    if set_min_size then set_min_size(min_w, min_h) end
     state.last_term_w, state.last_term_h = term_w, term_h
 
     if term_w < min_w or term_h < min_h then
@@ -1408,7 +1439,7 @@ local function handle_result_key(key)
     if key == "r" then
         deal_new_game(state.mode, state.spider_diff)
     elseif key == "q" or key == "esc" then
-        exit_game()
+        exit_game({ outcome = "won", mode = mode_key() })
     end
 end
 
@@ -1563,7 +1594,7 @@ local function init_game()
 end
 
 local function input_tick()
-    local key = normalize_key(get_key(false))
+    local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil or state.mode_input))
     if key == "" then return end
 
     if state.mode_input then
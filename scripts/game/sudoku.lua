@@ -1,4 +1,21 @@
-﻿GAME_META = { name = "Sudoku", description = "Fill each row, column, and 3x3 box with digits 1-9 exactly once." }
+﻿GAME_META = { name = "Sudoku", description = "Fill each row, column, and 3x3 box with digits 1-9 exactly once.", tags = { "puzzle" },
+  min_size = { width = 42, height = 21 },
+  records = { { name = "best_time", label = "game.sudoku.best_time", unit = "seconds", better = "lower", dimension = "difficulty" } },
+  achievements = { { id = "first_win" }, { id = "no_undo" }, { id = "evil_win", hidden = true }, { id = "wins", target = 10 } },
+  actions = {
+    { name = "move_up", keys = { "up" } },
+    { name = "move_down", keys = { "down" } },
+    { name = "move_left", keys = { "left" } },
+    { name = "move_right", keys = { "right" } },
+    { name = "clear", keys = { "space" } },
+    { name = "undo", keys = { "a" } },
+    { name = "jump", keys = { "d" } },
+    { name = "difficulty", keys = { "p" } },
+    { name = "locator", keys = { "z" } },
+    { name = "save", keys = { "s" } },
+    { name = "restart", keys = { "r" } },
+    { name = "quit", keys = { "q", "esc" } }
+  } }
 local N, B, FPS, MS, UL = 9, 3, 60, 16, 100
 local HOLES = { [1] = 30, [2] = 40, [3] = 50, [4] = 60, [5] = 70 }
 local H1 = "      1 2 3  4 5 6  7 8 9"
@@ -7,7 +24,7 @@ local BT = "    ╔══════╤══════╤══════
 local BM = "    ╟──────┼──────┼──────╢"
 local BB = "    ╚══════╧══════╧══════╝"
 local S = { d = 3, p = {}, sol = {}, b = {}, g = {}, cf = {}, r = 1, c = 1, undo = {}, f = 0, sf = 0, ef = nil, win = false, bc = false, im = nil, ib =
-"", cm = nil, toast = nil, tu = 0, as = 0, best = nil, dirty = true, le = -1, lt = false, launch = "new", area = nil, tw = 0, th = 0, hl = false }
+"", cm = nil, toast = nil, tu = 0, as = 0, best = nil, dirty = true, le = -1, lt = false, launch = "new", area = nil, tw = 0, th = 0, hl = false, uu = false }
 local function tr(key)
     if type(translate) ~= "function" then
         return key
@@ -190,8 +207,15 @@ local function load_best()
 end
 local function save_best(x) if type(save_data) == "function" then pcall(save_data, "sudoku_best",
       { d = x.d, t = x.t, difficulty = x.d, min_time_sec = x.t }) end end
+local function sbest(x) if x and type(submit_record) == "function" then pcall(submit_record, "best_time", x.t,
+      tostring(x.d)) end end
+local function ach(id, n)
+  if n then if type(add_achievement_progress) == "function" then pcall(add_achievement_progress, id, n) end
+  elseif type(unlock_achievement) == "function" then pcall(unlock_achievement, id) end
+end
 local function cbest()
-  if S.bc or not S.win then return end; local n = { d = S.d, t = sec() }
+  if S.bc or not S.win then return end; local n = { d = S.d, t = sec() }; sbest(n)
+  ach("first_win"); ach("wins", 1); if not S.uu then ach("no_undo") end; if S.d == 5 then ach("evil_win") end
   if rep(S.best, n) then
     S.best = n; save_best(n)
   end; if type(update_game_stats) == "function" then
@@ -210,7 +234,7 @@ local function pushu(r, c, o, n)
     S.undo, 1) end
 end
 local function snap() return { d = S.d, p = cp(S.p), s = cp(S.sol), b = cp(S.b), g = cp(S.g), r = S.r, c = S.c, e = sec(), w =
-  S.win, u = S.undo, a = S.as, hl = S.hl } end
+  S.win, u = S.undo, a = S.as, hl = S.hl, uu = S.uu } end
 local function vm(m)
   if type(m) ~= "table" then return false end; for r = 1, N do
     if type(m[r]) ~= "table" then return false end; for c = 1, N do
@@ -229,7 +253,7 @@ local function restore(x)
         S.p[r][c] ~= 0 end end end
   S.r = math.max(1, math.min(9, math.floor(tonumber(x.r) or 1))); S.c = math.max(1,
     math.min(9, math.floor(tonumber(x.c) or 1)))
-  S.hl = (x.hl == true)
+  S.hl = (x.hl == true); S.uu = (x.uu == true)
   S.undo = {}; local u = x.u or x.undo_stack; if type(u) == "table" then for i = 1, #u do
       local e = u[i]
       if type(e) == "table" then
@@ -276,7 +300,7 @@ end
 local function reset(d)
   S.d = math.max(1, math.min(5, d or S.d)); local p, s = gen(S.d); S.p, S.sol, S.b = p, s, cp(p); S.g = mx(false)
   for r = 1, N do for c = 1, N do S.g[r][c] = S.p[r][c] ~= 0 end end
-  S.cf = mx(false); S.r, S.c = 1, 1; S.undo = {}; S.sf = S.f; S.ef = nil; S.win = false; S.bc = false; S.im = nil; S.ib = ""; S.cm =
+  S.cf = mx(false); S.r, S.c = 1, 1; S.undo = {}; S.uu = false; S.sf = S.f; S.ef = nil; S.win = false; S.bc = false; S.im = nil; S.ib = ""; S.cm =
   nil; S.toast = nil; S.tu = 0; S.as = 0; S.area = nil; S.dirty = true; recf()
 end
 local function setv(r, c, n)
@@ -286,7 +310,7 @@ end
 local function undo()
   local n = #S.undo; if n <= 0 then
     S.toast = tr("game.sudoku.undo_empty"); S.tu = S.f + 3 * FPS; S.dirty = true; return
-  end; local e = table.remove(S.undo, n); if S.g[e.r][e.c] then return end; S.b[e.r][e.c] = e.o; recf(); chk(); S.toast =
+  end; local e = table.remove(S.undo, n); if S.g[e.r][e.c] then return end; S.b[e.r][e.c] = e.o; S.uu = true; recf(); chk(); S.toast =
   tr("game.sudoku.undo_done"); S.tu = S.f + 2 * FPS; S.dirty = true
 end
 local function wrap(t, w)
@@ -422,7 +446,9 @@ local function warn(tw, th, mw, mh)
 end
 local function size_ok()
   local g = geo(); local tw, th, mw, mh = g.tw, g.th, g.rw, g.rh
-  if tw >= mw and th >= mh then return true end
+  if set_min_size then set_min_size(mw, mh) end
+  local ok = tw >= mw and th >= mh
+  if ok then return true end
   clear(); warn(tw, th, mw, mh); return false
 end
 local function sync_resize()
@@ -544,13 +570,13 @@ local function input(k)
   return "none"
 end
 local function init()
-  clear(); S.tw, S.th = ts(); S.best = load_best(); S.launch = lmode()
+  clear(); S.tw, S.th = ts(); S.best = load_best(); sbest(S.best); S.launch = lmode()
   if S.launch == "continue" then if not load_state() then reset(3) end else reset(3) end
   S.dirty = true
 end
 local function loop()
   while true do
-    local k = key(get_key(false))
+    local k = key(get_action_key(false, S.cm ~= nil or S.im ~= nil))
     if size_ok() then
       local a = input(k); if a == "exit" then return end
       sync_resize(); autosave(); refresh(); if S.dirty then
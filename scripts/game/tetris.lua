@@ -1,6 +1,29 @@
 ﻿GAME_META = {
     name = "俄罗斯方块",
-    description = "Stack falling blocks and clear complete lines."
+    description = "Stack falling blocks and clear complete lines.",
+    min_size = { width = 72, height = 28 },
+    tags = { "arcade" },
+    records = {
+        { name = "high_score" }
+    },
+    achievements = {
+        { id = "four_lines" },
+        { id = "lines_100", target = 100 },
+        { id = "reincarnation", hidden = true }
+    },
+    leaderboard = {},
+    actions = {
+        { name = "move_left", keys = { "left" } },
+        { name = "move_right", keys = { "right" } },
+        { name = "rotate_left", keys = { "z" } },
+        { name = "rotate_right", keys = { "x" } },
+        { name = "soft_drop", keys = { "down" } },
+        { name = "hard_drop", keys = { "space" } },
+        { name = "level", keys = { "p" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 local BOARD_W = 10
@@ -123,7 +146,7 @@ local state = {
     counters = { single = 0, double = 0, triple = 0, tetris = 0 },
     piece_used = { I = 0, O = 0, T = 0, Z = 0, L = 0, S = 0, J = 0 },
     color_group = { 27, 27, 27 }, color_level = -999,
-    game_over = false, end_frame = nil, result_committed = false,
+    game_over = false, end_frame = nil, result_committed = false, leaderboard_checked = false,
     confirm_mode = nil, input_mode = nil, input_buffer = "",
     toast_text = nil, toast_until = 0,
     frame = 0, start_frame = 0, drop_accum = 0, last_auto_save_sec = 0,
@@ -465,10 +488,21 @@ local function clear_full_rows()
     return cleared
 end
 
+local function achieve(id, amount)
+    if amount ~= nil then
+        if type(add_achievement_progress) == "function" then
+            pcall(add_achievement_progress, id, amount)
+        end
+    elseif type(unlock_achievement) == "function" then
+        pcall(unlock_achievement, id)
+    end
+end
+
 local function apply_level_up()
     if state.level >= 255 then
         state.level = 0
         state.reincarnated = true
+        achieve("reincarnation")
         state.phase = state.phase + 1
     else
         state.level = state.level + 1
@@ -479,6 +513,10 @@ end
 
 local function apply_line_result(cleared)
     if cleared <= 0 then return end
+    achieve("lines_100", cleared)
+    if cleared >= 4 then
+        achieve("four_lines")
+    end
 
     if cleared == 1 then
         state.counters.single = state.counters.single + 1
@@ -704,6 +742,7 @@ local function reset_run(start_level)
     state.game_over = false
     state.end_frame = nil
     state.result_committed = false
+    state.leaderboard_checked = false
     state.confirm_mode = nil
     state.input_mode = nil
     state.input_buffer = ""
@@ -826,6 +865,7 @@ end
 local function ensure_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
 
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
@@ -856,6 +896,15 @@ local function force_full_refresh()
     state.dirty = true
 end
 
+-- 游戏结束时提交排行榜,能上榜时宿主会弹出输入名字的框
+local function submit_leaderboard_once()
+    if state.leaderboard_checked then return end
+    state.leaderboard_checked = true
+    if type(submit_leaderboard) ~= "function" or state.score <= 0 then return end
+    pcall(submit_leaderboard, { score = state.score, time = elapsed_seconds() })
+    force_full_refresh()
+end
+
 local function draw_frame(layout)
     local x, y = layout.board_x, layout.board_y
     draw_text(x, y, BORDER_TL .. string.rep(BORDER_H, FRAME_W - 2) .. BORDER_TR, "blue", "black")
@@ -1181,7 +1230,7 @@ local function handle_input(key)
         end
         if key == "q" or key == "esc" then
             commit_result_once()
-            exit_game()
+            exit_game({ outcome = "finished", score = state.score })
             return
         end
         return
@@ -1255,11 +1304,14 @@ end
 
 local function loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil or state.input_mode ~= nil))
 
         if ensure_size_ok() then
             handle_input(key)
             gameplay_update()
+            if state.game_over then
+                submit_leaderboard_once()
+            end
             update_toast_timer()
             refresh_dirty_time()
             sync_resize()
@@ -1,6 +1,19 @@
 GAME_META = {
     name = "井字棋",
-    description = "Place X and O marks and connect three in a row."
+    description = "Place X and O marks and connect three in a row.",
+    min_size = { width = 42, height = 12 },
+    tags = { "strategy", "casual" },
+    records = {},
+    actions = {
+        { name = "move_up", keys = { "up", "k" } },
+        { name = "move_down", keys = { "down", "j" } },
+        { name = "move_left", keys = { "left", "h" } },
+        { name = "move_right", keys = { "right", "l" } },
+        { name = "place", keys = { "space", "enter" } },
+        { name = "switch_mark", keys = { "x" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 local FPS = 60
@@ -497,6 +510,7 @@ end
 local function ensure_terminal_size_ok()
     local term_w, term_h = terminal_size()
     local min_w, min_h = minimum_required_size()
+    if set_min_size then set_min_size(min_w, min_h) end
     if term_w >= min_w and term_h >= min_h then
         if state.size_warning_active then
             clear()
@@ -731,7 +745,7 @@ end
 
 local function game_loop()
     while true do
-        local key = normalize_key(get_key(false))
+        local key = normalize_key(get_action_key(false, state.confirm_mode ~= nil))
         if ensure_terminal_size_ok() then
             if key ~= "" and not should_debounce(key) then
                 if state.confirm_mode ~= nil then
@@ -1,7 +1,29 @@
 ﻿
 GAME_META = {
     name = "24 Points",
-    description = "Use A/J/Q/K and + - * / () to form an expression equal to 24."
+    description = "Use A/J/Q/K and + - * / () to form an expression equal to 24.",
+    min_size = { width = 66, height = 13 },
+    tags = { "cards", "puzzle" },
+    records = {
+        { name = "best_time", label = "game.twenty_four.best_time", unit = "seconds", better = "lower" }
+    },
+    actions = {
+        { name = "cursor_left", keys = { "left" } },
+        { name = "cursor_right", keys = { "right" } },
+        { name = "toggle", keys = { "c" } },
+        { name = "swap_up", keys = { "up" } },
+        { name = "swap_down", keys = { "down" } },
+        { name = "plus", keys = { "1", "+" } },
+        { name = "minus", keys = { "2", "-" } },
+        { name = "multiply", keys = { "3", "*" } },
+        { name = "divide", keys = { "4", "/" } },
+        { name = "clear", keys = { "space" } },
+        { name = "add_paren", keys = { "z" } },
+        { name = "remove_paren", keys = { "x" } },
+        { name = "difficulty", keys = { "p" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 local FPS, FRAME_MS, EPS = 60, 16, 1e-6
@@ -200,16 +222,21 @@ local function rand_num(mode)
     return (rnd(100) < 50) and -v or v
 end
 
+local function submit_best(t)
+    if t > 0 and type(submit_record) == "function" then pcall(submit_record, "best_time", t) end
+end
+
 local function load_best()
     S.best_time = 0
     if type(load_data) ~= "function" then return end
     local ok, d = pcall(load_data, "twenty_four_best_time")
     if not ok then return end
-    if type(d) == "number" then S.best_time = math.max(0, math.floor(d)); return end
+    if type(d) == "number" then S.best_time = math.max(0, math.floor(d)); submit_best(S.best_time); return end
     if type(d) == "table" then
         local s = tonumber(d.time_sec) or tonumber(d.best_time_sec) or 0
         S.best_time = math.max(0, math.floor(s))
     end
+    submit_best(S.best_time)
 end
 
 local function save_best()
@@ -220,6 +247,7 @@ local function commit_once()
     if S.committed then return end
     S.committed = true
     local t = sec()
+    submit_best(t)
     if S.best_time <= 0 or t < S.best_time then S.best_time = t; save_best() end
     if type(update_game_stats) == "function" then
         local score = math.max(0, 1000000 - t * 100 - S.steps)
@@ -525,6 +553,7 @@ end
 
 local function size_ok()
     local tw, th = ts(); local mw, mh = min_size()
+    if set_min_size then set_min_size(mw, mh) end
     if tw >= mw and th >= mh then
         if S.warn then clear(); S.dirty = true end
         if tw ~= S.tw or th ~= S.th then clear(); S.dirty = true end
@@ -763,7 +792,7 @@ end
 
 local function loop()
     while true do
-        local k = key(get_key(false))
+        local k = key(get_action_key(false, S.confirm ~= nil or S.input_mode ~= nil))
         if size_ok() then
             local a = "none"
             if S.confirm then a = handle_confirm(k) elseif S.input_mode then a = handle_input_mode(k) else a = handle_active(k) end
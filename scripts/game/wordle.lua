@@ -1,6 +1,19 @@
 ﻿GAME_META = {
     name = "Wordle",
-    description = "Guess the hidden word using color hints from each attempt."
+    description = "Guess the hidden word using color hints from each attempt.",
+    min_size = { width = 60, height = 14 },
+    tags = { "word", "puzzle" },
+    records = {
+        { name = "high_score", label = "game.wordle.best_streak" }
+    },
+    actions = {
+        { name = "switch_mode", keys = { "tab" } },
+        { name = "submit", keys = { "enter" } },
+        { name = "delete", keys = { "backspace", "delete" } },
+        { name = "save", keys = { "s" } },
+        { name = "restart", keys = { "r" } },
+        { name = "quit", keys = { "q", "esc" } }
+    }
 }
 
 local FPS, FRAME_MS = 60, 16
@@ -386,6 +399,7 @@ end
 local function size_ok()
     local tw, th = ts()
     local mw, mh = min_size()
+    if set_min_size then set_min_size(mw, mh) end
     if tw >= mw and th >= mh then
         if S.warn then clear(); S.dirty = true end
         if tw ~= S.tw or th ~= S.th then clear(); S.dirty = true end
@@ -657,7 +671,11 @@ local function loop()
             sleep(FRAME_MS)
             S.frame = S.frame + 1
         else
-            local k = key(get_key(false))
+            local k, raw = get_action_key(false, S.confirm ~= nil)
+            -- 输入单词时字母按原样返回
+            if S.mode == "input" and not S.settled and raw:match("^[a-z]$") then
+                k = raw
+            end
             local a = "none"
 
             if k ~= "" then
@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n;
+use crate::utils::path_utils;
+
+/// One achievement a game declares in `GAME_META.achievements` or its manifest.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct AchievementSpec {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub hidden: bool,
+    pub target: Option<u64>,
+}
+
+impl AchievementSpec {
+    /// Returns the translated title, defaulting to `game.<id>.achievement.<aid>.title`.
+    pub fn title(&self, game_id: &str) -> String {
+        let key = self
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("game.{game_id}.achievement.{}.title", self.id));
+        i18n::t_or(&key, &self.id)
+    }
+
+    /// Returns the translated description, defaulting to `game.<id>.achievement.<aid>.description`.
+    pub fn description(&self, game_id: &str) -> String {
+        let key = self
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("game.{game_id}.achievement.{}.description", self.id));
+        i18n::t_or(&key, "")
+    }
+
+    /// Progress needed to unlock; achievements without a target unlock at 1.
+    pub fn target(&self) -> u64 {
+        self.target.unwrap_or(1).max(1)
+    }
+}
+
+/// Stored progress of one achievement.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct AchievementState {
+    pub progress: u64,
+    pub unlocked_at: Option<u64>,
+}
+
+/// Progress of all achievements of one game, keyed by achievement id.
+pub type GameAchievements = BTreeMap<String, AchievementState>;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct AchievementsFile {
+    #[serde(default)]
+    games: HashMap<String, GameAchievements>,
+}
+
+/// Loads the achievement progress of all games.
+pub fn load_achievements() -> HashMap<String, GameAchievements> {
+    load_file().map(|file| file.games).unwrap_or_default()
+}
+
+/// Adds progress to an achievement, returning whether this call unlocked it.
+pub fn add_progress(game_id: &str, spec: &AchievementSpec, amount: u64) -> Result<bool> {
+    let mut file = load_file()?;
+    let state = file
+        .games
+        .entry(game_id.to_string())
+        .or_default()
+        .entry(spec.id.clone())
+        .or_default();
+    if state.unlocked_at.is_some() || amount == 0 {
+        return Ok(false);
+    }
+    state.progress = state.progress.saturating_add(amount).min(spec.target());
+    let unlocked = state.progress >= spec.target();
+    if unlocked {
+        state.unlocked_at = Some(now_secs());
+    }
+    save_file(&file)?;
+    Ok(unlocked)
+}
+
+/// Unlocks an achievement at once, returning whether it was locked before.
+pub fn unlock(game_id: &str, spec: &AchievementSpec) -> Result<bool> {
+    add_progress(game_id, spec, spec.target())
+}
+
+/// Counts how many of the declared achievements are unlocked.
+pub fn unlocked_count(specs: &[AchievementSpec], states: Option<&GameAchievements>) -> usize {
+    let Some(states) = states else {
+        return 0;
+    };
+    specs
+        .iter()
+        .filter(|spec| {
+            states
+                .get(&spec.id)
+                .is_some_and(|state| state.unlocked_at.is_some())
+        })
+        .count()
+}
+
+/// Forgets the achievement progress of a game.
+pub fn remove_game(game_id: &str) -> Result<()> {
+    let mut file = load_file()?;
+    let before = file.games.len();
+    file.games.retain(|id, _| !id.eq_ignore_ascii_case(game_id));
+    if file.games.len() != before {
+        save_file(&file)?;
+    }
+    Ok(())
+}
+
+/// Formats an unlock timestamp as a UTC `YYYY-MM-DD` date.
+pub fn format_date(timestamp: u64) -> String {
+    // 公历日期换算,见 Howard Hinnant 的 civil_from_days
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Merges imported achievement progress into the current one.
+///
+/// Keeps the higher progress and the earliest unlock of each achievement.
+/// Returns the merged content and how many achievements it changes.
+pub fn merge_achievements(current: &str, incoming: &str) -> Result<(String, usize)> {
+    let mut merged: AchievementsFile = serde_json::from_str(current).unwrap_or_default();
+    let incoming: AchievementsFile = serde_json::from_str(incoming)?;
+    let mut changes = 0;
+    for (game_id, states) in incoming.games {
+        let game = merged.games.entry(game_id).or_default();
+        for (id, state) in states {
+            let entry = game.entry(id).or_default();
+            let updated = AchievementState {
+                progress: entry.progress.max(state.progress),
+                unlocked_at: match (entry.unlocked_at, state.unlocked_at) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                },
+            };
+            if *entry != updated {
+                *entry = updated;
+                changes += 1;
+            }
+        }
+    }
+    Ok((serde_json::to_string_pretty(&merged)?, changes))
+}
+
+fn load_file() -> Result<AchievementsFile> {
+    let path = achievements_file_path();
+    if !path.exists() {
+        return Ok(AchievementsFile::default());
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+fn save_file(file: &AchievementsFile) -> Result<()> {
+    let path = achievements_file_path();
+    path_utils::ensure_parent_dir(&path)?;
+    fs::write(path, serde_json::to_string_pretty(file)?)?;
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn achievements_file_path() -> PathBuf {
+    match path_utils::achievements_file() {
+        Ok(path) => path,
+        Err(_) => PathBuf::from("achievements.json"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AchievementSpec, AchievementState, GameAchievements, format_date, unlocked_count};
+
+    #[test]
+    fn format_date_works() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_792_368_000), "2026-10-19");
+    }
+
+    #[test]
+    fn unlocked_count_ignores_undeclared_and_locked() {
+        let spec = |id: &str| AchievementSpec {
+            id: id.to_string(),
+            ..AchievementSpec::default()
+        };
+        let specs = vec![spec("a"), spec("b"), spec("c")];
+        let mut states = GameAchievements::new();
+        states.insert(
+            "a".to_string(),
+            AchievementState {
+                progress: 1,
+                unlocked_at: Some(10),
+            },
+        );
+        states.insert(
+            "b".to_string(),
+            AchievementState {
+                progress: 3,
+                unlocked_at: None,
+            },
+        );
+        states.insert(
+            "removed".to_string(),
+            AchievementState {
+                progress: 1,
+                unlocked_at: Some(5),
+            },
+        );
+        assert_eq!(unlocked_count(&specs, Some(&states)), 1);
+        assert_eq!(unlocked_count(&specs, None), 0);
+    }
+}
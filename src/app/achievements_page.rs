@@ -0,0 +1,210 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+
+use crate::app::achievements::{self, AchievementSpec, GameAchievements};
+use crate::app::i18n;
+use crate::app::keymap::Keymap;
+use crate::lua_bridge::script_loader::scan_scripts;
+
+pub struct AchievementsPage {
+    rows: Vec<AchievementRow>,
+    list_state: ListState,
+    scroll: u16,
+    keymap: Keymap,
+}
+
+struct AchievementRow {
+    game_id: String,
+    name: String,
+    specs: Vec<AchievementSpec>,
+    states: GameAchievements,
+}
+
+pub enum AchievementsAction {
+    Back,
+}
+
+impl AchievementsPage {
+    /// Creates the page listing every game that declares achievements.
+    pub fn new() -> Self {
+        let mut states = achievements::load_achievements();
+        let mut rows: Vec<AchievementRow> = scan_scripts()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|game| !game.achievements.is_empty())
+            .map(|game| AchievementRow {
+                name: i18n::t_or(&format!("game.{}.name", game.id), &game.name),
+                states: states.remove(&game.id).unwrap_or_default(),
+                specs: game.achievements,
+                game_id: game.id,
+            })
+            .collect();
+        rows.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut list_state = ListState::default();
+        if !rows.is_empty() {
+            list_state.select(Some(0));
+        }
+        Self {
+            rows,
+            list_state,
+            scroll: 0,
+            keymap: Keymap::menu(),
+        }
+    }
+
+    /// Handles keyboard events and returns high-level actions.
+    pub fn handle_event(&mut self, key: KeyEvent) -> Option<AchievementsAction> {
+        match self.keymap.pick(key, &["up", "down", "close"]) {
+            Some("up") => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                self.list_state.select(Some(selected.saturating_sub(1)));
+                self.scroll = 0;
+            }
+            Some("down") => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                let last = self.rows.len().saturating_sub(1);
+                self.list_state.select(Some((selected + 1).min(last)));
+                self.scroll = 0;
+            }
+            Some(_) => return Some(AchievementsAction::Back),
+            None => match key.code {
+                KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(5),
+                KeyCode::PageDown => self.scroll = self.scroll.saturating_add(5),
+                _ => {}
+            },
+        }
+        None
+    }
+
+    /// Renders the achievements page.
+    pub fn render(&mut self, frame: &mut ratatui::Frame<'_>, area: Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(area);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                self.keymap.hint("achievements.hint"),
+                Style::default().fg(Color::DarkGray),
+            )),
+            rows[1],
+        );
+
+        if self.rows.is_empty() {
+            let empty = Paragraph::new(i18n::t("achievements.empty"))
+                .alignment(Alignment::Center)
+                .block(page_block(i18n::t("achievements.title")));
+            frame.render_widget(empty, rows[0]);
+            return;
+        }
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(rows[0]);
+
+        let items: Vec<ListItem<'_>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let unlocked = achievements::unlocked_count(&row.specs, Some(&row.states));
+                ListItem::new(format!("{} ({unlocked}/{})", row.name, row.specs.len()))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(page_block(i18n::t("achievements.title")))
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::LightBlue));
+        frame.render_stateful_widget(list, columns[0], &mut self.list_state);
+
+        let Some(row) = self
+            .list_state
+            .selected()
+            .and_then(|idx| self.rows.get(idx))
+        else {
+            return;
+        };
+        let lines: Vec<Line<'static>> = row
+            .specs
+            .iter()
+            .flat_map(|spec| achievement_lines(row, spec))
+            .collect();
+        let max_scroll = (lines.len() as u16).saturating_sub(1);
+        self.scroll = self.scroll.min(max_scroll);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0))
+                .block(page_block(row.name.clone())),
+            columns[1],
+        );
+    }
+
+    /// Returns the minimum terminal size needed for stable layout.
+    pub fn minimum_size(&self) -> (u16, u16) {
+        (70, 20)
+    }
+}
+
+fn page_block(title: String) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_set(symbols::border::DOUBLE)
+        .border_style(Style::default().fg(Color::White))
+        .title(format!(" {title} "))
+}
+
+fn achievement_lines(row: &AchievementRow, spec: &AchievementSpec) -> Vec<Line<'static>> {
+    let state = row.states.get(&spec.id).cloned().unwrap_or_default();
+    let (title, status, style) = match state.unlocked_at {
+        Some(at) => (
+            format!("✔ {}", spec.title(&row.game_id)),
+            i18n::t_args(
+                "achievements.unlocked_on",
+                &[("date", achievements::format_date(at).into())],
+            ),
+            Style::default()
+                .fg(Color::LightGreen)
+                .add_modifier(Modifier::BOLD),
+        ),
+        None if spec.hidden => (
+            "? ???".to_string(),
+            String::new(),
+            Style::default().fg(Color::DarkGray),
+        ),
+        None => (
+            format!("○ {}", spec.title(&row.game_id)),
+            if spec.target() > 1 {
+                format!("{}/{}", state.progress, spec.target())
+            } else {
+                String::new()
+            },
+            Style::default().fg(Color::White),
+        ),
+    };
+
+    let mut heading = vec![Span::styled(title, style)];
+    if !status.is_empty() {
+        heading.push(Span::styled(
+            format!("  {status}"),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+    let description = if spec.hidden && state.unlocked_at.is_none() {
+        i18n::t("achievements.hidden_description")
+    } else {
+        spec.description(&row.game_id)
+    };
+    let mut lines = vec![Line::from(heading)];
+    if !description.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {description}"),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+    lines
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n;
+use crate::utils::path_utils;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct GameActivity {
+    pub favorite: bool,
+    pub launch_count: u64,
+    pub last_played: Option<u64>,
+    pub play_sec: u64,
+    pub completions: u64,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct ActivityFile {
+    #[serde(default)]
+    games: HashMap<String, GameActivity>,
+}
+
+/// Loads favorites, launch counts and last-launch timestamps of all games.
+pub fn load_activity() -> HashMap<String, GameActivity> {
+    load_file().map(|file| file.games).unwrap_or_default()
+}
+
+/// Counts one launch of a game and stamps it with the current time.
+pub fn record_launch(game_id: &str) -> Result<()> {
+    update(game_id, |entry| {
+        entry.launch_count += 1;
+        entry.last_played = Some(now_secs());
+    })
+}
+
+/// Adds the host-measured play time of one session, counting it as a completion if finished.
+pub fn record_session(game_id: &str, duration_sec: u64, finished: bool) -> Result<()> {
+    update(game_id, |entry| {
+        entry.play_sec = entry.play_sec.saturating_add(duration_sec);
+        entry.completions += u64::from(finished);
+    })
+}
+
+/// Flips the favorite flag of a game and returns the new value.
+pub fn toggle_favorite(game_id: &str) -> Result<bool> {
+    let mut favorite = false;
+    update(game_id, |entry| {
+        entry.favorite = !entry.favorite;
+        favorite = entry.favorite;
+    })?;
+    Ok(favorite)
+}
+
+/// Forgets everything recorded for a game.
+pub fn remove_game(game_id: &str) -> Result<()> {
+    let mut file = load_file()?;
+    if file.games.remove(game_id).is_some() {
+        save_file(&file)?;
+    }
+    Ok(())
+}
+
+/// Describes how long ago a timestamp was, in whole days.
+pub fn format_last_played(timestamp: u64) -> String {
+    match now_secs().saturating_sub(timestamp) / 86_400 {
+        0 => i18n::t("activity.ago.today"),
+        1 => i18n::t("activity.ago.yesterday"),
+        days => i18n::t_args("activity.ago.days", &[("count", days.into())]),
+    }
+}
+
+/// Merges imported favorites and launch records into the current ones.
+///
+/// Returns the merged content and how many games it changes.
+pub fn merge_activity(current: &str, incoming: &str) -> Result<(String, usize)> {
+    let mut merged: ActivityFile = serde_json::from_str(current).unwrap_or_default();
+    let incoming: ActivityFile = serde_json::from_str(incoming)?;
+    let mut changes = 0;
+    for (game_id, activity) in incoming.games {
+        let entry = merged.games.entry(game_id).or_default();
+        let updated = GameActivity {
+            favorite: entry.favorite || activity.favorite,
+            launch_count: entry.launch_count.max(activity.launch_count),
+            last_played: entry.last_played.max(activity.last_played),
+            play_sec: entry.play_sec.max(activity.play_sec),
+            completions: entry.completions.max(activity.completions),
+        };
+        if *entry != updated {
+            *entry = updated;
+            changes += 1;
+        }
+    }
+    Ok((serde_json::to_string_pretty(&merged)?, changes))
+}
+
+fn update(game_id: &str, apply: impl FnOnce(&mut GameActivity)) -> Result<()> {
+    let mut file = load_file()?;
+    apply(file.games.entry(game_id.to_string()).or_default());
+    save_file(&file)
+}
+
+fn load_file() -> Result<ActivityFile> {
+    let path = activity_file_path();
+    if !path.exists() {
+        return Ok(ActivityFile::default());
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+fn save_file(file: &ActivityFile) -> Result<()> {
+    let path = activity_file_path();
+    path_utils::ensure_parent_dir(&path)?;
+    fs::write(path, serde_json::to_string_pretty(file)?)?;
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn activity_file_path() -> PathBuf {
+    match path_utils::activity_file() {
+        Ok(path) => path,
+        Err(_) => PathBuf::from("activity.json"),
+    }
+}
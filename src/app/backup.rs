@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::app::stats::{self, RecordBetter};
+use crate::app::{achievements, activity, game_library, history, i18n, leaderboard};
+use crate::lua_bridge::script_loader::{GameMeta, scan_scripts};
+use crate::utils::path_utils;
+
+/// Marks a file as a backup written by `tui-game export`.
+pub const BACKUP_FORMAT: &str = "tui-game-backup";
+/// Layout version of backup files; backups from newer versions are refused.
+pub const BACKUP_VERSION: u32 = 1;
+
+const LEADERBOARD_FILE: &str = "leaderboards.json";
+const LATEST_SAVE_KEY: &str = "__latest_save_game";
+const KEY_OWNERS: &str = "__key_owners";
+
+/// Every data file of a profile bundled into one JSON document.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Backup {
+    pub format: String,
+    pub version: u32,
+    pub app_version: String,
+    pub created_at: u64,
+    pub profile: String,
+    /// Data file name -> file content.
+    pub files: BTreeMap<String, String>,
+}
+
+/// How imported data is combined with local data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ImportMode {
+    /// Keeps the best of each record and the union of history.
+    Merge,
+    /// Overwrites the profile's files with the ones in the backup.
+    ///
+    /// The leaderboards are shared by all profiles and are merged instead.
+    Replace,
+}
+
+/// One data file an import would write.
+pub struct FileImport {
+    pub name: String,
+    pub changes: usize,
+    mode: ImportMode,
+    path: PathBuf,
+    content: String,
+}
+
+impl FileImport {
+    /// Describes what importing this file changes, for the preview.
+    pub fn summary(&self) -> String {
+        let stem = Path::new(&self.name)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let label = i18n::t_or(&format!("backup.file.{stem}"), &self.name);
+        match self.mode {
+            ImportMode::Merge => i18n::t_args(
+                "backup.preview.merge",
+                &[
+                    ("file", label.into()),
+                    ("changes", (self.changes as u64).into()),
+                ],
+            ),
+            ImportMode::Replace => {
+                i18n::t_args("backup.preview.replace", &[("file", label.into())])
+            }
+        }
+    }
+}
+
+/// Collects the data files of the active profile and the shared leaderboards.
+pub fn create_backup() -> Result<Backup> {
+    let mut files = BTreeMap::new();
+    for (name, path) in data_files()? {
+        if let Ok(content) = fs::read_to_string(&path) {
+            files.insert(name, content);
+        }
+    }
+    Ok(Backup {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: now_secs(),
+        profile: path_utils::active_profile(),
+        files,
+    })
+}
+
+/// Writes a backup to `path` and returns how many data files it holds.
+pub fn export_to(path: &Path) -> Result<usize> {
+    let backup = create_backup()?;
+    path_utils::ensure_parent_dir(path)?;
+    fs::write(path, serde_json::to_string_pretty(&backup)?)?;
+    Ok(backup.files.len())
+}
+
+/// Reads a backup file and checks that this version can import it.
+pub fn read_backup(path: &Path) -> Result<Backup> {
+    let content = fs::read_to_string(path)?;
+    let backup: Backup = serde_json::from_str(&content)
+        .ok()
+        .filter(|backup: &Backup| backup.format == BACKUP_FORMAT)
+        .ok_or_else(|| anyhow!(i18n::t("backup.error.not_backup")))?;
+    if backup.version > BACKUP_VERSION {
+        return Err(anyhow!(i18n::t_args(
+            "backup.error.version",
+            &[("version", u64::from(backup.version).into())]
+        )));
+    }
+    Ok(backup)
+}
+
+/// Works out which files an import would write, without writing anything.
+///
+/// Files the import would leave unchanged are left out.
+pub fn plan_import(backup: &Backup, mode: ImportMode) -> Result<Vec<FileImport>> {
+    let games = scan_scripts().unwrap_or_default();
+    let mut plan = Vec::new();
+    for (name, path) in data_files()? {
+        let Some(incoming) = backup.files.get(&name) else {
+            continue;
+        };
+        let current = fs::read_to_string(&path).unwrap_or_default();
+        // 排行榜是所有档案共用的,覆盖模式下也只合并,不能替换掉别的档案的成绩
+        let mode = if name == LEADERBOARD_FILE {
+            ImportMode::Merge
+        } else {
+            mode
+        };
+        let (content, changes) = match mode {
+            ImportMode::Replace => (incoming.clone(), usize::from(*incoming != current)),
+            ImportMode::Merge => merge_file(&name, &current, incoming, &games)?,
+        };
+        if changes > 0 {
+            plan.push(FileImport {
+                name,
+                changes,
+                mode,
+                path,
+                content,
+            });
+        }
+    }
+    Ok(plan)
+}
+
+/// Writes the planned files and reloads the language preference.
+pub fn apply_import(plan: &[FileImport]) -> Result<()> {
+    for file in plan {
+        path_utils::ensure_parent_dir(&file.path)?;
+        fs::write(&file.path, &file.content)?;
+    }
+    i18n::init("us-en")
+}
+
+/// Suggested file name for a new backup, dated today.
+pub fn default_backup_name() -> String {
+    format!(
+        "tui-game-backup-{}.json",
+        achievements::format_date(now_secs())
+    )
+}
+
+/// Runs `tui-game export <file>` and `tui-game import <file> [--replace] [--yes]`.
+pub fn run_cli(command: &str, args: &[String]) -> Result<()> {
+    let target = args.iter().find(|arg| !arg.starts_with("--"));
+    match command {
+        "export" => {
+            let path = target.ok_or_else(|| anyhow!("usage: tui-game export <file>"))?;
+            let files = export_to(Path::new(path))?;
+            println!(
+                "{}",
+                i18n::t_args(
+                    "backup.exported",
+                    &[
+                        ("path", path.as_str().into()),
+                        ("files", (files as u64).into())
+                    ]
+                )
+            );
+            Ok(())
+        }
+        "import" => {
+            let path = target
+                .ok_or_else(|| anyhow!("usage: tui-game import <file> [--replace] [--yes]"))?;
+            let mode = if args.iter().any(|arg| arg == "--replace") {
+                ImportMode::Replace
+            } else {
+                ImportMode::Merge
+            };
+            let backup = read_backup(Path::new(path))?;
+            let plan = plan_import(&backup, mode)?;
+            println!("{}", preview_header(&backup));
+            if plan.is_empty() {
+                println!("{}", i18n::t("backup.preview.nothing"));
+                return Ok(());
+            }
+            for file in &plan {
+                println!("  {}", file.summary());
+            }
+            let confirmed = args.iter().any(|arg| arg == "--yes")
+                || game_library::ask_yes_no(&i18n::t("backup.confirm.import"))?;
+            if confirmed {
+                apply_import(&plan)?;
+                println!("{}", i18n::t("backup.imported"));
+            }
+            Ok(())
+        }
+        _ => Err(anyhow!("unknown command: {command}")),
+    }
+}
+
+/// Describes where a backup comes from.
+pub fn preview_header(backup: &Backup) -> String {
+    i18n::t_args(
+        "backup.preview.header",
+        &[
+            ("profile", backup.profile.as_str().into()),
+            ("date", achievements::format_date(backup.created_at).into()),
+            ("version", backup.app_version.as_str().into()),
+        ],
+    )
+}
+
+fn data_files() -> Result<Vec<(String, PathBuf)>> {
+    let dir = path_utils::profile_data_dir()?;
+    let mut files: Vec<(String, PathBuf)> = path_utils::PROFILE_FILES
+        .iter()
+        .map(|name| (name.to_string(), dir.join(name)))
+        .collect();
+    files.push((LEADERBOARD_FILE.to_string(), path_utils::leaderboard_file()?));
+    Ok(files)
+}
+
+fn merge_file(
+    name: &str,
+    current: &str,
+    incoming: &str,
+    games: &[GameMeta],
+) -> Result<(String, usize)> {
+    match name {
+        "stats.json" => stats::merge_stats(current, incoming, |game_id, record| {
+            find_game(games, game_id)
+                .and_then(|game| game.records.as_ref())
+                .and_then(|records| records.iter().find(|spec| spec.name == record))
+                .map_or(RecordBetter::Higher, |spec| spec.better)
+        }),
+        "history.jsonl" => history::merge_history(current, incoming),
+        "achievements.json" => achievements::merge_achievements(current, incoming),
+        "activity.json" => activity::merge_activity(current, incoming),
+        "lua_saves.json" => merge_saves(current, incoming),
+        LEADERBOARD_FILE => leaderboard::merge_leaderboards(current, incoming, |game_id| {
+            find_game(games, game_id)
+                .and_then(|game| game.leaderboard.clone())
+                .unwrap_or_default()
+        }),
+        // 语言这类单个设置,本地没有时才用备份里的
+        _ if current.trim().is_empty() => {
+            Ok((incoming.to_string(), usize::from(!incoming.trim().is_empty())))
+        }
+        _ => Ok((current.to_string(), 0)),
+    }
+}
+
+fn find_game<'a>(games: &'a [GameMeta], game_id: &str) -> Option<&'a GameMeta> {
+    games.iter().find(|game| game.id.eq_ignore_ascii_case(game_id))
+}
+
+// 存档按键合并,本地已有的键保留本地的值
+// 本地已经有继续游戏的存档时不导入备份里的存档槽位,否则会同时有两个
+fn merge_saves(current: &str, incoming: &str) -> Result<(String, usize)> {
+    let mut merged: Map<String, Value> = serde_json::from_str(current).unwrap_or_default();
+    let incoming: Map<String, Value> = serde_json::from_str(incoming)?;
+    let has_slot = merged.keys().any(|key| is_slot_key(key));
+    let mut changes = 0;
+    for (key, value) in incoming {
+        // 键的归属表按条目合并,本地有记录的保留本地
+        if key == KEY_OWNERS
+            && let (Some(Value::Object(local)), Value::Object(owners)) =
+                (merged.get_mut(&key), &value)
+        {
+            for (owned, owner) in owners {
+                local.entry(owned.clone()).or_insert_with(|| owner.clone());
+            }
+            continue;
+        }
+        if merged.contains_key(&key) || (has_slot && is_slot_key(&key)) {
+            continue;
+        }
+        merged.insert(key, value);
+        changes += 1;
+    }
+    Ok((serde_json::to_string_pretty(&merged)?, changes))
+}
+
+fn is_slot_key(key: &str) -> bool {
+    key == LATEST_SAVE_KEY || key.starts_with("game:")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_saves;
+
+    #[test]
+    fn merge_saves_keeps_local_values_and_slot() {
+        let current = r#"{"game:tetris":{"lines":3},"__latest_save_game":"tetris","2048_best":100}"#;
+        let incoming = r#"{"game:sudoku":{},"__latest_save_game":"sudoku","2048_best":50,"snake_best":7}"#;
+        let (merged, changes) = merge_saves(current, incoming).unwrap();
+        let merged: serde_json::Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(changes, 1);
+        assert_eq!(merged["__latest_save_game"], "tetris");
+        assert_eq!(merged["2048_best"], 100);
+        assert_eq!(merged["snake_best"], 7);
+        assert!(merged.get("game:sudoku").is_none());
+
+        let (merged, changes) = merge_saves("", incoming).unwrap();
+        assert_eq!(changes, 4);
+        assert!(merged.contains("game:sudoku"));
+    }
+}
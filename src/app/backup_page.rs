@@ -0,0 +1,255 @@
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+
+use crate::app::backup::{self, Backup, FileImport, ImportMode};
+use crate::app::i18n;
+use crate::app::layout::centered_rect;
+
+pub struct BackupPage {
+    path: String,
+    selected: usize,
+    preview: Option<ImportPreview>,
+    message: Option<(String, bool)>,
+}
+
+struct ImportPreview {
+    backup: Backup,
+    mode: ImportMode,
+    plan: Vec<FileImport>,
+}
+
+pub enum BackupAction {
+    Back,
+}
+
+impl BackupPage {
+    /// Creates the page with a dated backup file in the current directory.
+    pub fn new() -> Self {
+        let dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        Self {
+            path: dir
+                .join(backup::default_backup_name())
+                .display()
+                .to_string(),
+            selected: 0,
+            preview: None,
+            message: None,
+        }
+    }
+
+    /// Handles keyboard events and returns high-level actions.
+    pub fn handle_event(&mut self, key: KeyEvent) -> Option<BackupAction> {
+        if self.preview.is_some() {
+            self.handle_preview_key(key.code);
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Esc => return Some(BackupAction::Back),
+            KeyCode::Up | KeyCode::Down => self.selected = 1 - self.selected,
+            KeyCode::Backspace => {
+                self.path.pop();
+            }
+            KeyCode::Char(c) => self.path.push(c),
+            KeyCode::Enter if self.selected == 0 => self.export(),
+            KeyCode::Enter => self.open_preview(),
+            _ => {}
+        }
+        None
+    }
+
+    /// Renders the backup and restore page.
+    pub fn render(&self, frame: &mut ratatui::Frame<'_>, area: Rect) {
+        let (width, height) = self.minimum_size();
+        let area = centered_rect(area, width, height.min(area.height));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols::border::DOUBLE)
+            .border_style(Style::default().fg(Color::White))
+            .title(format!(" {} ", i18n::t("backup.title")));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let lines = match &self.preview {
+            Some(preview) => preview_lines(preview),
+            None => self.form_lines(),
+        };
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), rows[0]);
+
+        if let Some((message, is_error)) = &self.message {
+            let color = if *is_error {
+                Color::LightRed
+            } else {
+                Color::LightGreen
+            };
+            frame.render_widget(
+                Paragraph::new(Span::styled(message.clone(), Style::default().fg(color)))
+                    .wrap(Wrap { trim: false }),
+                rows[1],
+            );
+        }
+
+        let hint = if self.preview.is_some() {
+            i18n::t("backup.hint.preview")
+        } else {
+            i18n::t("backup.hint")
+        };
+        frame.render_widget(
+            Paragraph::new(Span::styled(hint, Style::default().fg(Color::DarkGray))),
+            rows[2],
+        );
+    }
+
+    /// Returns the minimum terminal size needed for stable layout.
+    pub fn minimum_size(&self) -> (u16, u16) {
+        (72, 16)
+    }
+
+    fn handle_preview_key(&mut self, code: KeyCode) {
+        let Some(preview) = &mut self.preview else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => {
+                self.preview = None;
+                self.message = None;
+            }
+            KeyCode::Tab => {
+                let mode = match preview.mode {
+                    ImportMode::Merge => ImportMode::Replace,
+                    ImportMode::Replace => ImportMode::Merge,
+                };
+                match backup::plan_import(&preview.backup, mode) {
+                    Ok(plan) => {
+                        preview.mode = mode;
+                        preview.plan = plan;
+                    }
+                    Err(err) => self.message = Some((err.to_string(), true)),
+                }
+            }
+            KeyCode::Enter => {
+                let result = backup::apply_import(&preview.plan);
+                self.preview = None;
+                self.message = Some(match result {
+                    Ok(()) => (i18n::t("backup.imported"), false),
+                    Err(err) => (err.to_string(), true),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn export(&mut self) {
+        self.message = Some(match backup::export_to(Path::new(self.path.trim())) {
+            Ok(files) => (
+                i18n::t_args(
+                    "backup.exported",
+                    &[
+                        ("path", self.path.trim().into()),
+                        ("files", (files as u64).into()),
+                    ],
+                ),
+                false,
+            ),
+            Err(err) => (err.to_string(), true),
+        });
+    }
+
+    fn open_preview(&mut self) {
+        let result = backup::read_backup(Path::new(self.path.trim())).and_then(|backup| {
+            let plan = backup::plan_import(&backup, ImportMode::Merge)?;
+            Ok(ImportPreview {
+                backup,
+                mode: ImportMode::Merge,
+                plan,
+            })
+        });
+        match result {
+            Ok(preview) => {
+                self.preview = Some(preview);
+                self.message = None;
+            }
+            Err(err) => self.message = Some((err.to_string(), true)),
+        }
+    }
+
+    fn form_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from(Span::styled(
+                i18n::t("backup.file_label"),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                format!("{}_", self.path),
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+        for (idx, key) in ["backup.action.export", "backup.action.import"]
+            .iter()
+            .enumerate()
+        {
+            let style = if idx == self.selected {
+                Style::default()
+                    .fg(Color::LightCyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let marker = if idx == self.selected { "\u{25B6} " } else { "  " };
+            lines.push(Line::from(Span::styled(
+                format!("{marker}{}", i18n::t(key)),
+                style,
+            )));
+        }
+        lines
+    }
+}
+
+fn preview_lines(preview: &ImportPreview) -> Vec<Line<'static>> {
+    let mode = match preview.mode {
+        ImportMode::Merge => i18n::t("backup.mode.merge"),
+        ImportMode::Replace => i18n::t("backup.mode.replace"),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            backup::preview_header(&preview.backup),
+            Style::default().fg(Color::White),
+        )),
+        Line::from(Span::styled(
+            mode,
+            Style::default()
+                .fg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    if preview.plan.is_empty() {
+        lines.push(Line::from(Span::styled(
+            i18n::t("backup.preview.nothing"),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+    for file in &preview.plan {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", file.summary()),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+    lines
+}
@@ -0,0 +1,74 @@
+/// Scores how well `query` fuzzy-matches `text`, or `None` when it does not match.
+///
+/// Matching is case-insensitive and works on characters, so CJK text matches the
+/// same way as English. Every query character must appear in order; contiguous
+/// runs, word starts and whole-substring hits score higher. Whitespace in the
+/// query is ignored.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+
+    let mut score = 0i64;
+    let mut matched = 0;
+    let mut first = None;
+    let mut prev: Option<usize> = None;
+    for (idx, &c) in text.iter().enumerate() {
+        if matched == query.len() {
+            break;
+        }
+        if c != query[matched] {
+            continue;
+        }
+        score += 1;
+        if prev.is_some_and(|p| p + 1 == idx) {
+            score += 5;
+        }
+        if idx == 0 || !text[idx - 1].is_alphanumeric() {
+            score += 3;
+        }
+        first.get_or_insert(idx);
+        prev = Some(idx);
+        matched += 1;
+    }
+    if matched < query.len() {
+        return None;
+    }
+
+    let query: String = query.into_iter().collect();
+    let text: String = text.into_iter().collect();
+    if text.starts_with(&query) {
+        score += 20;
+    } else if text.contains(&query) {
+        score += 10;
+    }
+    let span = prev.unwrap_or(0) + 1 - first.unwrap_or(0);
+    Some(score - (span - query.chars().count()) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn matches_subsequences_and_ranks_prefixes_first() {
+        assert!(fuzzy_score("msw", "Minesweeper").is_some());
+        assert!(fuzzy_score("xyz", "Minesweeper").is_none());
+        assert!(fuzzy_score("", "anything").is_some());
+        assert!(fuzzy_score("sol", "Solitaire") > fuzzy_score("sol", "Console"));
+        assert!(fuzzy_score("MINE", "minesweeper").is_some());
+    }
+
+    #[test]
+    fn matches_cjk_characters() {
+        assert!(fuzzy_score("方块", "俄罗斯方块").is_some());
+        assert!(fuzzy_score("俄方", "俄罗斯方块").is_some());
+        assert!(fuzzy_score("井字", "俄罗斯方块").is_none());
+    }
+}
@@ -0,0 +1,292 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Result, anyhow};
+use mlua::Lua;
+
+use crate::app::{achievements, activity, history, i18n, leaderboard, stats};
+use crate::lua_bridge::api;
+use crate::lua_bridge::script_loader::{
+    GameManifest, GameMeta, GameSource, MANIFEST_FILE, scan_scripts,
+};
+use crate::utils::path_utils;
+
+/// Installs a game package from a directory or a `.zip` archive into the user library.
+///
+/// The manifest and every Lua file are checked before anything is copied. An id that
+/// is already installed is refused unless `force` is set; forcing replaces a user copy
+/// and shadows a bundled one. Returns the installed game id.
+pub fn install(source: &Path, force: bool) -> Result<String> {
+    if !source.exists() {
+        return Err(anyhow!(i18n::t_args(
+            "manage.error.not_found",
+            &[("path", source.display().to_string().into())],
+        )));
+    }
+
+    if source.is_dir() {
+        return install_dir(source, force);
+    }
+
+    let is_zip = source
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if !is_zip {
+        return Err(anyhow!(i18n::t("manage.error.unsupported_source")));
+    }
+
+    let staging = std::env::temp_dir().join(format!("tui-game-install-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)?;
+    let result = extract_zip(source, &staging)
+        .and_then(|_| find_package_root(&staging))
+        .and_then(|root| install_dir(&root, force));
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+/// Removes a game from the user library, optionally deleting its saves and stats.
+///
+/// Bundled games cannot be removed.
+pub fn uninstall(game_id: &str, remove_data: bool) -> Result<()> {
+    let games = scan_scripts()?;
+    let installed_ids: Vec<String> = games.iter().map(|g| g.id.clone()).collect();
+    let game = games
+        .into_iter()
+        .find(|g| g.id.eq_ignore_ascii_case(game_id))
+        .ok_or_else(|| {
+            anyhow!(i18n::t_args(
+                "manage.error.not_installed",
+                &[("game", game_id.into())]
+            ))
+        })?;
+    if game.source != GameSource::User {
+        return Err(anyhow!(i18n::t_args(
+            "manage.error.bundled",
+            &[("game", game.id.as_str().into())]
+        )));
+    }
+
+    remove_game_files(&game)?;
+    if remove_data {
+        api::clear_game_data(&game.id, &installed_ids)?;
+        stats::remove_game_stats(&game.id)?;
+        activity::remove_game(&game.id)?;
+        history::remove_game(&game.id)?;
+        achievements::remove_game(&game.id)?;
+        leaderboard::remove_game(&game.id)?;
+    }
+    Ok(())
+}
+
+/// Runs the `install`, `uninstall` and `list` subcommands.
+pub fn run_cli(command: &str, args: &[String]) -> Result<()> {
+    let force = args.iter().any(|arg| arg == "--force");
+    let target = args.iter().find(|arg| !arg.starts_with("--"));
+
+    match command {
+        "install" => {
+            let path = target
+                .ok_or_else(|| anyhow!("usage: tui-game install <path.zip|dir> [--force]"))?;
+            let id = install(Path::new(path), force)?;
+            println!(
+                "{}",
+                i18n::t_args("manage.installed", &[("game", id.into())])
+            );
+            Ok(())
+        }
+        "uninstall" => {
+            let id = target.ok_or_else(|| {
+                anyhow!("usage: tui-game uninstall <id> [--keep-data|--delete-data]")
+            })?;
+            let remove_data = if args.iter().any(|arg| arg == "--keep-data") {
+                false
+            } else if args.iter().any(|arg| arg == "--delete-data") {
+                true
+            } else {
+                ask_yes_no(&i18n::t_args(
+                    "manage.confirm.delete_data",
+                    &[("game", id.as_str().into())],
+                ))?
+            };
+            uninstall(id, remove_data)?;
+            println!(
+                "{}",
+                i18n::t_args("manage.uninstalled", &[("game", id.as_str().into())])
+            );
+            Ok(())
+        }
+        "list" => {
+            for game in scan_scripts()? {
+                let source = match game.source {
+                    GameSource::Bundled => "bundled",
+                    GameSource::User => "user",
+                };
+                println!(
+                    "{:<24} {:<10} {:<8} {}",
+                    game.id,
+                    game.version.as_deref().unwrap_or("-"),
+                    source,
+                    game_location(&game).display()
+                );
+            }
+            Ok(())
+        }
+        _ => Err(anyhow!("unknown command: {command}")),
+    }
+}
+
+/// Returns the directory or script file that holds a game.
+pub fn game_location(game: &GameMeta) -> &Path {
+    game.package_dir
+        .as_deref()
+        .unwrap_or(game.script_path.as_path())
+}
+
+fn install_dir(package_dir: &Path, force: bool) -> Result<String> {
+    let manifest = GameManifest::load(package_dir)?;
+    let id = manifest.id.trim().to_string();
+    if !id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(anyhow!(i18n::t_args(
+            "manage.error.invalid_id",
+            &[("game", id.into())]
+        )));
+    }
+
+    let entry = manifest.entry_path(package_dir);
+    if !entry.is_file() {
+        return Err(anyhow!("{}: entry script not found", entry.display()));
+    }
+    check_lua_syntax(package_dir)?;
+
+    let target = path_utils::user_games_dir()?.join(&id);
+    let existing = scan_scripts()?
+        .into_iter()
+        .find(|g| g.id.eq_ignore_ascii_case(&id));
+    if (existing.is_some() || target.exists()) && !force {
+        return Err(anyhow!(i18n::t_args(
+            "manage.error.id_clash",
+            &[("game", id.into())]
+        )));
+    }
+    if let Some(game) = existing.filter(|g| g.source == GameSource::User) {
+        remove_game_files(&game)?;
+    }
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+
+    if let Err(err) = path_utils::copy_dir(package_dir, &target) {
+        let _ = fs::remove_dir_all(&target);
+        return Err(err);
+    }
+    Ok(id)
+}
+
+fn check_lua_syntax(dir: &Path) -> Result<()> {
+    let lua = Lua::new();
+    for path in lua_files(dir)? {
+        let source = fs::read_to_string(&path)?;
+        lua.load(source.trim_start_matches('\u{feff}'))
+            .set_name(path.display().to_string())
+            .into_function()
+            .map_err(|err| {
+                anyhow!(i18n::t_args(
+                    "manage.error.syntax",
+                    &[("error", err.to_string().into())]
+                ))
+            })?;
+    }
+    Ok(())
+}
+
+fn lua_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(lua_files(&path)?);
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("lua"))
+        {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn remove_game_files(game: &GameMeta) -> Result<()> {
+    match &game.package_dir {
+        Some(dir) => fs::remove_dir_all(dir)?,
+        None => {
+            fs::remove_file(&game.script_path)?;
+            if let Some(parent) = game.script_path.parent() {
+                let lang_dir = parent.join("lang").join(&game.id);
+                if lang_dir.is_dir() {
+                    fs::remove_dir_all(lang_dir)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// The standard library has no zip support, so archives are unpacked with the
+// system tools: `tar` ships with Windows 10+ and macOS and reads zip files,
+// most Linux systems have `unzip`.
+fn extract_zip(archive: &Path, dest: &Path) -> Result<()> {
+    let unzip = Command::new("unzip")
+        .arg("-q")
+        .arg(archive)
+        .arg("-d")
+        .arg(dest)
+        .status();
+    if let Ok(status) = unzip
+        && status.success()
+    {
+        return Ok(());
+    }
+
+    let tar = Command::new("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dest)
+        .status();
+    match tar {
+        Ok(status) if status.success() => Ok(()),
+        _ => Err(anyhow!(i18n::t("manage.error.extract"))),
+    }
+}
+
+// Archives may hold the package files directly or wrap them in one folder.
+fn find_package_root(dir: &Path) -> Result<PathBuf> {
+    if dir.join(MANIFEST_FILE).is_file() {
+        return Ok(dir.to_path_buf());
+    }
+    let candidates: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.join(MANIFEST_FILE).is_file())
+        .collect();
+    match candidates.as_slice() {
+        [root] => Ok(root.clone()),
+        _ => Err(anyhow!(i18n::t("manage.error.no_manifest"))),
+    }
+}
+
+/// Asks a yes/no question on the terminal; anything but yes counts as no.
+pub fn ask_yes_no(question: &str) -> Result<bool> {
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
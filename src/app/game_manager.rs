@@ -0,0 +1,263 @@
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::symbols;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+
+use crate::app::game_library;
+use crate::app::i18n;
+use crate::app::keymap::Keymap;
+use crate::lua_bridge::script_loader::{GameMeta, GameSource, scan_scripts};
+
+pub struct GameManager {
+    games: Vec<GameMeta>,
+    list_state: ListState,
+    mode: ManagerMode,
+    message: Option<(String, bool)>,
+    keymap: Keymap,
+}
+
+enum ManagerMode {
+    Browse,
+    Install { input: String, force: bool },
+    ConfirmUninstall { game_id: String },
+}
+
+pub enum GameManagerAction {
+    Back,
+}
+
+impl GameManager {
+    /// Creates the manage games page from a fresh library scan.
+    pub fn new() -> Self {
+        let mut manager = Self {
+            games: Vec::new(),
+            list_state: ListState::default(),
+            mode: ManagerMode::Browse,
+            message: None,
+            keymap: Keymap::menu(),
+        };
+        manager.reload();
+        manager
+    }
+
+    /// Handles keyboard events and returns high-level actions.
+    pub fn handle_event(&mut self, key: KeyEvent) -> Option<GameManagerAction> {
+        match &mut self.mode {
+            ManagerMode::Browse => return self.handle_browse_key(key),
+            ManagerMode::Install { input, force } => match key.code {
+                KeyCode::Esc => self.mode = ManagerMode::Browse,
+                KeyCode::Tab => *force = !*force,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Enter => {
+                    let path = input.trim().trim_matches('"').to_string();
+                    let force = *force;
+                    self.mode = ManagerMode::Browse;
+                    self.message = Some(match game_library::install(Path::new(&path), force) {
+                        Ok(id) => (
+                            i18n::t_args("manage.installed", &[("game", id.into())]),
+                            false,
+                        ),
+                        Err(err) => (format!("{err:#}"), true),
+                    });
+                    self.reload();
+                }
+                _ => {}
+            },
+            ManagerMode::ConfirmUninstall { game_id } => {
+                let remove_data = match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => true,
+                    KeyCode::Char('k') | KeyCode::Char('K') => false,
+                    KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                        self.mode = ManagerMode::Browse;
+                        return None;
+                    }
+                    _ => return None,
+                };
+                let game_id = std::mem::take(game_id);
+                self.mode = ManagerMode::Browse;
+                self.message = Some(match game_library::uninstall(&game_id, remove_data) {
+                    Ok(()) => (
+                        i18n::t_args("manage.uninstalled", &[("game", game_id.into())]),
+                        false,
+                    ),
+                    Err(err) => (format!("{err:#}"), true),
+                });
+                self.reload();
+            }
+        }
+        None
+    }
+
+    /// Renders the manage games page.
+    pub fn render(&mut self, frame: &mut ratatui::Frame<'_>, area: Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(3)])
+            .split(area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols::border::DOUBLE)
+            .border_style(Style::default().fg(Color::White))
+            .title(format!(" {} ", i18n::t("manage.title")));
+
+        if self.games.is_empty() {
+            let empty = Paragraph::new(i18n::t("game_selection.empty"))
+                .alignment(Alignment::Center)
+                .block(block);
+            frame.render_widget(empty, rows[0]);
+        } else {
+            let user_tag = i18n::t("game_selection.source.user_tag");
+            let items: Vec<ListItem<'_>> = self
+                .games
+                .iter()
+                .map(|game| {
+                    let style = match game.source {
+                        GameSource::User => Style::default().fg(Color::White),
+                        GameSource::Bundled => Style::default().fg(Color::DarkGray),
+                    };
+                    let mut spans = vec![Span::styled(
+                        format!(
+                            "{} ({})",
+                            i18n::t_or(&format!("game.{}.name", game.id), &game.name),
+                            game.id
+                        ),
+                        style,
+                    )];
+                    if let Some(version) = &game.version {
+                        spans.push(Span::styled(format!(" v{version}"), style));
+                    }
+                    if game.source == GameSource::User {
+                        spans.push(Span::styled(
+                            format!(" [{user_tag}]"),
+                            Style::default().fg(Color::Cyan),
+                        ));
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
+            let list = List::new(items)
+                .block(block)
+                .highlight_style(Style::default().fg(Color::Black).bg(Color::LightBlue));
+            frame.render_stateful_widget(list, rows[0], &mut self.list_state);
+        }
+
+        frame.render_widget(
+            Paragraph::new(self.footer_lines()).wrap(Wrap { trim: false }),
+            rows[1],
+        );
+    }
+
+    /// Returns the minimum terminal size needed for stable layout.
+    pub fn minimum_size(&self) -> (u16, u16) {
+        (60, 12)
+    }
+
+    // 方向和返回按键位设置,安装和卸载固定
+    fn handle_browse_key(&mut self, key: KeyEvent) -> Option<GameManagerAction> {
+        self.message = None;
+        match self.keymap.pick(key, &["up", "down", "close"]) {
+            Some("up") => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                self.list_state.select(Some(selected.saturating_sub(1)));
+                return None;
+            }
+            Some("down") => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                let last = self.games.len().saturating_sub(1);
+                self.list_state.select(Some((selected + 1).min(last)));
+                return None;
+            }
+            Some(_) => return Some(GameManagerAction::Back),
+            None => {}
+        }
+        match key.code {
+            KeyCode::Char('i') | KeyCode::Char('I') => {
+                self.mode = ManagerMode::Install {
+                    input: String::new(),
+                    force: false,
+                };
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                let game = self
+                    .list_state
+                    .selected()
+                    .and_then(|idx| self.games.get(idx))?;
+                if game.source == GameSource::User {
+                    self.mode = ManagerMode::ConfirmUninstall {
+                        game_id: game.id.clone(),
+                    };
+                } else {
+                    let message =
+                        i18n::t_args("manage.error.bundled", &[("game", game.id.as_str().into())]);
+                    self.message = Some((message, true));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn footer_lines(&self) -> Vec<Line<'static>> {
+        let hint_style = Style::default().fg(Color::DarkGray);
+        match &self.mode {
+            ManagerMode::Browse => {
+                let mut lines = Vec::new();
+                if let Some((message, is_error)) = &self.message {
+                    let color = if *is_error { Color::Red } else { Color::Green };
+                    lines.push(Line::from(Span::styled(
+                        message.clone(),
+                        Style::default().fg(color),
+                    )));
+                }
+                lines.push(Line::from(Span::styled(
+                    self.keymap.hint("manage.hint"),
+                    hint_style,
+                )));
+                lines
+            }
+            ManagerMode::Install { input, force } => {
+                let force_label = if *force {
+                    i18n::t("manage.install.force_on")
+                } else {
+                    i18n::t("manage.install.force_off")
+                };
+                vec![
+                    Line::from(vec![
+                        Span::raw(i18n::t("manage.install.prompt")),
+                        Span::styled(format!(" {input}_"), Style::default().fg(Color::LightCyan)),
+                    ]),
+                    Line::from(Span::styled(force_label, hint_style)),
+                    Line::from(Span::styled(i18n::t("manage.install.hint"), hint_style)),
+                ]
+            }
+            ManagerMode::ConfirmUninstall { game_id } => vec![
+                Line::from(Span::styled(
+                    i18n::t_args(
+                        "manage.confirm.uninstall",
+                        &[("game", game_id.as_str().into())],
+                    ),
+                    Style::default().fg(Color::Yellow),
+                )),
+                Line::from(Span::styled(i18n::t("manage.confirm.hint"), hint_style)),
+            ],
+        }
+    }
+
+    fn reload(&mut self) {
+        self.games = scan_scripts().unwrap_or_default();
+        let selected = self.list_state.selected().unwrap_or(0);
+        self.list_state.select(if self.games.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.games.len() - 1))
+        });
+    }
+}
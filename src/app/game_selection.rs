@@ -1,4 +1,6 @@
-﻿use std::collections::HashMap;
+﻿use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
@@ -8,28 +10,44 @@ use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
 use ratatui::{symbols, widgets::Wrap};
 use unicode_width::UnicodeWidthStr;
 
+use crate::app::achievements::{self, GameAchievements};
+use crate::app::activity::{self, GameActivity};
+use crate::app::fuzzy;
+use crate::app::game_library;
 use crate::app::i18n;
-use crate::app::rich_text;
-use crate::app::stats::{
-    self, GameStats, LightsOutBest, MazeEscapeBest, MemoryFlipBest, MinesweeperBest, SolitaireBest, SudokuBest,
-};
-use crate::lua_bridge::script_loader::GameMeta;
+use crate::app::keymap::{self, Keymap};
+use crate::app::leaderboard::{self, GameLeaderboards};
+use crate::app::rich_text::{self, BuiltinCommands, CommandHandler};
+use crate::app::settings_store;
+use crate::app::stats::{self, GameRecords, RecordSpec, format_duration};
+use crate::lua_bridge::compat;
+use crate::lua_bridge::script_loader::{GameMeta, GameSource, ScanReport};
+use crate::lua_bridge::text_commands::TextCommandRuntime;
+use crate::terminal::renderer;
 
 pub struct GameSelection {
+    library: Vec<GameMeta>,
     games: Vec<GameMeta>,
-    stats: HashMap<String, GameStats>,
-    lights_out_best: Option<LightsOutBest>,
-    memory_flip_best: Option<MemoryFlipBest>,
-    minesweeper_best: Option<MinesweeperBest>,
-    maze_escape_best: Option<MazeEscapeBest>,
-    solitaire_best: Option<SolitaireBest>,
-    sudoku_best: Option<SudokuBest>,
-    twenty_four_best_time_sec: Option<u64>,
+    tag_filter: Option<String>,
+    search_query: String,
+    searching: bool,
+    activity: HashMap<String, GameActivity>,
+    sort_mode: SortMode,
+    recent_count: usize,
+    keymap: Keymap,
+    game_keymaps: HashMap<String, Keymap>,
+    recent_ids: Vec<String>,
+    records: HashMap<String, GameRecords>,
+    achievements: HashMap<String, GameAchievements>,
+    leaderboards: HashMap<String, GameLeaderboards>,
+    detail_tab: DetailTab,
     list_state: ListState,
     page_state: PageState,
-    launch_placeholder: bool,
+    notice: Option<String>,
     detail_scroll: usize,
     detail_scroll_available: bool,
+    text_commands: Option<(PathBuf, TextCommandRuntime)>,
+    scan_warnings: Vec<String>,
 }
 
 #[derive(Clone, Copy)]
@@ -39,125 +57,247 @@ struct PageState {
     total_pages: usize,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortMode {
+    Alphabetical,
+    MostPlayed,
+    LastPlayed,
+}
+
+impl SortMode {
+    fn next(self) -> Self {
+        match self {
+            SortMode::Alphabetical => SortMode::MostPlayed,
+            SortMode::MostPlayed => SortMode::LastPlayed,
+            SortMode::LastPlayed => SortMode::Alphabetical,
+        }
+    }
+
+    fn from_setting() -> Self {
+        match settings_store::get_text("default_sort").as_str() {
+            "most_played" => SortMode::MostPlayed,
+            "last_played" => SortMode::LastPlayed,
+            _ => SortMode::Alphabetical,
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            SortMode::Alphabetical => i18n::t("game_selection.sort.alphabetical"),
+            SortMode::MostPlayed => i18n::t("game_selection.sort.most_played"),
+            SortMode::LastPlayed => i18n::t("game_selection.sort.last_played"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DetailTab {
+    Details,
+    Leaderboard,
+}
+
+// 游戏列表响应的菜单动作,同一个键按这个顺序取第一个
+const LIST_ACTIONS: &[&str] = &[
+    "back",
+    "search",
+    "confirm",
+    "up",
+    "down",
+    "page_prev",
+    "page_next",
+    "scroll_up",
+    "scroll_down",
+    "favorite",
+    "sort",
+    "leaderboard",
+];
+
 pub enum GameSelectionAction {
     BackToMenu,
-    LaunchGame(GameMeta),
+    LaunchGame(Box<GameMeta>),
 }
 
 impl GameSelection {
-    /// Creates a game selection state from scanned games and local stats.
-    pub fn new(games: Vec<GameMeta>) -> Self {
-        let stats = stats::load_stats();
-        let lights_out_best = stats::load_lights_out_best();
-        let memory_flip_best = stats::load_memory_flip_best();
-        let minesweeper_best = stats::load_minesweeper_best();
-        let maze_escape_best = stats::load_maze_escape_best();
-        let solitaire_best = stats::load_solitaire_best();
-        let sudoku_best = stats::load_sudoku_best();
-        let twenty_four_best_time_sec = stats::load_twenty_four_best_time();
+    /// Creates a game selection state from a library scan and local stats.
+    pub fn new(report: ScanReport) -> Self {
+        let ScanReport {
+            games,
+            warnings: scan_warnings,
+        } = report;
         let initial_page_size = games.len().max(1);
 
         let mut list_state = ListState::default();
         if !games.is_empty() {
             list_state.select(Some(0));
         }
+        let game_keymaps = games
+            .iter()
+            .filter(|game| !game.actions.is_empty())
+            .map(|game| (game.id.clone(), Keymap::load(&game.id, &game.actions)))
+            .collect();
 
-        Self {
+        let mut selection = Self {
+            library: games.clone(),
             games,
-            stats,
-            lights_out_best,
-            memory_flip_best,
-            minesweeper_best,
-            maze_escape_best,
-            solitaire_best,
-            sudoku_best,
-            twenty_four_best_time_sec,
+            tag_filter: None,
+            search_query: String::new(),
+            searching: false,
+            activity: activity::load_activity(),
+            sort_mode: SortMode::from_setting(),
+            recent_count: settings_store::get_int("recent_games").max(0) as usize,
+            keymap: Keymap::menu(),
+            game_keymaps,
+            recent_ids: Vec::new(),
+            records: stats::load_records(),
+            achievements: achievements::load_achievements(),
+            leaderboards: leaderboard::load_leaderboards(),
+            detail_tab: DetailTab::Details,
             list_state,
             page_state: PageState {
                 current_page: 0,
                 page_size: initial_page_size,
                 total_pages: 1,
             },
-            launch_placeholder: false,
+            notice: None,
             detail_scroll: 0,
             detail_scroll_available: false,
-        }
+            text_commands: None,
+            scan_warnings,
+        };
+        selection.apply_filters();
+        selection
+    }
+
+    /// Shows a message over the list until the next key press.
+    pub fn show_notice(&mut self, message: String) {
+        self.notice = Some(message);
+    }
+
+    /// Returns the menu keymap, read when the page was created.
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
     }
 
     /// Handles keyboard events and returns high-level actions.
     pub fn handle_event(&mut self, key: KeyEvent) -> Option<GameSelectionAction> {
-        if self.launch_placeholder {
-            self.launch_placeholder = false;
+        if self.notice.take().is_some() {
             return None;
         }
 
-        match key.code {
-            KeyCode::Esc => Some(GameSelectionAction::BackToMenu),
-            KeyCode::Char('w') | KeyCode::Char('W') => {
+        if self.searching {
+            match key.code {
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.search_query.clear();
+                    self.apply_filters();
+                    return None;
+                }
+                KeyCode::Backspace => {
+                    self.search_query.pop();
+                    self.apply_filters();
+                    return None;
+                }
+                KeyCode::Char(c) => {
+                    self.search_query.push(c);
+                    self.apply_filters();
+                    return None;
+                }
+                KeyCode::Enter => self.searching = false,
+                _ => {}
+            }
+        }
+
+        match self.keymap.pick(key, LIST_ACTIONS) {
+            Some("back") if !self.search_query.is_empty() => {
+                self.search_query.clear();
+                self.apply_filters();
+                None
+            }
+            Some("back") => Some(GameSelectionAction::BackToMenu),
+            Some("search") => {
+                self.searching = true;
+                None
+            }
+            Some("scroll_up") => {
                 self.scroll_detail_up();
                 None
             }
-            KeyCode::Char('s') | KeyCode::Char('S') => {
+            Some("scroll_down") => {
                 self.scroll_detail_down();
                 None
             }
-            KeyCode::Char('q') | KeyCode::Char('Q') => {
+            Some("page_prev") => {
                 self.prev_page();
                 None
             }
-            KeyCode::Char('e') | KeyCode::Char('E') => {
+            Some("page_next") => {
                 self.next_page();
                 None
             }
-            KeyCode::Up | KeyCode::Char('k') => {
+            Some("up") => {
                 self.select_prev();
                 None
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Some("down") => {
                 self.select_next();
                 None
             }
-            KeyCode::Enter => {
-                if let Some(game) = self.selected_game_cloned() {
-                    if game.id == "2048"
-                        || game.id == "lights_out"
-                        || game.id == "memory_flip"
-                        || game.id == "sliding_puzzle"
-                        || game.id == "solitaire"
-                        || game.id == "color_memory"
-                        || game.id == "minesweeper"
-                        || game.id == "rock_paper_scissors"
-                        || game.id == "blackjack"
-                        || game.id == "maze_escape"
-                        || game.id == "pacman"
-                        || game.id == "snake"
-                        || game.id == "shooter"
-                        || game.id == "sudoku"
-                        || game.id == "tetris"
-                        || game.id == "tic_tac_toe"
-                        || game.id == "twenty_four"
-                        || game.id == "wordle"
-                    {
-                        return Some(GameSelectionAction::LaunchGame(game));
+            Some("favorite") => {
+                self.toggle_favorite();
+                None
+            }
+            Some("sort") => {
+                self.sort_mode = self.sort_mode.next();
+                self.apply_filters();
+                None
+            }
+            Some("leaderboard") => {
+                self.detail_tab = match self.detail_tab {
+                    DetailTab::Details => DetailTab::Leaderboard,
+                    DetailTab::Leaderboard => DetailTab::Details,
+                };
+                self.reset_detail_scroll();
+                None
+            }
+            Some("confirm") => {
+                let game = self.selected_game_cloned()?;
+                match compat::launch_block(&game) {
+                    None => Some(GameSelectionAction::LaunchGame(Box::new(game))),
+                    Some(block) => {
+                        self.notice = Some(block.message());
+                        None
                     }
-                    self.launch_placeholder = true;
                 }
-                None
             }
-            _ => None,
+            _ => match key.code {
+                KeyCode::Tab => {
+                    self.cycle_tag_filter(true);
+                    None
+                }
+                KeyCode::BackTab => {
+                    self.cycle_tag_filter(false);
+                    None
+                }
+                _ => None,
+            },
         }
     }
 
     /// Renders the game selection UI.
     pub fn render(&mut self, frame: &mut ratatui::Frame<'_>, area: Rect) {
-        if self.launch_placeholder {
-            self.render_launch_placeholder(frame, area);
+        if let Some(message) = &self.notice {
+            render_notice(frame, area, message);
             return;
         }
 
+        let warning_lines = self.scan_warning_lines();
         let root = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(warning_lines.len() as u16),
+                Constraint::Length(1),
+            ])
             .split(area);
 
         let columns = Layout::default()
@@ -167,23 +307,36 @@ impl GameSelection {
 
         self.render_list_panel(frame, columns[0]);
         self.render_detail_panel(frame, columns[1]);
+        frame.render_widget(Paragraph::new(warning_lines), root[1]);
 
-        let mut hints = i18n::t("game_selection.hint.controls");
-        if self.detail_scroll_available {
+        let mut hints = if self.searching {
+            i18n::t("game_selection.hint.search")
+        } else {
+            self.keymap.hint("game_selection.hint.controls")
+        };
+        if self.detail_scroll_available && !self.searching {
+            hints.push_str("  ");
+            hints.push_str(&self.keymap.hint("game_selection.hint.detail_scroll"));
+        }
+        let filter_hint = self.keymap.hint("game_selection.hint.filter");
+        if !self.searching
+            && UnicodeWidthStr::width(hints.as_str()) + 2 + UnicodeWidthStr::width(filter_hint.as_str())
+                <= root[2].width as usize
+        {
             hints.push_str("  ");
-            hints.push_str(&i18n::t("game_selection.hint.detail_scroll"));
+            hints.push_str(&filter_hint);
         }
         let hint_widget = Paragraph::new(hints)
             .style(Style::default().fg(Color::DarkGray))
             .alignment(Alignment::Center);
-        frame.render_widget(hint_widget, root[1]);
+        frame.render_widget(hint_widget, root[2]);
     }
 
     /// Returns the minimum terminal size needed for stable layout.
     pub fn minimum_size(&self) -> (u16, u16) {
         let list_title = i18n::t("game_selection.panel.games");
         let detail_title = i18n::t("game_selection.panel.details");
-        let hint = i18n::t("game_selection.hint.controls");
+        let hint = self.keymap.hint("game_selection.hint.controls");
         let list_title_w = UnicodeWidthStr::width(list_title.as_str());
         let detail_title_w = UnicodeWidthStr::width(detail_title.as_str());
         let hint_w = UnicodeWidthStr::width(hint.as_str());
@@ -202,39 +355,100 @@ impl GameSelection {
         (min_w, min_h)
     }
 
+    fn scan_warning_lines(&self) -> Vec<Line<'static>> {
+        const MAX_LINES: usize = 3;
+        if self.scan_warnings.len() <= MAX_LINES {
+            return self.scan_warnings.iter().cloned().map(warning_line).collect();
+        }
+
+        let mut lines: Vec<Line<'static>> = self.scan_warnings[..MAX_LINES - 1]
+            .iter()
+            .cloned()
+            .map(warning_line)
+            .collect();
+        let rest = (self.scan_warnings.len() - (MAX_LINES - 1)) as u64;
+        lines.push(warning_line(i18n::t_args(
+            "game_selection.warning.more",
+            &[("count", rest.into())],
+        )));
+        lines
+    }
+
     fn render_list_panel(&mut self, frame: &mut ratatui::Frame<'_>, area: Rect) {
         let block = Block::default()
             .borders(Borders::ALL)
             .border_set(symbols::border::DOUBLE)
             .border_style(Style::default().fg(Color::White))
-            .title(format!(" {} ", i18n::t("game_selection.panel.games")));
+            .title(format!(
+                " {} · {} ",
+                i18n::t("game_selection.panel.games"),
+                self.sort_mode.label()
+            ));
 
         let inner = block.inner(area);
         frame.render_widget(block, area);
 
-        let rows = Layout::default()
+        let tag_counts = self.tag_counts();
+        let filter_h = if tag_counts.is_empty() { 0 } else { 1 };
+        let show_search = self.searching || !self.search_query.is_empty();
+        let sections = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .constraints([
+                Constraint::Length(filter_h),
+                Constraint::Length(u16::from(show_search)),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
             .split(inner);
+        let rows = [sections[2], sections[3]];
+        if !tag_counts.is_empty() {
+            let bar = self.filter_bar_line(&tag_counts, sections[0].width as usize);
+            frame.render_widget(Paragraph::new(bar), sections[0]);
+        }
+        if show_search {
+            frame.render_widget(Paragraph::new(self.search_line()), sections[1]);
+        }
 
         self.sync_paging(rows[0].height as usize);
 
-        let page_games: Vec<String> = self
+        let page_games: Vec<(String, Option<String>, GameSource)> = self
             .current_page_games()
             .iter()
-            .map(|g| self.localized_game_name(g))
+            .map(|g| {
+                let reason = compat::launch_block(g).map(|block| block.message());
+                let name = format!("{}{}", self.list_marker(g), self.localized_game_name(g));
+                (name, reason, g.source)
+            })
             .collect();
         if page_games.is_empty() {
-            let p = Paragraph::new(i18n::t("game_selection.empty"))
+            let empty_key = if self.search_query.is_empty() {
+                "game_selection.empty"
+            } else {
+                "game_selection.search.no_match"
+            };
+            let p = Paragraph::new(i18n::t(empty_key))
                 .alignment(Alignment::Center)
                 .style(Style::default().fg(Color::White));
             frame.render_widget(p, rows[0]);
             return;
         }
 
+        let user_tag = format!(" [{}]", i18n::t("game_selection.source.user_tag"));
         let items: Vec<ListItem<'_>> = page_games
             .iter()
-            .map(|name| ListItem::new(Line::from(name.clone())))
+            .map(|(name, reason, source)| {
+                let mut spans = match reason {
+                    None => vec![Span::raw(name.clone())],
+                    Some(reason) => vec![
+                        Span::styled(name.clone(), Style::default().fg(Color::DarkGray)),
+                        Span::styled(format!(" ({reason})"), Style::default().fg(Color::DarkGray)),
+                    ],
+                };
+                if *source == GameSource::User {
+                    spans.push(Span::styled(user_tag.clone(), Style::default().fg(Color::Cyan)));
+                }
+                ListItem::new(Line::from(spans))
+            })
             .collect();
 
         let list = List::new(items)
@@ -245,7 +459,7 @@ impl GameSelection {
         let has_prev = self.page_state.current_page > 0;
         let has_next = self.page_state.current_page + 1 < self.page_state.total_pages;
         let left = if has_prev {
-            i18n::t("game_selection.pager.prev")
+            self.keymap.hint("game_selection.pager.prev")
         } else {
             String::new()
         };
@@ -255,7 +469,7 @@ impl GameSelection {
             self.page_state.total_pages
         );
         let right = if has_next {
-            i18n::t("game_selection.pager.next")
+            self.keymap.hint("game_selection.pager.next")
         } else {
             String::new()
         };
@@ -288,15 +502,40 @@ impl GameSelection {
     }
 
     fn render_detail_panel(&mut self, frame: &mut ratatui::Frame<'_>, area: Rect) {
+        let tab_style = |tab: DetailTab| {
+            if self.detail_tab == tab {
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            }
+        };
         let block = Block::default()
             .borders(Borders::ALL)
             .border_set(symbols::border::DOUBLE)
             .border_style(Style::default().fg(Color::White))
-            .title(format!(" {} ", i18n::t("game_selection.panel.details")));
+            .title(Line::from(vec![
+                Span::styled(
+                    format!(" {} ", i18n::t("game_selection.panel.details")),
+                    tab_style(DetailTab::Details),
+                ),
+                Span::styled("│", Style::default().fg(Color::White)),
+                Span::styled(
+                    format!(" {} ", i18n::t("game_selection.panel.leaderboard")),
+                    tab_style(DetailTab::Leaderboard),
+                ),
+            ]));
 
         let inner = block.inner(area);
         frame.render_widget(block, area);
 
+        if let Some(script_path) = self.selected_game().map(|g| g.script_path.clone()) {
+            self.ensure_text_commands(&script_path);
+        }
+        let handler: &dyn CommandHandler = match &self.text_commands {
+            Some((_, runtime)) => runtime,
+            None => &BuiltinCommands,
+        };
+
         let Some(game) = self.selected_game() else {
             let p = Paragraph::new(i18n::t("game_selection.empty"))
                 .alignment(Alignment::Center)
@@ -307,7 +546,18 @@ impl GameSelection {
             return;
         };
 
-        let s = self.stats.get(&game.id).copied().unwrap_or_default();
+        if self.detail_tab == DetailTab::Leaderboard {
+            let lines = self.leaderboard_lines(game);
+            let max_scroll = lines.len().saturating_sub(inner.height as usize);
+            self.detail_scroll = self.detail_scroll.min(max_scroll);
+            self.detail_scroll_available = max_scroll > 0;
+            let paragraph = Paragraph::new(lines)
+                .style(Style::default().fg(Color::White))
+                .scroll((self.detail_scroll as u16, 0));
+            frame.render_widget(paragraph, inner);
+            return;
+        }
+
         let sep_len = inner.width as usize;
         let separator = "─".repeat(sep_len.max(1));
         let name = self.localized_game_name(game);
@@ -318,197 +568,14 @@ impl GameSelection {
             name,
             Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
         ))];
+        top_lines.extend(package_meta_lines(game));
+        top_lines.extend(self.activity_lines(game));
+        top_lines.extend(game.warnings.iter().map(|key| warning_line(i18n::t(key))));
 
         top_lines.push(Line::from(separator.clone()));
         let stat_lines_start = top_lines.len();
-        if game.id == "lights_out" {
-            if let Some(best) = self.lights_out_best {
-                top_lines.push(Line::from(format!(
-                    "{} {}x{}",
-                    i18n::t("game.lights_out.best_size"),
-                    best.max_size,
-                    best.max_size
-                )));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.lights_out.best_steps"),
-                    best.min_steps
-                )));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.lights_out.best_time"),
-                    stats::format_duration(best.min_time_sec)
-                )));
-            } else {
-                top_lines.push(Line::from(i18n::t("game.lights_out.best_none")));
-            }
-        } else if game.id == "memory_flip" {
-            if let Some(best) = self.memory_flip_best {
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.memory_flip.best_difficulty"),
-                    best.difficulty
-                )));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.memory_flip.best_steps"),
-                    best.min_steps
-                )));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.memory_flip.best_time"),
-                    stats::format_duration(best.min_time_sec)
-                )));
-            } else {
-                top_lines.push(Line::from(i18n::t("game.memory_flip.best_none")));
-            }
-        } else if game.id == "minesweeper" {
-            if let Some(best) = self.minesweeper_best {
-                let fmt = |v: Option<u64>| -> String {
-                    match v {
-                        Some(sec) => stats::format_duration(sec),
-                        None => "-".to_string(),
-                    }
-                };
-                top_lines.push(Line::from(i18n::t("game.minesweeper.best_title")));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.minesweeper.best_d1"),
-                    fmt(best.d1_min_time_sec)
-                )));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.minesweeper.best_d2"),
-                    fmt(best.d2_min_time_sec)
-                )));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.minesweeper.best_d3"),
-                    fmt(best.d3_min_time_sec)
-                )));
-            } else {
-                top_lines.push(Line::from(i18n::t("game.minesweeper.best_none")));
-            }
-        } else if game.id == "maze_escape" {
-            if let Some(best) = self.maze_escape_best {
-                let size = if best.max_cols > 0 && best.max_rows > 0 {
-                    format!("{}x{}", best.max_cols, best.max_rows)
-                } else if best.max_area > 0 {
-                    best.max_area.to_string()
-                } else {
-                    "-".to_string()
-                };
-                let fastest = best
-                    .min_time_sec
-                    .map(stats::format_duration)
-                    .unwrap_or_else(|| "-".to_string());
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.maze_escape.best_max_size"),
-                    size
-                )));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.maze_escape.best_max_mode"),
-                    best.max_mode
-                )));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.maze_escape.best_fastest"),
-                    fastest
-                )));
-            } else {
-                top_lines.push(Line::from(i18n::t("game.maze_escape.best_none")));
-            }
-        } else if game.id == "solitaire" {
-            let fmt = |v: Option<u64>| -> String {
-                v.map(stats::format_duration)
-                    .unwrap_or_else(|| "--:--:--".to_string())
-            };
-            let best = self.solitaire_best.unwrap_or_default();
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game.solitaire.best.freecell"),
-                fmt(best.freecell_min_time_sec)
-            )));
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game.solitaire.best.klondike"),
-                fmt(best.klondike_min_time_sec)
-            )));
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game.solitaire.best.spider"),
-                fmt(best.spider_min_time_sec)
-            )));
-        } else if game.id == "sudoku" {
-            if let Some(best) = self.sudoku_best {
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.sudoku.best_difficulty"),
-                    i18n::t(&format!("game.sudoku.difficulty.{}", best.difficulty))
-                )));
-                top_lines.push(Line::from(format!(
-                    "{} {}",
-                    i18n::t("game.sudoku.best_time"),
-                    stats::format_duration(best.min_time_sec)
-                )));
-            } else {
-                top_lines.push(Line::from(i18n::t("game.sudoku.best_none")));
-            }
-        } else if game.id == "twenty_four" {
-            let best = self
-                .twenty_four_best_time_sec
-                .map(stats::format_duration)
-                .unwrap_or_else(|| i18n::t("game.twenty_four.none"));
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game.twenty_four.best_time"),
-                best
-            )));
-        } else if game.id == "tic_tac_toe" {
-        } else if game.id == "pacman" {
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game_selection.label.high_score"),
-                s.high_score
-            )));
-        } else if game.id == "wordle" {
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game.wordle.best_streak"),
-                s.high_score
-            )));
-        } else if game.id == "rock_paper_scissors" {
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game.rock_paper_scissors.best_streak"),
-                s.high_score
-            )));
-        } else if game.id == "blackjack" {
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game_selection.label.high_net_profit"),
-                s.high_score
-            )));
-        } else if game.id == "tetris" {
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game_selection.label.high_score"),
-                s.high_score
-            )));
-        } else {
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game_selection.label.high_score"),
-                s.high_score
-            )));
-            top_lines.push(Line::from(format!(
-                "{} {}",
-                i18n::t("game_selection.label.longest_play"),
-                stats::format_duration(s.max_duration_sec)
-            )));
-        }
+        top_lines.extend(self.record_lines(game));
+        top_lines.extend(self.achievement_line(game));
         if top_lines.len() > stat_lines_start {
             top_lines.push(Line::from(separator.clone()));
         }
@@ -518,6 +585,7 @@ impl GameSelection {
             &description,
             inner.width.saturating_sub(1) as usize,
             Style::default().fg(Color::White),
+            handler,
         );
         top_lines.extend(rich_lines);
 
@@ -557,6 +625,7 @@ impl GameSelection {
             &details,
             detail_rows[2].width.saturating_sub(2) as usize,
             Style::default().fg(Color::White),
+            handler,
         );
 
         let viewport_h = detail_rows[2].height as usize;
@@ -587,24 +656,24 @@ impl GameSelection {
             let scroll_x = detail_rows[2].x + detail_rows[2].width - 1;
             let can_up = self.detail_scroll > 0;
             let can_down = self.detail_scroll < max_scroll;
+            let up_key = self.scroll_key_label("scroll_up");
+            let down_key = self.scroll_key_label("scroll_down");
 
             frame.render_widget(
                 Paragraph::new(if can_up { "↑" } else { " " }).style(Style::default().fg(Color::White)),
                 Rect::new(scroll_x, detail_rows[2].y, 1, 1),
             );
             frame.render_widget(
-                Paragraph::new(if can_up { "W" } else { " " }).style(Style::default().fg(Color::White)),
+                Paragraph::new(if can_up { up_key.as_str() } else { " " }).style(Style::default().fg(Color::White)),
                 Rect::new(scroll_x, detail_rows[2].y.saturating_add(1), 1, 1),
             );
 
             if detail_rows[2].height > 4 {
                 let track_start = detail_rows[2].y.saturating_add(2);
                 let track_len = detail_rows[2].height.saturating_sub(4);
-                let pos = if max_scroll == 0 {
-                    0
-                } else {
-                    ((self.detail_scroll * (track_len as usize - 1)) / max_scroll) as u16
-                };
+                let pos = (self.detail_scroll * (track_len as usize - 1))
+                    .checked_div(max_scroll)
+                    .unwrap_or(0) as u16;
                 frame.render_widget(
                     Paragraph::new("█").style(Style::default().fg(Color::White)),
                     Rect::new(scroll_x, track_start.saturating_add(pos), 1, 1),
@@ -613,7 +682,7 @@ impl GameSelection {
 
             let d_y = detail_rows[2].y + detail_rows[2].height.saturating_sub(2);
             frame.render_widget(
-                Paragraph::new(if can_down { "S" } else { " " }).style(Style::default().fg(Color::White)),
+                Paragraph::new(if can_down { down_key.as_str() } else { " " }).style(Style::default().fg(Color::White)),
                 Rect::new(scroll_x, d_y, 1, 1),
             );
             frame.render_widget(
@@ -624,28 +693,312 @@ impl GameSelection {
     }
 
 
-    fn render_launch_placeholder(&self, frame: &mut ratatui::Frame<'_>, area: Rect) {
-        let width = 32u16.min(area.width.saturating_sub(2));
-        let height = 5u16.min(area.height.saturating_sub(2));
-        let x = area.x + area.width.saturating_sub(width) / 2;
-        let y = area.y + area.height.saturating_sub(height) / 2;
-        let rect = Rect::new(x, y, width.max(1), height.max(1));
+    fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for tag in self.library.iter().flat_map(|g| g.tags.iter()) {
+            match counts.iter_mut().find(|(name, _)| name == tag) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((tag.clone(), 1)),
+            }
+        }
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    fn cycle_tag_filter(&mut self, forward: bool) {
+        let mut options: Vec<Option<String>> = vec![None];
+        options.extend(self.tag_counts().into_iter().map(|(tag, _)| Some(tag)));
+        let current = options.iter().position(|tag| *tag == self.tag_filter).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % options.len()
+        } else {
+            (current + options.len() - 1) % options.len()
+        };
+        self.tag_filter = options.swap_remove(next);
+        self.apply_filters();
+    }
 
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .border_set(symbols::border::DOUBLE)
-            .border_style(Style::default().fg(Color::White));
-        let inner = block.inner(rect);
-        frame.render_widget(block, rect);
-
-        let msg = Paragraph::new(format!(
-            "{}\n{}",
-            i18n::t("game_selection.placeholder.title"),
-            i18n::t("game_selection.placeholder.back")
-        ))
-            .style(Style::default().fg(Color::White))
-            .alignment(Alignment::Center);
-        frame.render_widget(msg, inner);
+    fn apply_filters(&mut self) {
+        let mut matches: Vec<(i64, &GameMeta)> = self
+            .library
+            .iter()
+            .filter(|g| self.tag_filter.as_ref().is_none_or(|tag| g.tags.contains(tag)))
+            .filter_map(|g| self.search_score(g).map(|score| (score, g)))
+            .collect();
+        if !self.search_query.trim().is_empty() {
+            matches.sort_by_key(|(score, _)| Reverse(*score));
+            self.recent_ids.clear();
+            self.games = matches.into_iter().map(|(_, g)| g.clone()).collect();
+        } else {
+            let mut ordered: Vec<&GameMeta> = matches.into_iter().map(|(_, g)| g).collect();
+            self.sort_games(&mut ordered);
+            let (favorites, rest): (Vec<&GameMeta>, Vec<&GameMeta>) =
+                ordered.into_iter().partition(|g| self.game_activity(g).favorite);
+
+            let mut recent: Vec<&GameMeta> = Vec::new();
+            if self.sort_mode != SortMode::LastPlayed {
+                recent = rest.iter().copied().filter(|g| self.game_activity(g).last_played.is_some()).collect();
+                recent.sort_by_key(|g| Reverse(self.game_activity(g).last_played));
+                recent.truncate(self.recent_count);
+            }
+            let recent_ids: Vec<String> = recent.iter().map(|g| g.id.clone()).collect();
+
+            let games: Vec<GameMeta> = favorites
+                .into_iter()
+                .chain(recent)
+                .chain(rest.into_iter().filter(|g| !recent_ids.contains(&g.id)))
+                .cloned()
+                .collect();
+            self.games = games;
+            self.recent_ids = recent_ids;
+        }
+        self.page_state.current_page = 0;
+        self.list_state.select(if self.games.is_empty() { None } else { Some(0) });
+        self.reset_detail_scroll();
+    }
+
+    fn sort_games(&self, games: &mut [&GameMeta]) {
+        let name_key = |g: &GameMeta| self.localized_game_name(g).to_lowercase();
+        games.sort_by_cached_key(|g| name_key(g));
+        match self.sort_mode {
+            SortMode::Alphabetical => {}
+            SortMode::MostPlayed => games.sort_by_key(|g| Reverse(self.game_activity(g).launch_count)),
+            SortMode::LastPlayed => games.sort_by_key(|g| Reverse(self.game_activity(g).last_played)),
+        }
+    }
+
+    fn game_activity(&self, game: &GameMeta) -> GameActivity {
+        self.activity.get(&game.id).cloned().unwrap_or_default()
+    }
+
+    fn list_marker(&self, game: &GameMeta) -> &'static str {
+        if self.game_activity(game).favorite {
+            "★ "
+        } else if self.recent_ids.contains(&game.id) {
+            "◷ "
+        } else {
+            ""
+        }
+    }
+
+    fn activity_lines(&self, game: &GameMeta) -> Vec<Line<'static>> {
+        let activity = self.game_activity(game);
+        let Some(last_played) = activity.last_played else {
+            return Vec::new();
+        };
+        let label_style = Style::default().fg(Color::DarkGray);
+        vec![
+            Line::from(vec![
+                Span::styled(i18n::t("game_selection.label.launches"), label_style),
+                Span::raw(format!(" {}", activity.launch_count)),
+                Span::styled(
+                    format!("  {}", i18n::t("game_selection.label.completions")),
+                    label_style,
+                ),
+                Span::raw(format!(" {}", activity.completions)),
+            ]),
+            Line::from(vec![
+                Span::styled(i18n::t("game_selection.label.play_time"), label_style),
+                Span::raw(format!(" {}", format_duration(activity.play_sec))),
+            ]),
+            Line::from(vec![
+                Span::styled(i18n::t("game_selection.label.last_played"), label_style),
+                Span::raw(format!(" {}", activity::format_last_played(last_played))),
+            ]),
+        ]
+    }
+
+    fn record_lines(&self, game: &GameMeta) -> Vec<Line<'static>> {
+        let specs = game.records.clone().unwrap_or_else(RecordSpec::defaults);
+        let stored = self.records.get(&game.id);
+        let mut lines = Vec::new();
+        for spec in &specs {
+            let label = spec.label(&game.id);
+            let mut values: Vec<(&String, &i64)> = stored
+                .and_then(|records| records.get(&spec.name))
+                .map(|values| values.iter().collect())
+                .unwrap_or_default();
+            if spec.dimension.is_none() || values.is_empty() {
+                let value = values
+                    .first()
+                    .map(|(_, value)| spec.format_value(**value))
+                    .unwrap_or_else(|| "-".to_string());
+                lines.push(Line::from(format!("{label} {value}")));
+                continue;
+            }
+            values.sort_by_key(|(dimension, _)| (dimension.len(), *dimension));
+            lines.push(Line::from(label));
+            for (dimension, value) in values {
+                lines.push(Line::from(format!(
+                    "  {} {}",
+                    spec.dimension_label(&game.id, dimension),
+                    spec.format_value(*value)
+                )));
+            }
+        }
+        lines
+    }
+
+    fn achievement_line(&self, game: &GameMeta) -> Option<Line<'static>> {
+        if game.achievements.is_empty() {
+            return None;
+        }
+        let unlocked =
+            achievements::unlocked_count(&game.achievements, self.achievements.get(&game.id));
+        Some(Line::from(format!(
+            "{} {unlocked}/{}",
+            i18n::t("game_selection.label.achievements"),
+            game.achievements.len()
+        )))
+    }
+
+    fn leaderboard_lines(&self, game: &GameMeta) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from(Span::styled(
+            self.localized_game_name(game),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ))];
+        let mut boards: Vec<_> = self
+            .leaderboards
+            .get(&game.id)
+            .map(|boards| boards.iter().filter(|(_, entries)| !entries.is_empty()).collect())
+            .unwrap_or_default();
+        if boards.is_empty() {
+            lines.push(Line::from(Span::styled(
+                i18n::t("game_selection.leaderboard.empty"),
+                Style::default().fg(Color::Gray),
+            )));
+            return lines;
+        }
+        boards.sort_by_key(|(mode, _)| (mode.len(), *mode));
+
+        let spec = game.leaderboard.clone().unwrap_or_default();
+        let row = |rank: String, name: String, score: String, time: String, date: String| {
+            format!(
+                "{} {} {} {} {}",
+                pad_to_width(&rank, 3),
+                pad_to_width(&name, 16),
+                pad_to_width(&score, 10),
+                pad_to_width(&time, 8),
+                date
+            )
+        };
+        for (mode, entries) in boards {
+            lines.push(Line::from(""));
+            if !mode.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    spec.mode_label(&game.id, mode),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            lines.push(Line::from(Span::styled(
+                row(
+                    "#".to_string(),
+                    i18n::t("game_selection.leaderboard.name"),
+                    i18n::t("game_selection.leaderboard.score"),
+                    i18n::t("game_selection.leaderboard.time"),
+                    i18n::t("game_selection.leaderboard.date"),
+                ),
+                Style::default().fg(Color::Gray),
+            )));
+            for (index, entry) in entries.iter().enumerate() {
+                let mut spans = vec![Span::raw(row(
+                    format!("{}.", index + 1),
+                    entry.name.clone(),
+                    spec.unit.format(entry.score),
+                    format_duration(entry.duration_sec),
+                    achievements::format_date(entry.date),
+                ))];
+                if let Some(seed) = &entry.seed {
+                    spans.push(Span::styled(
+                        format!(" #{seed}"),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        lines
+    }
+
+    fn toggle_favorite(&mut self) {
+        let Some(game_id) = self.selected_game().map(|g| g.id.clone()) else {
+            return;
+        };
+        if let Ok(favorite) = activity::toggle_favorite(&game_id) {
+            self.activity.entry(game_id.clone()).or_default().favorite = favorite;
+        }
+        self.apply_filters();
+        if let Some(index) = self.games.iter().position(|g| g.id == game_id) {
+            let page_size = self.page_state.page_size.max(1);
+            self.page_state.current_page = index / page_size;
+            self.list_state.select(Some(index % page_size));
+        }
+    }
+
+    fn search_score(&self, game: &GameMeta) -> Option<i64> {
+        let query = self.search_query.as_str();
+        let weighted = [
+            (self.localized_game_name(game), 3),
+            (game.name.clone(), 3),
+            (game.id.clone(), 2),
+            (self.localized_game_description(game), 1),
+        ];
+        let tags = game.tags.iter().flat_map(|tag| [(tag_label(tag), 2), (tag.clone(), 2)]);
+        weighted
+            .into_iter()
+            .chain(tags)
+            .filter_map(|(text, weight)| fuzzy::fuzzy_score(query, &text).map(|score| score * weight))
+            .max()
+    }
+
+    fn search_line(&self) -> Line<'static> {
+        let cursor = if self.searching { "_" } else { "" };
+        Line::from(vec![
+            Span::styled("/ ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{}{cursor}", self.search_query), Style::default().fg(Color::LightCyan)),
+            Span::styled(format!("  ({})", self.games.len()), Style::default().fg(Color::DarkGray)),
+        ])
+    }
+
+    fn filter_bar_line(&self, tag_counts: &[(String, usize)], width: usize) -> Line<'static> {
+        let mut segments = vec![(None, format!(" {} {} ", i18n::t("game_selection.filter.all"), self.library.len()))];
+        segments.extend(
+            tag_counts
+                .iter()
+                .map(|(tag, count)| (Some(tag.clone()), format!(" {} {count} ", tag_label(tag)))),
+        );
+        let active = segments.iter().position(|(tag, _)| *tag == self.tag_filter).unwrap_or(0);
+
+        let widths: Vec<usize> = segments.iter().map(|(_, text)| UnicodeWidthStr::width(text.as_str())).collect();
+        let mut start = 0;
+        while start < active && widths[start..=active].iter().sum::<usize>() > width {
+            start += 1;
+        }
+
+        let spans = segments
+            .into_iter()
+            .enumerate()
+            .skip(start)
+            .map(|(idx, (_, text))| {
+                let style = if idx == active {
+                    Style::default().fg(Color::Black).bg(Color::Cyan)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                Span::styled(text, style)
+            })
+            .collect::<Vec<_>>();
+        Line::from(spans)
+    }
+
+    fn ensure_text_commands(&mut self, script_path: &Path) {
+        let dir = script_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        if self.text_commands.as_ref().is_some_and(|(loaded, _)| *loaded == dir) {
+            return;
+        }
+        self.text_commands = TextCommandRuntime::for_script(script_path)
+            .ok()
+            .map(|runtime| (dir, runtime));
     }
 
     fn selected_game(&self) -> Option<&GameMeta> {
@@ -720,6 +1073,22 @@ impl GameSelection {
         }
     }
 
+    // 滚动条只有一格宽,显示动作第一个按键,放不下时留空
+    fn scroll_key_label(&self, action: &str) -> String {
+        let label = self
+            .keymap
+            .keys(action)
+            .first()
+            .map(|key| keymap::key_label(key))
+            .unwrap_or_default();
+        let label = label.trim_start_matches('[').trim_end_matches(']');
+        if label.width() == 1 {
+            label.to_string()
+        } else {
+            " ".to_string()
+        }
+    }
+
     fn localized_game_name(&self, game: &GameMeta) -> String {
         i18n::t_or(&format!("game.{}.name", game.id), &game.name)
     }
@@ -729,7 +1098,24 @@ impl GameSelection {
     }
 
     fn localized_game_details(&self, game: &GameMeta) -> String {
-        i18n::t_or(&format!("game.{}.details", game.id), "")
+        let details = i18n::t_or(&format!("game.{}.details", game.id), "");
+        let Some(keymap) = self.game_keymaps.get(&game.id) else {
+            return details;
+        };
+        // 声明了动作的游戏按当前键位生成操作说明
+        let controls = keymap.controls_text(&game.id, &game.actions);
+        let details = match details.strip_prefix("f%") {
+            Some(text) => text.to_string(),
+            None => details
+                .replace('\\', "\\\\")
+                .replace('{', "\\{")
+                .replace('}', "\\}"),
+        };
+        if details.is_empty() {
+            format!("f%{controls}")
+        } else {
+            format!("f%{details}\n\n{controls}")
+        }
     }
 
     fn selected_global_index(&self) -> Option<usize> {
@@ -766,14 +1152,71 @@ impl GameSelection {
     }
 }
 
+fn package_meta_lines(game: &GameMeta) -> Vec<Line<'static>> {
+    let fields = [
+        ("game_selection.label.version", game.version.clone()),
+        ("game_selection.label.authors", Some(game.authors.join(", "))),
+        ("game_selection.label.license", game.license.clone()),
+        (
+            "game_selection.label.tags",
+            Some(game.tags.iter().map(|tag| tag_label(tag)).collect::<Vec<_>>().join(", ")),
+        ),
+        ("game_selection.label.languages", Some(game.languages.join(", "))),
+        ("game_selection.label.source", Some(source_text(game))),
+    ];
+
+    fields
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let value = value.filter(|v| !v.trim().is_empty())?;
+            Some(Line::from(vec![
+                Span::styled(i18n::t(key), Style::default().fg(Color::DarkGray)),
+                Span::raw(format!(" {value}")),
+            ]))
+        })
+        .collect()
+}
 
+fn tag_label(tag: &str) -> String {
+    i18n::t_or(&format!("tag.{tag}"), tag)
+}
 
+fn source_text(game: &GameMeta) -> String {
+    format!("{} ({})", game.source.label(), game_library::game_location(game).display())
+}
 
+fn warning_line(text: String) -> Line<'static> {
+    Line::from(Span::styled(
+        format!("! {text}"),
+        Style::default().fg(Color::Yellow),
+    ))
+}
 
+fn render_notice(frame: &mut ratatui::Frame<'_>, area: Rect, message: &str) {
+    let back = i18n::t("game_selection.notice.dismiss");
+    let content_w = UnicodeWidthStr::width(message).max(UnicodeWidthStr::width(back.as_str()));
+    let width = (content_w as u16 + 4).max(32).min(area.width.saturating_sub(2));
+    let text_lines = renderer::wrap_text(message, width.saturating_sub(2) as usize).len() + 1;
+    let height = (text_lines as u16 + 2).min(area.height.saturating_sub(2));
+    let x = area.x + area.width.saturating_sub(width) / 2;
+    let y = area.y + area.height.saturating_sub(height) / 2;
+    let rect = Rect::new(x, y, width.max(1), height.max(1));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(symbols::border::DOUBLE)
+        .border_style(Style::default().fg(Color::White));
+    let inner = block.inner(rect);
+    frame.render_widget(block, rect);
+
+    let msg = Paragraph::new(format!("{message}\n{back}"))
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(msg, inner);
+}
 
-
-
-
-
-
-
+fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(UnicodeWidthStr::width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n;
+use crate::utils::path_utils;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionOutcome {
+    Won,
+    Lost,
+    Draw,
+    Finished,
+    #[default]
+    Quit,
+    Error,
+}
+
+impl SessionOutcome {
+    /// Parses an outcome a script passes to `exit_game`; scripts cannot report errors.
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "won" => Some(Self::Won),
+            "lost" => Some(Self::Lost),
+            "draw" => Some(Self::Draw),
+            "finished" => Some(Self::Finished),
+            "quit" => Some(Self::Quit),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the session was played to the end.
+    pub fn is_completed(self) -> bool {
+        !matches!(self, Self::Quit | Self::Error)
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            SessionOutcome::Won => i18n::t("statistics.outcome.won"),
+            SessionOutcome::Lost => i18n::t("statistics.outcome.lost"),
+            SessionOutcome::Draw => i18n::t("statistics.outcome.draw"),
+            SessionOutcome::Finished => i18n::t("statistics.outcome.finished"),
+            SessionOutcome::Quit => i18n::t("statistics.outcome.quit"),
+            SessionOutcome::Error => i18n::t("statistics.outcome.error"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HistoryEntry {
+    pub game_id: String,
+    pub mode: Option<String>,
+    pub started_at: u64,
+    pub duration_sec: u64,
+    pub score: Option<i64>,
+    pub outcome: SessionOutcome,
+    pub launch_mode: String,
+}
+
+/// Summary of all recorded sessions of one game.
+#[derive(Clone, Debug, Default)]
+pub struct GameSummary {
+    pub sessions: usize,
+    pub finished: usize,
+    pub total_sec: u64,
+    pub best_score: Option<i64>,
+    pub worst_score: Option<i64>,
+    pub average_score: Option<f64>,
+    pub last_played: Option<u64>,
+    pub recent_scores: Vec<i64>,
+}
+
+/// Appends one finished session to the history file.
+pub fn append(entry: &HistoryEntry) -> Result<()> {
+    let path = history_file_path();
+    path_utils::ensure_parent_dir(&path)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Loads every recorded session, oldest first.
+///
+/// Lines that cannot be parsed are skipped so one broken write does not hide
+/// the rest of the history.
+pub fn load_history() -> Vec<HistoryEntry> {
+    let Ok(content) = fs::read_to_string(history_file_path()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Groups the history by game and summarizes it, keeping up to `recent` last scores.
+pub fn summarize(entries: &[HistoryEntry], recent: usize) -> HashMap<String, GameSummary> {
+    let mut summaries: HashMap<String, GameSummary> = HashMap::new();
+    let mut score_totals: HashMap<&str, (i64, usize)> = HashMap::new();
+    for entry in entries {
+        let summary = summaries.entry(entry.game_id.clone()).or_default();
+        summary.sessions += 1;
+        if entry.outcome.is_completed() {
+            summary.finished += 1;
+        }
+        summary.total_sec += entry.duration_sec;
+        summary.last_played = summary.last_played.max(Some(entry.started_at));
+        if let Some(score) = entry.score {
+            summary.best_score = Some(summary.best_score.map_or(score, |best| best.max(score)));
+            summary.worst_score = Some(summary.worst_score.map_or(score, |worst| worst.min(score)));
+            summary.recent_scores.push(score);
+            let total = score_totals.entry(&entry.game_id).or_default();
+            total.0 += score;
+            total.1 += 1;
+        }
+    }
+    for (game_id, summary) in summaries.iter_mut() {
+        if let Some((total, count)) = score_totals.get(game_id.as_str()) {
+            summary.average_score = Some(*total as f64 / *count as f64);
+        }
+        let skip = summary.recent_scores.len().saturating_sub(recent);
+        summary.recent_scores.drain(..skip);
+    }
+    summaries
+}
+
+/// Removes every session of one game from the history.
+pub fn remove_game(game_id: &str) -> Result<()> {
+    let path = history_file_path();
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let kept: Vec<&str> = content
+        .lines()
+        .filter(|line| {
+            serde_json::from_str::<HistoryEntry>(line)
+                .map(|entry| !entry.game_id.eq_ignore_ascii_case(game_id))
+                .unwrap_or(true)
+        })
+        .collect();
+    if kept.len() != content.lines().count() {
+        let mut payload = kept.join("\n");
+        if !payload.is_empty() {
+            payload.push('\n');
+        }
+        fs::write(path, payload)?;
+    }
+    Ok(())
+}
+
+/// Merges imported history into the current one, skipping sessions it already has.
+///
+/// Returns the merged content, oldest first, and how many sessions were added.
+pub fn merge_history(current: &str, incoming: &str) -> Result<(String, usize)> {
+    let parse = |content: &str| -> Vec<HistoryEntry> {
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    };
+    let mut entries = parse(current);
+    let mut seen: HashSet<(String, u64, u64)> = entries.iter().map(session_key).collect();
+    let mut added = 0;
+    for entry in parse(incoming) {
+        if seen.insert(session_key(&entry)) {
+            entries.push(entry);
+            added += 1;
+        }
+    }
+    entries.sort_by_key(|entry| entry.started_at);
+
+    let mut payload = String::new();
+    for entry in &entries {
+        payload.push_str(&serde_json::to_string(entry)?);
+        payload.push('\n');
+    }
+    Ok((payload, added))
+}
+
+// 同一个游戏同一时刻开始且时长相同的记录视为同一局
+fn session_key(entry: &HistoryEntry) -> (String, u64, u64) {
+    (
+        entry.game_id.to_ascii_lowercase(),
+        entry.started_at,
+        entry.duration_sec,
+    )
+}
+
+fn history_file_path() -> PathBuf {
+    match path_utils::history_file() {
+        Ok(path) => path,
+        Err(_) => PathBuf::from("history.jsonl"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HistoryEntry, SessionOutcome, summarize};
+
+    #[test]
+    fn summarize_groups_sessions_per_game() {
+        let entry = |game_id: &str, score: Option<i64>, started_at: u64| HistoryEntry {
+            game_id: game_id.to_string(),
+            started_at,
+            duration_sec: 60,
+            score,
+            outcome: SessionOutcome::Finished,
+            ..HistoryEntry::default()
+        };
+        let entries = vec![
+            entry("tetris", Some(100), 1),
+            entry("snake", None, 2),
+            entry("tetris", Some(300), 3),
+            entry("tetris", Some(200), 4),
+        ];
+
+        let summaries = summarize(&entries, 2);
+        let tetris = &summaries["tetris"];
+        assert_eq!(tetris.sessions, 3);
+        assert_eq!(tetris.total_sec, 180);
+        assert_eq!(tetris.best_score, Some(300));
+        assert_eq!(tetris.worst_score, Some(100));
+        assert_eq!(tetris.average_score, Some(200.0));
+        assert_eq!(tetris.last_played, Some(4));
+        assert_eq!(tetris.recent_scores, vec![300, 200]);
+        assert_eq!(summaries["snake"].average_score, None);
+    }
+}
@@ -7,9 +7,11 @@ use anyhow::Result;
 use once_cell::sync::Lazy;
 use serde_json::Value;
 
+use crate::app::stats;
 use crate::utils::path_utils;
 
 const REQUIRED_KEYS: [&str; 3] = ["language_name", "language", "confirm_language"];
+const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
 
 #[derive(Clone, Debug)]
 pub struct LanguagePack {
@@ -23,6 +25,59 @@ struct I18nState {
     packs: Vec<LanguagePack>,
     fallback: LanguagePack,
     current_code: String,
+    // game id -> language code -> namespaced dict
+    game_packs: HashMap<String, HashMap<String, HashMap<String, String>>>,
+}
+
+impl I18nState {
+    // Game packs win over the global pack of the same language;
+    // English is consulted the same way when the language misses the key.
+    fn lookup(&self, code: &str, key: &str) -> Option<String> {
+        self.lookup_exact(code, key)
+            .or_else(|| self.game_value(&self.fallback.code, key))
+            .or_else(|| self.fallback.dict.get(key).cloned())
+    }
+
+    // Only the given language, without the English fallback.
+    fn lookup_exact(&self, code: &str, key: &str) -> Option<String> {
+        self.game_value(code, key).or_else(|| {
+            self.packs
+                .iter()
+                .find(|pack| pack.code == code)
+                .and_then(|pack| pack.dict.get(key))
+                .cloned()
+        })
+    }
+
+    fn game_value(&self, code: &str, key: &str) -> Option<String> {
+        let dicts = self.game_packs.get(game_id_of_key(key)?)?;
+        dicts.get(code)?.get(key).cloned()
+    }
+
+    fn merged_dict(&self, code: &str) -> HashMap<String, String> {
+        let mut dict = self
+            .packs
+            .iter()
+            .find(|pack| pack.code == code)
+            .map(|pack| pack.dict.clone())
+            .unwrap_or_default();
+        for dicts in self.game_packs.values() {
+            if let Some(game_dict) = dicts.get(code) {
+                dict.extend(game_dict.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        dict
+    }
+
+    // Plural variants are only taken from the current language, so a
+    // translation that defines just the plain key is not overridden by the
+    // English `.other` text.
+    fn plural_template(&self, key: &str, count: f64) -> Option<String> {
+        let code = &self.current_code;
+        let category = plural_category(code, count);
+        self.lookup_exact(code, &format!("{key}.{category}"))
+            .or_else(|| self.lookup_exact(code, &format!("{key}.other")))
+    }
 }
 
 static I18N: Lazy<RwLock<I18nState>> = Lazy::new(|| {
@@ -31,6 +86,7 @@ static I18N: Lazy<RwLock<I18nState>> = Lazy::new(|| {
         packs: vec![fallback.clone()],
         fallback: fallback.clone(),
         current_code: fallback.code.clone(),
+        game_packs: HashMap::new(),
     })
 });
 
@@ -65,10 +121,12 @@ pub fn init(default_code: &str) -> Result<()> {
     };
 
     if let Ok(mut state) = I18N.write() {
+        let game_packs = std::mem::take(&mut state.game_packs);
         *state = I18nState {
             packs,
             fallback,
             current_code,
+            game_packs,
         };
     }
 
@@ -105,50 +163,244 @@ pub fn set_language(code: &str) -> bool {
 
 /// Looks up a key in current language with built-in English fallback.
 pub fn t(key: &str) -> String {
-    if let Ok(state) = I18N.read() {
-        if let Some(current_pack) = state
-            .packs
-            .iter()
-            .find(|pack| pack.code == state.current_code)
-        {
-            if let Some(value) = current_pack.dict.get(key) {
-                return value.clone();
-            }
-        }
+    lookup(key).unwrap_or_else(|| format!("[missing-i18n-key:{}]", key))
+}
+
+/// Looks up a key in a specific language code with English fallback.
+pub fn t_for_code(code: &str, key: &str) -> String {
+    I18N.read()
+        .ok()
+        .and_then(|state| state.lookup(code, key))
+        .unwrap_or_else(|| format!("[missing-i18n-key:{}]", key))
+}
 
-        if let Some(value) = state.fallback.dict.get(key) {
-            return value.clone();
+/// Looks up a key in current language and falls back to provided text when missing.
+pub fn t_or(key: &str, fallback: &str) -> String {
+    let value = t(key);
+    if value.starts_with("[missing-i18n-key:") {
+        fallback.to_string()
+    } else {
+        value
+    }
+}
+
+/// Looks up a key and fills named placeholders such as `{game}`, `{score:number}`
+/// or `{time:duration}`.
+///
+/// When a numeric `count` argument is given, the plural variant `key.<category>`
+/// of the current language is preferred, then its `key.other`, then `key` itself
+/// with the usual English fallback.
+/// Braces that do not name a supplied argument are kept, so rich text markup
+/// like `{tc:red}` passes through untouched.
+pub fn t_args<K: AsRef<str>>(key: &str, args: &[(K, I18nArg)]) -> String {
+    let count = args.iter().find_map(|(name, value)| match value {
+        I18nArg::Number(n) if name.as_ref() == "count" => Some(*n),
+        _ => None,
+    });
+
+    let template = count
+        .and_then(|n| I18N.read().ok()?.plural_template(key, n))
+        .unwrap_or_else(|| t(key));
+
+    fill_placeholders(&template, args)
+}
+
+/// A value substituted into a translated template by [`t_args`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum I18nArg {
+    Text(String),
+    Number(f64),
+}
+
+impl From<&str> for I18nArg {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for I18nArg {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<i64> for I18nArg {
+    fn from(value: i64) -> Self {
+        Self::Number(value as f64)
+    }
+}
+
+impl From<u64> for I18nArg {
+    fn from(value: u64) -> Self {
+        Self::Number(value as f64)
+    }
+}
+
+impl From<f64> for I18nArg {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+/// Returns the CLDR plural category (`one`, `few`, `many`, `other`) of a number
+/// for the given language code.
+fn plural_category(code: &str, n: f64) -> &'static str {
+    let integer = n.fract() == 0.0;
+    let i = n.abs().trunc() as u64;
+    let segments: Vec<String> = code
+        .split(['-', '_'])
+        .map(|s| s.to_ascii_lowercase())
+        .collect();
+    let has = |langs: &[&str]| segments.iter().any(|s| langs.contains(&s.as_str()));
+
+    if has(&["zh", "ja", "ko", "vi", "th", "id", "ms"]) {
+        "other"
+    } else if has(&["fr", "pt"]) {
+        if i < 2 { "one" } else { "other" }
+    } else if has(&["ru", "uk", "be", "pl"]) {
+        let one = if has(&["pl"]) {
+            i == 1
+        } else {
+            i % 10 == 1 && i % 100 != 11
+        };
+        if !integer {
+            "other"
+        } else if one {
+            "one"
+        } else if (2..=4).contains(&(i % 10)) && !(12..=14).contains(&(i % 100)) {
+            "few"
+        } else {
+            "many"
         }
+    } else if integer && i == 1 {
+        "one"
+    } else {
+        "other"
     }
+}
 
-    format!("[missing-i18n-key:{}]", key)
+fn lookup(key: &str) -> Option<String> {
+    let state = I18N.read().ok()?;
+    state.lookup(&state.current_code, key)
 }
 
-/// Looks up a key in a specific language code with English fallback.
-pub fn t_for_code(code: &str, key: &str) -> String {
-    if let Ok(state) = I18N.read() {
-        if let Some(pack) = state.packs.iter().find(|pack| pack.code == code) {
-            if let Some(value) = pack.dict.get(key) {
-                return value.clone();
+// `game.<id>.xxx` -> `<id>`
+fn game_id_of_key(key: &str) -> Option<&str> {
+    key.strip_prefix("game.")?.split('.').next()
+}
+
+fn fill_placeholders<K: AsRef<str>>(template: &str, args: &[(K, I18nArg)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let inner = &after[..end];
+        let (name, format) = match inner.split_once(':') {
+            Some((name, format)) => (name.trim(), Some(format.trim())),
+            None => (inner.trim(), None),
+        };
+        match args.iter().find(|(key, _)| key.as_ref() == name) {
+            Some((_, value)) => out.push_str(&format_arg(value, format)),
+            None => {
+                out.push('{');
+                out.push_str(inner);
+                out.push('}');
             }
         }
+        rest = &after[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn format_arg(value: &I18nArg, format: Option<&str>) -> String {
+    match (value, format) {
+        (I18nArg::Number(n), Some("number")) => group_digits(*n),
+        (I18nArg::Number(n), Some("duration")) => stats::format_duration(n.max(0.0) as u64),
+        (I18nArg::Number(n), _) if n.fract() == 0.0 => format!("{}", *n as i64),
+        (I18nArg::Number(n), _) => n.to_string(),
+        (I18nArg::Text(text), _) => text.clone(),
+    }
+}
+
+fn group_digits(n: f64) -> String {
+    let raw = if n.fract() == 0.0 {
+        format!("{}", n.abs() as u64)
+    } else {
+        format!("{:.2}", n.abs())
+    };
+    let (int_part, frac_part) = match raw.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (raw.as_str(), None),
+    };
 
-        if let Some(value) = state.fallback.dict.get(key) {
-            return value.clone();
+    let mut grouped = String::new();
+    for (idx, ch) in int_part.chars().enumerate() {
+        if idx > 0 && (int_part.len() - idx) % 3 == 0 {
+            grouped.push(',');
         }
+        grouped.push(ch);
+    }
+    if let Some(frac_part) = frac_part {
+        grouped.push('.');
+        grouped.push_str(frac_part);
+    }
+    if n < 0.0 {
+        grouped.insert(0, '-');
     }
+    grouped
+}
 
-    format!("[missing-i18n-key:{}]", key)
+/// Returns true when `dict` translates `key`.
+///
+/// A plural variant such as `key.one` counts as translated when the language
+/// only defines the plain `key`, because [`t_args`] falls back to it.
+pub fn dict_covers(dict: &HashMap<String, String>, key: &str) -> bool {
+    dict.contains_key(key)
+        || key.rsplit_once('.').is_some_and(|(base, category)| {
+            PLURAL_CATEGORIES.contains(&category) && dict.contains_key(base)
+        })
 }
 
-/// Looks up a key in current language and falls back to provided text when missing.
-pub fn t_or(key: &str, fallback: &str) -> String {
-    let value = t(key);
-    if value.starts_with("[missing-i18n-key:") {
-        fallback.to_string()
-    } else {
-        value
+/// Returns the share of English keys (0-100) the given pack translates.
+pub fn translation_completeness(pack: &LanguagePack) -> u8 {
+    let (translated, total) = completeness(&pack.code);
+    if total == 0 {
+        return 100;
     }
+    (translated * 100 / total) as u8
+}
+
+/// Counts the English keys a language translates: (translated, total).
+///
+/// Keys of registered game packs are included on both sides, so the
+/// language grid and `i18n-check` report the same numbers.
+pub fn completeness(code: &str) -> (usize, usize) {
+    let Ok(state) = I18N.read() else {
+        return (0, 0);
+    };
+    let reference = state.merged_dict(&state.fallback.code);
+    let dict = state.merged_dict(code);
+    let translated = reference
+        .keys()
+        .filter(|key| dict_covers(&dict, key))
+        .count();
+    (translated, reference.len())
+}
+
+/// Returns every key of a language, global pack and registered game packs together.
+pub fn merged_dict(code: &str) -> HashMap<String, String> {
+    I18N.read()
+        .map(|state| state.merged_dict(code))
+        .unwrap_or_default()
 }
 
 fn load_language_packs() -> Result<Vec<LanguagePack>> {
@@ -181,6 +433,72 @@ fn load_language_packs() -> Result<Vec<LanguagePack>> {
     Ok(packs)
 }
 
+/// Loads the `<code>.json` files a game ships in its own language directories.
+///
+/// Keys are namespaced to the game, so `"name"` becomes `game.<id>.name`;
+/// keys already carrying the `game.<id>.` prefix are kept as they are.
+/// Lookups fall back to the global packs for anything the game does not translate.
+pub fn register_game_language_packs(game_id: &str, lang_dirs: &[PathBuf]) {
+    let prefix = format!("game.{game_id}.");
+    let mut dicts: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+    for path in game_lang_files(lang_dirs) {
+        let Some(code) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some(object) = fs::read_to_string(&path).ok().and_then(|content| {
+            serde_json::from_str::<Value>(content.trim_start_matches('\u{feff}'))
+                .ok()?
+                .as_object()
+                .cloned()
+        }) else {
+            continue;
+        };
+
+        let dict = dicts.entry(code.to_ascii_lowercase()).or_default();
+        for (key, value) in object {
+            let Some(text) = value.as_str() else {
+                continue;
+            };
+            let key = if key.starts_with(&prefix) {
+                key
+            } else {
+                format!("{prefix}{key}")
+            };
+            dict.entry(key).or_insert_with(|| text.to_string());
+        }
+    }
+
+    if let Ok(mut state) = I18N.write() {
+        if dicts.is_empty() {
+            state.game_packs.remove(game_id);
+        } else {
+            state.game_packs.insert(game_id.to_string(), dicts);
+        }
+    }
+}
+
+fn game_lang_files(lang_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in lang_dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut entries: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.eq_ignore_ascii_case("json"))
+                    .unwrap_or(false)
+            })
+            .collect();
+        entries.sort();
+        files.extend(entries);
+    }
+    files
+}
+
 fn parse_language_pack(path: &Path) -> Result<Option<LanguagePack>> {
     let code = match path.file_stem().and_then(|s| s.to_str()) {
         Some(stem) => stem.to_ascii_lowercase(),
@@ -307,7 +625,7 @@ fn minimal_builtin_english_pack() -> LanguagePack {
     dict.insert("language".to_string(), "Language".to_string());
     dict.insert(
         "confirm_language".to_string(),
-        "[Enter] Confirm language [ESC] / [Q] Return to main menu".to_string(),
+        "{confirm} Confirm language {close} Return to main menu".to_string(),
     );
 
     LanguagePack {
@@ -316,3 +634,96 @@ fn minimal_builtin_english_pack() -> LanguagePack {
         dict,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
+    use super::{
+        I18nArg, I18nState, LanguagePack, fill_placeholders, plural_category,
+        register_game_language_packs, t_for_code,
+    };
+
+    fn pack(code: &str, entries: &[(&str, &str)]) -> LanguagePack {
+        LanguagePack {
+            code: code.to_string(),
+            name: code.to_string(),
+            dict: entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn fill_placeholders_formats_named_args() {
+        let args = [
+            ("score", I18nArg::from(12345_i64)),
+            ("time", I18nArg::from(3661_i64)),
+            ("game", I18nArg::from("Snake")),
+        ];
+        assert_eq!(
+            fill_placeholders("{game}: {score:number} in {time:duration} {tc:red}", &args),
+            "Snake: 12,345 in 01:01:01 {tc:red}"
+        );
+    }
+
+    #[test]
+    fn plural_category_follows_language() {
+        assert_eq!(plural_category("us-en", 1.0), "one");
+        assert_eq!(plural_category("us-en", 5.0), "other");
+        assert_eq!(plural_category("zh-cn", 1.0), "other");
+        assert_eq!(plural_category("ru", 22.0), "few");
+        assert_eq!(plural_category("ru", 11.0), "many");
+    }
+
+    #[test]
+    fn plural_variants_come_from_the_current_language_only() {
+        let english = pack(
+            "us-en",
+            &[
+                ("moves", "{count} moves"),
+                ("moves.one", "{count} move"),
+                ("moves.other", "{count} moves"),
+            ],
+        );
+        let mut state = I18nState {
+            packs: vec![english.clone(), pack("zh-cn", &[("moves", "{count} 步")])],
+            fallback: english,
+            current_code: "us-en".to_string(),
+            game_packs: HashMap::new(),
+        };
+        assert_eq!(state.plural_template("moves", 1.0).unwrap(), "{count} move");
+        assert_eq!(
+            state.plural_template("moves", 5.0).unwrap(),
+            "{count} moves"
+        );
+
+        // zh-cn only has the plain key; the English `.other` must not win over it.
+        state.current_code = "zh-cn".to_string();
+        assert_eq!(state.plural_template("moves", 5.0), None);
+        assert_eq!(state.lookup("zh-cn", "moves").unwrap(), "{count} 步");
+        // Keys missing from zh-cn entirely still fall back to English.
+        assert_eq!(state.lookup("zh-cn", "moves.one").unwrap(), "{count} move");
+    }
+
+    #[test]
+    fn game_language_packs_are_namespaced() {
+        let dir = std::env::temp_dir()
+            .join("tui-game-i18n-test")
+            .join("pack_demo");
+        fs::create_dir_all(dir.join("lang")).unwrap();
+        fs::write(
+            dir.join("lang").join("us-en.json"),
+            r#"{"name": "Pack Demo", "game.pack_demo.details": "Details"}"#,
+        )
+        .unwrap();
+
+        register_game_language_packs("pack_demo", &[dir.join("lang")]);
+        assert_eq!(t_for_code("us-en", "game.pack_demo.name"), "Pack Demo");
+        assert_eq!(t_for_code("zh-cn", "game.pack_demo.details"), "Details");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::app::i18n;
+use crate::app::rich_text::{self, BuiltinCommands, CommandHandler};
+use crate::lua_bridge::script_loader::{GameMeta, scan_scripts};
+use crate::lua_bridge::text_commands::TextCommandRuntime;
+
+const REFERENCE_CODE: &str = "us-en";
+
+/// Runs the `i18n-check` command and prints a report.
+///
+/// Returns `false` when any language misses keys, has rich-text errors or when
+/// a script asks for a key that no pack defines.
+pub fn run() -> Result<bool> {
+    // Scanning registers the language packs shipped with games.
+    let games = scan_scripts()?;
+
+    let dicts: BTreeMap<String, HashMap<String, String>> = i18n::available_languages()
+        .into_iter()
+        .map(|pack| {
+            let dict = i18n::merged_dict(&pack.code);
+            (pack.code, dict)
+        })
+        .collect();
+    let Some(reference) = dicts.get(REFERENCE_CODE) else {
+        println!("reference language pack '{REFERENCE_CODE}' not found");
+        return Ok(false);
+    };
+
+    let mut clean = true;
+    let mut runtimes = HashMap::new();
+
+    for (code, dict) in &dicts {
+        println!("[{code}]");
+
+        let missing = sorted_keys(reference.keys().filter(|key| !i18n::dict_covers(dict, key)));
+        let extra = sorted_keys(dict.keys().filter(|key| !reference.contains_key(*key)));
+        let untranslated = sorted_keys(dict.iter().filter_map(|(key, value)| {
+            let same = code != REFERENCE_CODE
+                && reference.get(key) == Some(value)
+                && value.chars().any(char::is_alphabetic);
+            same.then_some(key)
+        }));
+
+        let mut rich_errors = Vec::new();
+        for key in sorted_keys(dict.keys()) {
+            let value = &dict[&key];
+            if !rich_text::is_rich_text(value) {
+                continue;
+            }
+            let runtime = game_for_key(&games, &key).and_then(|game| {
+                runtimes
+                    .entry(game.id.clone())
+                    .or_insert_with(|| TextCommandRuntime::for_script(&game.script_path).ok())
+                    .as_ref()
+            });
+            let handler: &dyn CommandHandler = match runtime {
+                Some(runtime) => runtime,
+                None => &BuiltinCommands,
+            };
+            let errors = rich_text::rich_text_errors(value, handler);
+            if !errors.is_empty() {
+                rich_errors.push(format!("{key}: {}", errors.join(", ")));
+            }
+        }
+
+        let (translated, total) = i18n::completeness(code);
+        let percent = translated * 100 / total.max(1);
+        println!("  completeness: {percent}% ({translated}/{total})");
+        print_section("missing", &missing);
+        print_section("extra", &extra);
+        print_section("untranslated", &untranslated);
+        print_section("rich text errors", &rich_errors);
+
+        clean &= missing.is_empty() && rich_errors.is_empty();
+    }
+
+    println!("[scripts]");
+    for game in &games {
+        let unknown: Vec<String> = script_translation_keys(&game.script_path)
+            .into_iter()
+            .filter(|key| !dicts.values().any(|dict| dict.contains_key(key)))
+            .collect();
+        if !unknown.is_empty() {
+            clean = false;
+            print_section(&game.script_path.display().to_string(), &unknown);
+        }
+    }
+
+    println!("{}", if clean { "OK" } else { "FAILED" });
+    Ok(clean)
+}
+
+fn sorted_keys<'a>(keys: impl Iterator<Item = &'a String>) -> Vec<String> {
+    keys.cloned().collect::<BTreeSet<_>>().into_iter().collect()
+}
+
+fn print_section(title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    println!("  {title} ({}):", items.len());
+    for item in items {
+        println!("    {item}");
+    }
+}
+
+fn game_for_key<'a>(games: &'a [GameMeta], key: &str) -> Option<&'a GameMeta> {
+    let id = key.strip_prefix("game.")?.split('.').next()?;
+    games.iter().find(|game| game.id == id)
+}
+
+/// Collects literal keys passed to `translate(...)` or `tr(...)` in a Lua source file.
+fn script_translation_keys(path: &Path) -> BTreeSet<String> {
+    let Ok(source) = fs::read_to_string(path) else {
+        return BTreeSet::new();
+    };
+
+    let mut keys = BTreeSet::new();
+    for name in ["translate(", "tr("] {
+        for (pos, _) in source.match_indices(name) {
+            let is_call = source[..pos]
+                .chars()
+                .next_back()
+                .is_none_or(|ch| !(ch.is_alphanumeric() || ch == '_' || ch == '.' || ch == ':'));
+            if !is_call {
+                continue;
+            }
+
+            let rest = source[pos + name.len()..].trim_start();
+            let Some(quote) = rest.chars().next().filter(|ch| *ch == '"' || *ch == '\'') else {
+                continue;
+            };
+            if let Some(end) = rest[1..].find(quote) {
+                let key = &rest[1..1 + end];
+                // `tr("prefix." .. name)` builds the key at runtime
+                let concatenated = rest[2 + end..].trim_start().starts_with("..");
+                if !key.is_empty() && !concatenated && !key.contains(char::is_whitespace) {
+                    keys.insert(key.to_string());
+                }
+            }
+        }
+    }
+    keys
+}
@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use serde::{Deserialize, Serialize};
+
+use crate::app::i18n;
+use crate::utils::path_utils;
+
+/// Keymap scope of the launcher's own menus; games use their id as scope.
+pub const MENU_SCOPE: &str = "menu";
+
+// 启动器菜单的动作和默认按键,同一个键可以在不同界面上对应不同动作
+const MENU_ACTIONS: &[(&str, &[&str])] = &[
+    ("up", &["up", "k"]),
+    ("down", &["down", "j"]),
+    ("confirm", &["enter"]),
+    ("back", &["esc"]),
+    ("close", &["esc", "q"]),
+    ("yes", &["y", "enter"]),
+    ("no", &["n", "q", "esc"]),
+    ("search", &["/"]),
+    ("scroll_up", &["w"]),
+    ("scroll_down", &["s"]),
+    ("page_prev", &["q"]),
+    ("page_next", &["e"]),
+    ("favorite", &["f"]),
+    ("sort", &["o"]),
+    ("leaderboard", &["l"]),
+    ("profiles", &["p"]),
+];
+
+/// A named action and its default keys, declared in `GAME_META.actions` or by the launcher.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct ActionSpec {
+    pub name: String,
+    pub keys: Vec<String>,
+    pub label: Option<String>,
+}
+
+impl ActionSpec {
+    /// Returns the translated name, defaulting to `game.<id>.action.<name>`.
+    ///
+    /// Menu actions use `keymap.action.<name>`.
+    pub fn label(&self, scope: &str) -> String {
+        let key = self.label.clone().unwrap_or_else(|| {
+            if scope == MENU_SCOPE {
+                format!("keymap.action.{}", self.name)
+            } else {
+                format!("game.{scope}.action.{}", self.name)
+            }
+        });
+        i18n::t_or(&key, &self.name)
+    }
+}
+
+/// Returns the actions of the launcher's own menus.
+pub fn menu_actions() -> Vec<ActionSpec> {
+    MENU_ACTIONS
+        .iter()
+        .map(|(name, keys)| ActionSpec {
+            name: name.to_string(),
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+            label: None,
+        })
+        .collect()
+}
+
+/// Keys bound to each action of one scope, with the player's changes applied.
+#[derive(Clone, Debug, Default)]
+pub struct Keymap {
+    bindings: Vec<(String, Vec<String>)>,
+}
+
+impl Keymap {
+    /// Loads the bindings of `scope`; actions the player never changed keep their default keys.
+    pub fn load(scope: &str, actions: &[ActionSpec]) -> Self {
+        let mut custom = load_file().remove(scope).unwrap_or_default();
+        let bindings = actions
+            .iter()
+            .map(|action| {
+                let keys = custom
+                    .remove(&action.name)
+                    .unwrap_or_else(|| action.keys.clone());
+                let keys = keys.iter().map(|key| normalize_key(key)).collect();
+                (action.name.clone(), keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+
+    /// Loads the bindings of the launcher's own menus.
+    pub fn menu() -> Self {
+        Self::load(MENU_SCOPE, &menu_actions())
+    }
+
+    /// Returns the first action bound to a key name.
+    pub fn action(&self, key: &str) -> Option<&str> {
+        let key = normalize_key(key);
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the first of `actions` that `key` triggers.
+    pub fn pick<'a>(&self, key: KeyEvent, actions: &[&'a str]) -> Option<&'a str> {
+        let key = key_name(key.code);
+        actions
+            .iter()
+            .copied()
+            .find(|action| self.keys(action).contains(&key))
+    }
+
+    /// Returns the keys bound to an action.
+    pub fn keys(&self, action: &str) -> &[String] {
+        self.bindings
+            .iter()
+            .find(|(name, _)| name == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Translates a pressed key back to the first default key of the action it is bound to.
+    ///
+    /// Default keys whose action was rebound give an empty string; other keys are returned as they are.
+    pub fn default_key(&self, key: &str, actions: &[ActionSpec]) -> String {
+        let key = normalize_key(key);
+        let defaults = |action: &ActionSpec| {
+            action
+                .keys
+                .iter()
+                .map(|key| normalize_key(key))
+                .collect::<Vec<_>>()
+        };
+        if let Some(first) = self
+            .action(&key)
+            .and_then(|name| actions.iter().find(|action| action.name == name))
+            .and_then(|action| defaults(action).into_iter().next())
+        {
+            return first;
+        }
+        if actions.iter().any(|action| defaults(action).contains(&key)) {
+            return String::new();
+        }
+        key
+    }
+
+    /// Formats the keys of an action for hints, e.g. `[↑]/[K]`.
+    pub fn describe(&self, action: &str) -> String {
+        self.keys(action)
+            .iter()
+            .map(|key| key_label(key))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Translates a hint, passing the first key of every action as `{<action>}` arguments.
+    pub fn hint(&self, key: &str) -> String {
+        let args: Vec<(&str, i18n::I18nArg)> = self
+            .bindings
+            .iter()
+            .map(|(name, keys)| {
+                let label = keys.first().map(|key| key_label(key)).unwrap_or_default();
+                (name.as_str(), label.into())
+            })
+            .collect();
+        i18n::t_args(key, &args)
+    }
+
+    /// Builds the rich-text controls section of a game's details.
+    pub fn controls_text(&self, scope: &str, actions: &[ActionSpec]) -> String {
+        let mut text = format!("{{tc:yellow}}{}{{tc:clear}}", i18n::t("keymap.controls"));
+        for action in actions {
+            text.push_str(&format!(
+                "\n{{tc:light_cyan}}{}{{tc:clear}} {}",
+                self.describe(&action.name),
+                action.label(scope)
+            ));
+        }
+        text
+    }
+}
+
+/// Returns a key as scripts see it, e.g. `up`, `enter`, `space` or `a`.
+pub fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Delete => "delete".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::BackTab => "tab".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_ascii_lowercase().to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Formats a key name for display, e.g. `[↑]`, `[Enter]` or `[A]`.
+pub fn key_label(key: &str) -> String {
+    let label = match key {
+        "up" => "↑".to_string(),
+        "down" => "↓".to_string(),
+        "left" => "←".to_string(),
+        "right" => "→".to_string(),
+        "esc" => "ESC".to_string(),
+        _ if key.chars().count() == 1 => key.to_uppercase(),
+        _ => {
+            let mut chars = key.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+    };
+    format!("[{label}]")
+}
+
+/// Binds `keys` to an action of `scope`; an empty list restores the default keys.
+pub fn set_keys(scope: &str, action: &str, keys: &[String]) -> Result<()> {
+    let mut file = load_file();
+    let custom = file.entry(scope.to_string()).or_default();
+    if keys.is_empty() {
+        custom.remove(action);
+    } else {
+        custom.insert(action.to_string(), keys.to_vec());
+    }
+    if custom.is_empty() {
+        file.remove(scope);
+    }
+    let path = keymap_file_path();
+    path_utils::ensure_parent_dir(&path)?;
+    fs::write(path, serde_json::to_string_pretty(&file)?)?;
+    Ok(())
+}
+
+fn normalize_key(key: &str) -> String {
+    match key.to_lowercase().as_str() {
+        " " => "space".to_string(),
+        "escape" => "esc".to_string(),
+        "return" => "enter".to_string(),
+        other => other.trim().to_string(),
+    }
+}
+
+// 作用域 -> 动作 -> 按键,只保存玩家改过的动作
+fn load_file() -> BTreeMap<String, BTreeMap<String, Vec<String>>> {
+    fs::read_to_string(keymap_file_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn keymap_file_path() -> PathBuf {
+    match path_utils::keymap_file() {
+        Ok(path) => path,
+        Err(_) => PathBuf::from("keymap.json"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActionSpec, Keymap, key_label};
+
+    #[test]
+    fn keymap_resolves_keys_to_actions_in_declared_order() {
+        let keymap = Keymap {
+            bindings: vec![
+                ("page_prev".to_string(), vec!["q".to_string()]),
+                (
+                    "no".to_string(),
+                    vec!["n".to_string(), "q".to_string(), "esc".to_string()],
+                ),
+            ],
+        };
+        assert_eq!(keymap.action("Q"), Some("page_prev"));
+        assert_eq!(keymap.action("esc"), Some("no"));
+        assert_eq!(keymap.action("x"), None);
+        assert_eq!(keymap.describe("no"), "[N]/[Q]/[ESC]");
+        assert_eq!(key_label("space"), "[Space]");
+
+        let spec = ActionSpec {
+            name: "rotate".to_string(),
+            ..ActionSpec::default()
+        };
+        assert_eq!(spec.label("tetris"), "rotate");
+    }
+
+    #[test]
+    fn rebound_keys_translate_to_the_default_key() {
+        let actions = vec![
+            ActionSpec {
+                name: "rotate".to_string(),
+                keys: vec!["up".to_string(), "w".to_string()],
+                label: None,
+            },
+            ActionSpec {
+                name: "drop".to_string(),
+                keys: vec!["space".to_string()],
+                label: None,
+            },
+        ];
+        let keymap = Keymap {
+            bindings: vec![
+                ("rotate".to_string(), vec!["x".to_string(), "w".to_string()]),
+                ("drop".to_string(), vec!["space".to_string()]),
+            ],
+        };
+        assert_eq!(keymap.default_key("X", &actions), "up");
+        assert_eq!(keymap.default_key("w", &actions), "up");
+        assert_eq!(keymap.default_key("space", &actions), "space");
+        assert_eq!(keymap.default_key("up", &actions), "");
+        assert_eq!(keymap.default_key("5", &actions), "5");
+    }
+}
@@ -0,0 +1,276 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::i18n;
+use crate::app::keymap::{self, ActionSpec, Keymap, MENU_SCOPE};
+use crate::app::layout::centered_rect;
+use crate::lua_bridge::script_loader::scan_scripts;
+
+pub struct KeymapPage {
+    scopes: Vec<KeymapScope>,
+    scope_index: usize,
+    selected: usize,
+    keymap: Keymap,
+    capture: Option<Capture>,
+    message: Option<(String, Color)>,
+}
+
+struct KeymapScope {
+    id: String,
+    title: String,
+    actions: Vec<ActionSpec>,
+}
+
+// 等待玩家按下新键,append为true时加在原有按键后面
+#[derive(Clone, Copy)]
+struct Capture {
+    append: bool,
+}
+
+pub enum KeymapAction {
+    Back,
+}
+
+impl KeymapPage {
+    /// Creates the page with the menu keymap and every game that declares actions.
+    pub fn new() -> Self {
+        let mut scopes = vec![KeymapScope {
+            id: MENU_SCOPE.to_string(),
+            title: i18n::t("keymap.scope.menu"),
+            actions: keymap::menu_actions(),
+        }];
+        let mut games: Vec<KeymapScope> = scan_scripts()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|game| !game.actions.is_empty())
+            .map(|game| KeymapScope {
+                title: i18n::t_or(&format!("game.{}.name", game.id), &game.name),
+                id: game.id,
+                actions: game.actions,
+            })
+            .collect();
+        games.sort_by_key(|scope| scope.title.to_lowercase());
+        scopes.extend(games);
+
+        let keymap = Keymap::load(&scopes[0].id, &scopes[0].actions);
+        Self {
+            scopes,
+            scope_index: 0,
+            selected: 0,
+            keymap,
+            capture: None,
+            message: None,
+        }
+    }
+
+    /// Handles keyboard events and returns high-level actions.
+    pub fn handle_event(&mut self, key: KeyEvent) -> Option<KeymapAction> {
+        if let Some(capture) = self.capture.take() {
+            if key.code != KeyCode::Esc {
+                self.bind(keymap::key_name(key.code), capture.append);
+            }
+            return None;
+        }
+
+        let action_count = self.scope().actions.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+                return Some(KeymapAction::Back);
+            }
+            KeyCode::Left => self.switch_scope(false),
+            KeyCode::Right => self.switch_scope(true),
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down if self.selected + 1 < action_count => self.selected += 1,
+            KeyCode::Enter => self.capture = Some(Capture { append: false }),
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                self.capture = Some(Capture { append: true });
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reset(),
+            _ => {}
+        }
+        None
+    }
+
+    /// Renders the key binding page.
+    pub fn render(&self, frame: &mut ratatui::Frame<'_>, area: Rect) {
+        let (width, _) = self.minimum_size();
+        let area = centered_rect(area, width, area.height.min(24));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols::border::DOUBLE)
+            .border_style(Style::default().fg(Color::White))
+            .title(format!(" {} ", i18n::t("keymap.title")));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(3),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let scope = self.scope();
+        let title = format!("\u{25C0} {} \u{25B6}", scope.title);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                title,
+                Style::default()
+                    .fg(Color::LightYellow)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .alignment(Alignment::Center),
+            rows[0],
+        );
+
+        let label_width = scope
+            .actions
+            .iter()
+            .map(|action| UnicodeWidthStr::width(action.label(&scope.id).as_str()))
+            .max()
+            .unwrap_or(0);
+        let lines: Vec<Line<'static>> = scope
+            .actions
+            .iter()
+            .enumerate()
+            .map(|(idx, action)| {
+                let is_selected = idx == self.selected;
+                let style = if is_selected {
+                    Style::default()
+                        .fg(Color::LightCyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let label = action.label(&scope.id);
+                let padding = label_width - UnicodeWidthStr::width(label.as_str());
+                let keys = match self.keymap.describe(&action.name) {
+                    keys if keys.is_empty() => i18n::t("keymap.unbound"),
+                    keys => keys,
+                };
+                let marker = if is_selected { "\u{25B6} " } else { "  " };
+                Line::from(vec![
+                    Span::styled(format!("{marker}{label}{}  ", " ".repeat(padding)), style),
+                    Span::styled(keys, Style::default().fg(Color::LightYellow)),
+                ])
+            })
+            .collect();
+        let visible = rows[1].height as usize;
+        let offset = self.selected.saturating_sub(visible.saturating_sub(1));
+        frame.render_widget(Paragraph::new(lines).scroll((offset as u16, 0)), rows[1]);
+
+        let status = match (&self.capture, &self.message) {
+            (Some(_), _) => Some((
+                i18n::t_args(
+                    "keymap.capture",
+                    &[("action", self.selected_action().label(&scope.id).into())],
+                ),
+                Color::LightCyan,
+            )),
+            (None, message) => message.clone(),
+        };
+        if let Some((text, color)) = status {
+            frame.render_widget(
+                Paragraph::new(Span::styled(text, Style::default().fg(color)))
+                    .wrap(Wrap { trim: false }),
+                rows[2],
+            );
+        }
+
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                i18n::t("keymap.hint"),
+                Style::default().fg(Color::DarkGray),
+            )),
+            rows[3],
+        );
+    }
+
+    /// Returns the minimum terminal size needed for stable layout.
+    pub fn minimum_size(&self) -> (u16, u16) {
+        let hint_width = UnicodeWidthStr::width(i18n::t("keymap.hint").as_str()) as u16;
+        (hint_width.max(60) + 4, 16)
+    }
+
+    fn scope(&self) -> &KeymapScope {
+        &self.scopes[self.scope_index]
+    }
+
+    fn selected_action(&self) -> &ActionSpec {
+        &self.scope().actions[self.selected]
+    }
+
+    fn switch_scope(&mut self, forward: bool) {
+        let total = self.scopes.len();
+        self.scope_index = if forward {
+            (self.scope_index + 1) % total
+        } else {
+            (self.scope_index + total - 1) % total
+        };
+        self.selected = 0;
+        self.message = None;
+        self.reload();
+    }
+
+    fn reload(&mut self) {
+        let scope = self.scope();
+        self.keymap = Keymap::load(&scope.id, &scope.actions);
+    }
+
+    fn bind(&mut self, key: String, append: bool) {
+        if key.is_empty() {
+            return;
+        }
+        let action = self.selected_action().name.clone();
+        let mut keys = if append {
+            self.keymap.keys(&action).to_vec()
+        } else {
+            Vec::new()
+        };
+        if !keys.contains(&key) {
+            keys.push(key.clone());
+        }
+        let scope_id = self.scope().id.clone();
+        if let Err(err) = keymap::set_keys(&scope_id, &action, &keys) {
+            self.message = Some((err.to_string(), Color::LightRed));
+            return;
+        }
+        self.reload();
+
+        // 同一个作用域里别的动作也用了这个键时提醒一下,不强行解绑
+        let scope = self.scope();
+        self.message = scope
+            .actions
+            .iter()
+            .find(|other| other.name != action && self.keymap.keys(&other.name).contains(&key))
+            .map(|other| {
+                (
+                    i18n::t_args(
+                        "keymap.conflict",
+                        &[
+                            ("key", keymap::key_label(&key).into()),
+                            ("action", other.label(&scope.id).into()),
+                        ],
+                    ),
+                    Color::LightYellow,
+                )
+            });
+    }
+
+    fn reset(&mut self) {
+        let action = self.selected_action().name.clone();
+        let scope_id = self.scope().id.clone();
+        self.message = keymap::set_keys(&scope_id, &action, &[])
+            .err()
+            .map(|err| (err.to_string(), Color::LightRed));
+        self.reload();
+    }
+}
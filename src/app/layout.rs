@@ -1,7 +1,7 @@
 ﻿use ratatui::layout::{Constraint, Direction, Layout, Rect};
 
 pub const MENU_MIN_WIDTH: u16 = 60;
-pub const MENU_MIN_HEIGHT: u16 = 15;
+pub const MENU_MIN_HEIGHT: u16 = 16;
 pub const MAIN_CONTENT_WIDTH: u16 = 72;
 pub const MENU_LIST_WIDTH: u16 = 30;
 
@@ -13,14 +13,14 @@ pub struct MainMenuAreas {
 
 /// Returns centered layout areas for the main menu.
 pub fn main_menu_areas(area: Rect) -> MainMenuAreas {
-    let content = centered_rect(area, MAIN_CONTENT_WIDTH.min(area.width), 15.min(area.height));
+    let content = centered_rect(area, MAIN_CONTENT_WIDTH.min(area.width), MENU_MIN_HEIGHT.min(area.height));
     let rows = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(6),
             Constraint::Length(1),
-            Constraint::Length(5),
-            Constraint::Length(2),
+            Constraint::Length(7),
+            Constraint::Length(1),
             Constraint::Length(1),
         ])
         .split(content);
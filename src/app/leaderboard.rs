@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::app::stats::{self, RecordBetter, RecordUnit};
+use crate::utils::path_utils;
+
+/// Number of entries kept per game and mode.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// How a game ranks its leaderboard, declared in `GAME_META.leaderboard` or its manifest.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct LeaderboardSpec {
+    pub unit: RecordUnit,
+    pub better: RecordBetter,
+    pub dimension: Option<String>,
+}
+
+impl LeaderboardSpec {
+    /// Returns the translated name of a mode; the empty mode has no name.
+    pub fn mode_label(&self, game_id: &str, mode: &str) -> String {
+        match &self.dimension {
+            Some(dimension) => stats::dimension_label(game_id, dimension, mode),
+            None => mode.to_string(),
+        }
+    }
+
+    fn compare(&self, a: &LeaderboardEntry, b: &LeaderboardEntry) -> Ordering {
+        let by_score = match self.better {
+            RecordBetter::Higher => b.score.cmp(&a.score),
+            RecordBetter::Lower => a.score.cmp(&b.score),
+        };
+        by_score.then(a.duration_sec.cmp(&b.duration_sec))
+    }
+}
+
+/// One row of a leaderboard.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: i64,
+    pub duration_sec: u64,
+    pub date: u64,
+    pub seed: Option<String>,
+}
+
+/// Leaderboards of one game: mode -> entries, best first.
+///
+/// Games without modes use the empty string.
+pub type GameLeaderboards = BTreeMap<String, Vec<LeaderboardEntry>>;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct LeaderboardFile {
+    #[serde(default)]
+    games: HashMap<String, GameLeaderboards>,
+    #[serde(default)]
+    last_name: Option<String>,
+}
+
+/// Loads the leaderboards of all games.
+pub fn load_leaderboards() -> HashMap<String, GameLeaderboards> {
+    load_file().map(|file| file.games).unwrap_or_default()
+}
+
+/// Returns the 1-based rank a result would get, or `None` when it does not make the table.
+pub fn qualifying_rank(
+    game_id: &str,
+    spec: &LeaderboardSpec,
+    mode: &str,
+    score: i64,
+    duration_sec: u64,
+) -> Option<usize> {
+    let file = load_file().ok()?;
+    let entries = file
+        .games
+        .get(game_id)
+        .and_then(|boards| boards.get(mode))
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    rank_in(entries, spec, score, duration_sec)
+}
+
+/// Inserts an entry into the leaderboard of a mode and returns its 1-based rank.
+///
+/// The name is remembered as the default for the next prompt.
+pub fn submit(
+    game_id: &str,
+    spec: &LeaderboardSpec,
+    mode: &str,
+    mut entry: LeaderboardEntry,
+) -> Result<Option<usize>> {
+    let mut file = load_file()?;
+    let entries = file
+        .games
+        .entry(game_id.to_string())
+        .or_default()
+        .entry(mode.to_string())
+        .or_default();
+    let Some(rank) = rank_in(entries, spec, entry.score, entry.duration_sec) else {
+        return Ok(None);
+    };
+    if entry.date == 0 {
+        entry.date = now_secs();
+    }
+    file.last_name = Some(entry.name.clone());
+    entries.insert(rank - 1, entry);
+    entries.truncate(LEADERBOARD_SIZE);
+    save_file(&file)?;
+    Ok(Some(rank))
+}
+
+/// Returns the name entered last time, used to prefill the name prompt.
+pub fn last_player_name() -> Option<String> {
+    load_file().ok()?.last_name
+}
+
+/// Forgets the leaderboards of a game.
+pub fn remove_game(game_id: &str) -> Result<()> {
+    let mut file = load_file()?;
+    let before = file.games.len();
+    file.games.retain(|id, _| !id.eq_ignore_ascii_case(game_id));
+    if file.games.len() != before {
+        save_file(&file)?;
+    }
+    Ok(())
+}
+
+/// Merges imported leaderboards into the current ones, keeping the best entries.
+///
+/// `spec` returns how a game ranks its table. Returns the merged content and
+/// how many entries made it into a table.
+pub fn merge_leaderboards(
+    current: &str,
+    incoming: &str,
+    spec: impl Fn(&str) -> LeaderboardSpec,
+) -> Result<(String, usize)> {
+    let mut merged: LeaderboardFile = serde_json::from_str(current).unwrap_or_default();
+    let incoming: LeaderboardFile = serde_json::from_str(incoming)?;
+    let mut changes = 0;
+    for (game_id, boards) in incoming.games {
+        let game_spec = spec(&game_id);
+        let game = merged.games.entry(game_id).or_default();
+        for (mode, entries) in boards {
+            let table = game.entry(mode).or_default();
+            for entry in entries {
+                if table.contains(&entry) {
+                    continue;
+                }
+                if let Some(rank) = rank_in(table, &game_spec, entry.score, entry.duration_sec) {
+                    table.insert(rank - 1, entry);
+                    table.truncate(LEADERBOARD_SIZE);
+                    changes += 1;
+                }
+            }
+        }
+    }
+    if merged.last_name.is_none() {
+        merged.last_name = incoming.last_name;
+    }
+    Ok((serde_json::to_string_pretty(&merged)?, changes))
+}
+
+// 新成绩排在所有不比它差的记录后面,同分同时长时先上榜的在前
+fn rank_in(
+    entries: &[LeaderboardEntry],
+    spec: &LeaderboardSpec,
+    score: i64,
+    duration_sec: u64,
+) -> Option<usize> {
+    let candidate = LeaderboardEntry {
+        score,
+        duration_sec,
+        ..LeaderboardEntry::default()
+    };
+    let position = entries
+        .iter()
+        .take_while(|entry| spec.compare(entry, &candidate) != Ordering::Greater)
+        .count();
+    (position < LEADERBOARD_SIZE).then_some(position + 1)
+}
+
+fn load_file() -> Result<LeaderboardFile> {
+    let path = leaderboard_file_path();
+    if !path.exists() {
+        return Ok(LeaderboardFile::default());
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+fn save_file(file: &LeaderboardFile) -> Result<()> {
+    let path = leaderboard_file_path();
+    path_utils::ensure_parent_dir(&path)?;
+    fs::write(path, serde_json::to_string_pretty(file)?)?;
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn leaderboard_file_path() -> PathBuf {
+    match path_utils::leaderboard_file() {
+        Ok(path) => path,
+        Err(_) => PathBuf::from("leaderboards.json"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LEADERBOARD_SIZE, LeaderboardEntry, LeaderboardSpec, rank_in};
+    use crate::app::stats::RecordBetter;
+
+    fn entry(score: i64, duration_sec: u64) -> LeaderboardEntry {
+        LeaderboardEntry {
+            score,
+            duration_sec,
+            ..LeaderboardEntry::default()
+        }
+    }
+
+    #[test]
+    fn rank_orders_by_score_then_time() {
+        let higher = LeaderboardSpec::default();
+        let entries = vec![entry(300, 50), entry(200, 40), entry(200, 60)];
+        assert_eq!(rank_in(&entries, &higher, 400, 10), Some(1));
+        assert_eq!(rank_in(&entries, &higher, 200, 40), Some(3));
+        assert_eq!(rank_in(&entries, &higher, 200, 50), Some(3));
+        assert_eq!(rank_in(&entries, &higher, 100, 0), Some(4));
+
+        let lower = LeaderboardSpec {
+            better: RecordBetter::Lower,
+            ..LeaderboardSpec::default()
+        };
+        let times = vec![entry(30, 30), entry(45, 45)];
+        assert_eq!(rank_in(&times, &lower, 40, 40), Some(2));
+    }
+
+    #[test]
+    fn full_table_rejects_worse_results() {
+        let spec = LeaderboardSpec::default();
+        let entries: Vec<LeaderboardEntry> = (0..LEADERBOARD_SIZE as i64)
+            .map(|i| entry(1000 - i * 10, 0))
+            .collect();
+        assert_eq!(rank_in(&entries, &spec, 500, 0), None);
+        assert_eq!(rank_in(&entries, &spec, 995, 0), Some(2));
+    }
+}
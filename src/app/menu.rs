@@ -2,11 +2,15 @@
 use ratatui::layout::{Alignment, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::Paragraph;
+use ratatui::symbols;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use unicode_width::UnicodeWidthStr;
 
-use crate::app::i18n::t;
+use crate::app::i18n::{self, t};
+use crate::app::keymap::Keymap;
 use crate::app::layout;
+use crate::terminal::renderer;
+use crate::utils::path_utils;
 
 pub const LOGO_ASCII: &str = r#"████████╗██╗   ██╗██╗     ██████╗  █████╗ ███╗   ███╗███████╗
 ╚══██╔══╝██║   ██║██║    ██╔════╝ ██╔══██╗████╗ ████║██╔════╝
@@ -19,6 +23,8 @@ pub const LOGO_ASCII: &str = r#"████████╗██╗   ██╗
 pub enum MenuAction {
     Play,
     Continue,
+    Statistics,
+    Achievements,
     Settings,
     About,
     Quit,
@@ -37,6 +43,8 @@ pub struct Menu {
     selected: usize,
     continue_game_id: Option<String>,
     continue_game_name: Option<String>,
+    notice: Option<String>,
+    keymap: Keymap,
 }
 
 impl Menu {
@@ -55,13 +63,23 @@ impl Menu {
                     action: MenuAction::Continue,
                 },
                 MenuItem {
-                    key: "menu.settings",
+                    key: "menu.statistics",
                     shortcut: KeyCode::Char('3'),
+                    action: MenuAction::Statistics,
+                },
+                MenuItem {
+                    key: "menu.achievements",
+                    shortcut: KeyCode::Char('4'),
+                    action: MenuAction::Achievements,
+                },
+                MenuItem {
+                    key: "menu.settings",
+                    shortcut: KeyCode::Char('5'),
                     action: MenuAction::Settings,
                 },
                 MenuItem {
                     key: "menu.about",
-                    shortcut: KeyCode::Char('4'),
+                    shortcut: KeyCode::Char('6'),
                     action: MenuAction::About,
                 },
                 MenuItem {
@@ -73,9 +91,26 @@ impl Menu {
             selected: 0,
             continue_game_id: None,
             continue_game_name: None,
+            notice: None,
+            keymap: Keymap::menu(),
         }
     }
 
+    /// Shows a message over the menu until the next key press.
+    pub fn show_notice(&mut self, message: String) {
+        self.notice = Some(message);
+    }
+
+    /// Dismisses the notice; returns true when one was shown.
+    pub fn take_notice(&mut self) -> bool {
+        self.notice.take().is_some()
+    }
+
+    /// Returns the menu keymap, read when the menu was created.
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
     /// Returns all menu items.
     pub fn items(&self) -> &[MenuItem] {
         &self.items
@@ -265,8 +300,45 @@ pub fn render_main_menu(
             Style::default().fg(Color::LightMagenta),
         ));
     }
+    // 当前档案,按P切换
+    version_spans.push(Span::styled(
+        format!(
+            "  {}",
+            i18n::t_args("menu.profile", &[("name", path_utils::active_profile().into())])
+        ),
+        Style::default().fg(Color::DarkGray),
+    ));
     let version_line = Paragraph::new(Line::from(version_spans)).alignment(Alignment::Center);
     frame.render_widget(version_line, areas.version);
+
+    if let Some(message) = &menu.notice {
+        render_notice(frame, message);
+    }
+}
+
+// 菜单上方的提示框,按任意键关闭
+fn render_notice(frame: &mut ratatui::Frame<'_>, message: &str) {
+    let area = frame.area();
+    let back = t("menu.notice.dismiss");
+    let content_w = UnicodeWidthStr::width(message).max(UnicodeWidthStr::width(back.as_str()));
+    let width = (content_w as u16 + 4).clamp(32, 72).min(area.width.saturating_sub(2));
+    let text_lines = renderer::wrap_text(message, width.saturating_sub(2) as usize).len() + 1;
+    let height = (text_lines as u16 + 2).min(area.height.saturating_sub(2));
+    let rect = layout::centered_rect(area, width.max(1), height.max(1));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(symbols::border::DOUBLE)
+        .border_style(Style::default().fg(Color::White));
+    let inner = block.inner(rect);
+    frame.render_widget(Clear, rect);
+    frame.render_widget(block, rect);
+
+    let msg = Paragraph::new(format!("{message}\n{back}"))
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(msg, inner);
 }
 
 fn menu_item_label(menu: &Menu, item: &MenuItem) -> String {
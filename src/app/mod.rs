@@ -1,10 +1,27 @@
-﻿pub mod game_selection;
+﻿pub mod achievements;
+pub mod achievements_page;
+pub mod activity;
+pub mod backup;
+pub mod backup_page;
+pub mod fuzzy;
+pub mod game_library;
+pub mod game_manager;
+pub mod game_selection;
+pub mod history;
 pub mod i18n;
+pub mod i18n_check;
+pub mod keymap;
+pub mod keymap_page;
 pub mod layout;
+pub mod leaderboard;
 pub mod menu;
 pub mod placeholder_pages;
+pub mod profiles;
+pub mod profiles_page;
 pub mod rich_text;
 pub mod settings;
+pub mod settings_store;
+pub mod statistics;
 pub mod stats;
 
 
@@ -2,7 +2,8 @@ use ratatui::layout::{Alignment, Constraint, Direction, Layout};
 use ratatui::style::{Color, Style};
 use ratatui::widgets::{Paragraph, Wrap};
 
-use crate::app::i18n::t;
+use crate::app::i18n::{self, t};
+use crate::app::keymap::Keymap;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum PlaceholderPage {
@@ -15,6 +16,7 @@ pub enum PlaceholderPage {
 pub fn render_placeholder(
     frame: &mut ratatui::Frame<'_>,
     page: PlaceholderPage,
+    keymap: &Keymap,
     runtime_version: &str,
     latest_version: Option<&str>,
 ) {
@@ -31,7 +33,11 @@ pub fn render_placeholder(
         PlaceholderPage::Continue => t("placeholder.continue").to_string(),
     };
 
-    let text = format!("{}\n\n{}", message, t("common.back_hint"));
+    let back_hint = i18n::t_args(
+        "common.back_hint",
+        &[("close", keymap.describe("close").into())],
+    );
+    let text = format!("{}\n\n{}", message, back_hint);
     let lines = text.lines().count() as u16;
     let sections = Layout::default()
         .direction(Direction::Vertical)
@@ -0,0 +1,109 @@
+use std::fs;
+
+use anyhow::{Result, anyhow};
+
+use crate::app::{i18n, stats};
+use crate::utils::path_utils::{self, DEFAULT_PROFILE};
+
+const MAX_NAME_CHARS: usize = 24;
+
+/// Selects the profile to start with: the `--profile` argument, else the one used last.
+pub fn init(requested: Option<&str>) -> Result<()> {
+    let name = match requested {
+        Some(name) => validate_name(name)?,
+        None => last_profile()
+            .and_then(|name| validate_name(&name).ok())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string()),
+    };
+    path_utils::set_active_profile(&name);
+    // 数据目录用不了时启动时已经提示过,这里照常启动,之后的读写各自失败
+    let _ = fs::create_dir_all(path_utils::profiles_dir()?.join(&name));
+    // 让默认档案在启动时就完成旧数据的迁移
+    let _ = path_utils::profile_data_dir();
+    // 旧存档里的最佳记录搬不过来时只是详情里暂时看不到,不影响启动
+    let _ = stats::migrate_legacy_bests();
+    Ok(())
+}
+
+/// Lists all profiles, the default one first.
+pub fn list_profiles() -> Vec<String> {
+    let mut names: Vec<String> = path_utils::profiles_dir()
+        .and_then(|dir| Ok(fs::read_dir(dir)?))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| name != DEFAULT_PROFILE)
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+/// Creates a profile directory and returns the cleaned-up name.
+pub fn create_profile(name: &str) -> Result<String> {
+    let name = validate_name(name)?;
+    let dir = path_utils::profiles_dir()?.join(&name);
+    if dir.exists() {
+        return Err(anyhow!(i18n::t_args(
+            "profiles.error.exists",
+            &[("name", name.as_str().into())]
+        )));
+    }
+    fs::create_dir_all(dir)?;
+    Ok(name)
+}
+
+/// Makes a profile active, remembers it for the next start and reloads its language.
+pub fn switch_profile(name: &str) -> Result<()> {
+    let name = validate_name(name)?;
+    path_utils::set_active_profile(&name);
+    path_utils::profile_data_dir()?;
+    let _ = stats::migrate_legacy_bests();
+    let path = path_utils::last_profile_file()?;
+    path_utils::ensure_parent_dir(&path)?;
+    fs::write(path, format!("{name}\n"))?;
+    i18n::init("us-en")
+}
+
+// 档案名会用作目录名,只允许字母、数字、下划线和短横线
+fn validate_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.chars().count() <= MAX_NAME_CHARS
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(name.to_string())
+    } else {
+        Err(anyhow!(i18n::t_args(
+            "profiles.error.invalid_name",
+            &[("name", name.into())]
+        )))
+    }
+}
+
+fn last_profile() -> Option<String> {
+    let content = fs::read_to_string(path_utils::last_profile_file().ok()?).ok()?;
+    let name = content.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_name;
+
+    #[test]
+    fn profile_names_are_safe_directory_names() {
+        assert_eq!(validate_name("  alice ").unwrap(), "alice");
+        assert_eq!(validate_name("小明_2").unwrap(), "小明_2");
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../evil").is_err());
+        assert!(validate_name("a b").is_err());
+        assert!(validate_name(&"x".repeat(25)).is_err());
+    }
+}
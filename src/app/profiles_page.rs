@@ -0,0 +1,165 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+
+use crate::app::i18n;
+use crate::app::keymap::Keymap;
+use crate::app::layout::centered_rect;
+use crate::app::profiles;
+use crate::utils::path_utils;
+
+const MAX_INPUT_CHARS: usize = 24;
+
+pub struct ProfilesPage {
+    profiles: Vec<String>,
+    active: String,
+    list_state: ListState,
+    input: Option<String>,
+    error: Option<String>,
+    keymap: Keymap,
+}
+
+pub enum ProfilesAction {
+    Back,
+    Switched,
+}
+
+impl ProfilesPage {
+    /// Creates the profile switcher with the active profile selected.
+    pub fn new() -> Self {
+        let profiles = profiles::list_profiles();
+        let active = path_utils::active_profile();
+        let mut list_state = ListState::default();
+        list_state.select(Some(
+            profiles.iter().position(|name| *name == active).unwrap_or(0),
+        ));
+        Self {
+            profiles,
+            active,
+            list_state,
+            input: None,
+            error: None,
+            keymap: Keymap::menu(),
+        }
+    }
+
+    /// Handles keyboard events and returns high-level actions.
+    pub fn handle_event(&mut self, key: KeyEvent) -> Option<ProfilesAction> {
+        self.error = None;
+        if let Some(input) = &mut self.input {
+            match key.code {
+                KeyCode::Esc => self.input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) if input.chars().count() < MAX_INPUT_CHARS => input.push(c),
+                KeyCode::Enter => {
+                    let result = profiles::create_profile(input)
+                        .and_then(|name| profiles::switch_profile(&name));
+                    match result {
+                        Ok(()) => return Some(ProfilesAction::Switched),
+                        Err(err) => self.error = Some(err.to_string()),
+                    }
+                }
+                _ => {}
+            }
+            return None;
+        }
+
+        match self.keymap.pick(key, &["up", "down", "confirm", "close"]) {
+            Some("up") => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                self.list_state.select(Some(selected.saturating_sub(1)));
+            }
+            Some("down") => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                let last = self.profiles.len().saturating_sub(1);
+                self.list_state.select(Some((selected + 1).min(last)));
+            }
+            Some("confirm") => {
+                let name = self.list_state.selected().and_then(|i| self.profiles.get(i))?;
+                match profiles::switch_profile(name) {
+                    Ok(()) => return Some(ProfilesAction::Switched),
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            Some(_) => return Some(ProfilesAction::Back),
+            None => {
+                if matches!(key.code, KeyCode::Char('n') | KeyCode::Char('N')) {
+                    self.input = Some(String::new());
+                }
+            }
+        }
+        None
+    }
+
+    /// Renders the profile switcher.
+    pub fn render(&mut self, frame: &mut ratatui::Frame<'_>, area: Rect) {
+        let height = (self.profiles.len() as u16 + 4).max(8);
+        let area = centered_rect(area, self.minimum_size().0, height);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        let items: Vec<ListItem<'_>> = self
+            .profiles
+            .iter()
+            .map(|name| {
+                let marker = if *name == self.active { "● " } else { "  " };
+                ListItem::new(format!("{marker}{name}"))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(symbols::border::DOUBLE)
+                    .border_style(Style::default().fg(Color::White))
+                    .title(format!(" {} ", i18n::t("profiles.title"))),
+            )
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::LightBlue));
+        frame.render_stateful_widget(list, rows[0], &mut self.list_state);
+
+        let status = match (&self.input, &self.error) {
+            (_, Some(error)) => Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::LightRed),
+            )),
+            (Some(input), None) => Line::from(vec![
+                Span::styled(
+                    format!("{} ", i18n::t("profiles.new_name")),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(
+                    format!("{input}_"),
+                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            (None, None) => Line::from(""),
+        };
+        frame.render_widget(Paragraph::new(status), rows[1]);
+
+        let hint = if self.input.is_some() {
+            i18n::t("profiles.hint.input")
+        } else {
+            self.keymap.hint("profiles.hint")
+        };
+        frame.render_widget(
+            Paragraph::new(Span::styled(hint, Style::default().fg(Color::DarkGray))),
+            rows[2],
+        );
+    }
+
+    /// Returns the minimum terminal size needed for stable layout.
+    pub fn minimum_size(&self) -> (u16, u16) {
+        (56, 12)
+    }
+}
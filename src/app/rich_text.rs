@@ -1,13 +1,54 @@
-﻿use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use unicode_width::UnicodeWidthChar;
 
 use crate::app::i18n;
 
-#[derive(Clone)]
+/// A run of text sharing one style after rich-text parsing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RichSpan {
+    pub text: String,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+/// Style channel a command writes to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StyleTarget {
+    Fg,
+    Bg,
+}
+
+/// Outcome of a single rich-text command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CommandEffect {
+    pub target: StyleTarget,
+    pub clear: bool,
+    pub color: Option<Color>,
+    pub count: Option<usize>,
+}
+
+/// Source of rich-text commands consulted before the built-in `tc`/`bg` commands.
+pub trait CommandHandler {
+    /// Returns `None` when the handler does not know `cmd`.
+    fn apply(&self, cmd: &str, params: &[String]) -> Option<Result<CommandEffect, String>>;
+}
+
+/// Built-in `tc`/`bg` commands, always available as the last resort.
+pub struct BuiltinCommands;
+
+impl CommandHandler for BuiltinCommands {
+    fn apply(&self, cmd: &str, params: &[String]) -> Option<Result<CommandEffect, String>> {
+        let target = builtin_target(cmd)?;
+        Some(parse_color_params(target, params))
+    }
+}
+
+#[derive(Clone, Copy)]
 struct StyledChar {
     ch: char,
-    style: Style,
+    fg: Option<Color>,
+    bg: Option<Color>,
 }
 
 #[derive(Clone)]
@@ -22,26 +63,223 @@ struct StyleState {
     bg_need_clear: bool,
 }
 
+/// Returns true when the text opts into rich-text rendering with the `f%` prefix.
+pub fn is_rich_text(text: &str) -> bool {
+    text.starts_with("f%")
+}
+
+/// Parses optional `f%` rich-text syntax into styled spans.
+///
+/// Commands are resolved through `handler` first and fall back to the built-in
+/// `tc`/`bg` commands. Errors are rendered inline as red `{message}` spans.
+/// A `\n` escape produces a real line break inside the span text.
+pub fn parse_rich_text(
+    text: &str,
+    handler: &dyn CommandHandler,
+    default_fg: Option<Color>,
+    default_bg: Option<Color>,
+) -> Vec<RichSpan> {
+    parse_styled_chars(text, handler, default_fg, default_bg)
+        .0
+        .iter()
+        .fold(Vec::new(), |mut spans, item| {
+            push_span_char(&mut spans, item);
+            spans
+        })
+}
+
+/// Returns the error messages produced while parsing rich text, empty when it is valid.
+pub fn rich_text_errors(text: &str, handler: &dyn CommandHandler) -> Vec<String> {
+    parse_styled_chars(text, handler, None, None).1
+}
+
 /// Parses optional `f%` rich-text syntax and wraps into ratatui lines.
 ///
-/// Supported commands:
+/// Built-in commands:
 /// - `{tc:<color>}` / `{tc:clear}` / `{tc:<color>><count>}`
 /// - `{bg:<color>}` / `{bg:clear}` / `{bg:<color>><count>}`
-pub fn parse_rich_text_wrapped(text: &str, width: usize, base: Style) -> Vec<Line<'static>> {
-    let content = text.strip_prefix("f%").unwrap_or(text);
+///
+/// Commands are looked up in `handler` first, then in the built-ins.
+pub fn parse_rich_text_wrapped(
+    text: &str,
+    width: usize,
+    base: Style,
+    handler: &dyn CommandHandler,
+) -> Vec<Line<'static>> {
+    let (chars, _) = parse_styled_chars(text, handler, base.fg, base.bg);
+    styled_chars_to_lines(&chars, width.max(1), base)
+}
+
+/// Parses a color name, `#RGB`, `#RRGGBB` or `rgb(r,g,b)`.
+pub fn parse_color(raw: &str) -> Option<Color> {
+    let text = raw.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Some(c) = parse_hex_color(text) {
+        return Some(c);
+    }
+    if let Some(c) = parse_rgb_color(text) {
+        return Some(c);
+    }
+
+    // 基础色名沿用游戏一直以来的亮色效果, dark_*是对应的暗色
+    match text.to_ascii_lowercase().as_str() {
+        "black" => Some(Color::Black),
+        "white" => Some(Color::White),
+        "red" | "light_red" => Some(Color::LightRed),
+        "dark_red" => Some(Color::Red),
+        "green" | "light_green" => Some(Color::LightGreen),
+        "dark_green" => Some(Color::Green),
+        "yellow" | "light_yellow" => Some(Color::LightYellow),
+        "dark_yellow" | "orange" => Some(Color::Yellow),
+        "blue" | "light_blue" => Some(Color::LightBlue),
+        "dark_blue" => Some(Color::Blue),
+        "magenta" | "light_magenta" => Some(Color::LightMagenta),
+        "dark_magenta" => Some(Color::Magenta),
+        "cyan" | "light_cyan" => Some(Color::LightCyan),
+        "dark_cyan" => Some(Color::Cyan),
+        "gray" | "grey" => Some(Color::Gray),
+        "dark_gray" | "dark_grey" => Some(Color::DarkGray),
+        _ => None,
+    }
+}
 
-    let mut state = StyleState {
-        default_fg: base.fg,
-        default_bg: base.bg,
-        fg: base.fg,
-        bg: base.bg,
-        fg_count: None,
-        bg_count: None,
-        fg_need_clear: false,
-        bg_need_clear: false,
+/// Returns the name `parse_color` reads back as `color`, e.g. `red` or `#1e90ff`.
+pub fn color_name(color: Color) -> Option<String> {
+    let name = match color {
+        Color::Black => "black",
+        Color::White => "white",
+        Color::LightRed => "red",
+        Color::Red => "dark_red",
+        Color::LightGreen => "green",
+        Color::Green => "dark_green",
+        Color::LightYellow => "yellow",
+        Color::Yellow => "dark_yellow",
+        Color::LightBlue => "blue",
+        Color::Blue => "dark_blue",
+        Color::LightMagenta => "magenta",
+        Color::Magenta => "dark_magenta",
+        Color::LightCyan => "cyan",
+        Color::Cyan => "dark_cyan",
+        Color::Gray => "gray",
+        Color::DarkGray => "dark_gray",
+        Color::Rgb(r, g, b) => return Some(format!("#{r:02x}{g:02x}{b:02x}")),
+        _ => return None,
     };
+    Some(name.to_string())
+}
 
+/// Parses `[color, count?]` / `[clear]` parameters shared by `tc`, `bg` and script commands.
+pub fn parse_color_params(target: StyleTarget, params: &[String]) -> Result<CommandEffect, String> {
+    if params.is_empty() || params[0].trim().is_empty() {
+        return Err(rt("rich_text.error.missing_param"));
+    }
+
+    if params[0].eq_ignore_ascii_case("clear") {
+        if params.len() != 1 {
+            return Err(rt("rich_text.error.invalid_param"));
+        }
+        return Ok(CommandEffect {
+            target,
+            clear: true,
+            color: None,
+            count: None,
+        });
+    }
+
+    let Some(color) = parse_color(&params[0]) else {
+        return Err(rt("rich_text.error.invalid_param"));
+    };
+
+    if params.len() > 2 {
+        return Err(rt("rich_text.error.invalid_param"));
+    }
+
+    let count = if params.len() == 2 && !params[1].trim().is_empty() {
+        match params[1].trim().parse::<usize>() {
+            Ok(v) if v > 0 => Some(v),
+            _ => return Err(rt("rich_text.error.invalid_param")),
+        }
+    } else {
+        None
+    };
+
+    Ok(CommandEffect {
+        target,
+        clear: false,
+        color: Some(color),
+        count,
+    })
+}
+
+/// Maps the built-in command names onto their style channel.
+pub fn builtin_target(cmd: &str) -> Option<StyleTarget> {
+    match cmd {
+        "tc" => Some(StyleTarget::Fg),
+        "bg" => Some(StyleTarget::Bg),
+        _ => None,
+    }
+}
+
+impl StyleState {
+    fn new(default_fg: Option<Color>, default_bg: Option<Color>) -> Self {
+        Self {
+            default_fg,
+            default_bg,
+            fg: default_fg,
+            bg: default_bg,
+            fg_count: None,
+            bg_count: None,
+            fg_need_clear: false,
+            bg_need_clear: false,
+        }
+    }
+
+    fn reset_to_default(&mut self) {
+        *self = Self::new(self.default_fg, self.default_bg);
+    }
+
+    fn apply(&mut self, effect: CommandEffect) {
+        let (color, count, need_clear, default) = match effect.target {
+            StyleTarget::Fg => (
+                &mut self.fg,
+                &mut self.fg_count,
+                &mut self.fg_need_clear,
+                self.default_fg,
+            ),
+            StyleTarget::Bg => (
+                &mut self.bg,
+                &mut self.bg_count,
+                &mut self.bg_need_clear,
+                self.default_bg,
+            ),
+        };
+
+        if effect.clear {
+            *color = default;
+            *count = None;
+            *need_clear = false;
+        } else {
+            *color = effect.color.or(default);
+            *count = effect.count;
+            *need_clear = effect.count.is_none();
+        }
+    }
+}
+
+fn parse_styled_chars(
+    text: &str,
+    handler: &dyn CommandHandler,
+    default_fg: Option<Color>,
+    default_bg: Option<Color>,
+) -> (Vec<StyledChar>, Vec<String>) {
+    let content = text.strip_prefix("f%").unwrap_or(text);
+
+    let mut state = StyleState::new(default_fg, default_bg);
     let mut out: Vec<StyledChar> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
     let chars: Vec<char> = content.chars().collect();
     let mut i = 0usize;
 
@@ -52,13 +290,13 @@ pub fn parse_rich_text_wrapped(text: &str, width: usize, base: Style) -> Vec<Lin
             if i + 1 < chars.len() {
                 let next = chars[i + 1];
                 if next == 'n' {
-                    push_char(&mut out, '\n', &mut state, base);
+                    push_char(&mut out, '\n', &mut state);
                 } else {
-                    push_char(&mut out, next, &mut state, base);
+                    push_char(&mut out, next, &mut state);
                 }
                 i += 2;
             } else {
-                push_char(&mut out, '\\', &mut state, base);
+                push_char(&mut out, '\\', &mut state);
                 i += 1;
             }
             continue;
@@ -67,57 +305,64 @@ pub fn parse_rich_text_wrapped(text: &str, width: usize, base: Style) -> Vec<Lin
         if ch == '{' {
             if let Some((block, consumed)) = read_block(&chars[i..]) {
                 if block.trim().is_empty() {
-                    push_error(&mut out, &rt("rich_text.error.empty_command"), base);
-                    reset_to_default(&mut state);
+                    push_error(
+                        &mut out,
+                        &mut errors,
+                        &rt("rich_text.error.empty_command"),
+                        default_bg,
+                    );
+                    state.reset_to_default();
                     i += consumed;
                     continue;
                 }
 
                 let rest = &chars[i + consumed..];
-                match apply_block(&block, &mut state, rest) {
-                    Ok(()) => {}
-                    Err(msg) => {
-                        push_error(&mut out, &msg, base);
-                        reset_to_default(&mut state);
-                    }
+                if let Err(msg) = apply_block(&block, &mut state, rest, handler) {
+                    push_error(&mut out, &mut errors, &msg, default_bg);
+                    state.reset_to_default();
                 }
 
                 i += consumed;
                 continue;
             }
 
-            push_error(&mut out, &rt("rich_text.error.unclosed_command"), base);
-            reset_to_default(&mut state);
+            push_error(
+                &mut out,
+                &mut errors,
+                &rt("rich_text.error.unclosed_command"),
+                default_bg,
+            );
+            state.reset_to_default();
             i += 1;
             continue;
         }
 
         if ch == '}' {
-            push_error(&mut out, &rt("rich_text.error.unclosed_command"), base);
-            reset_to_default(&mut state);
+            push_error(
+                &mut out,
+                &mut errors,
+                &rt("rich_text.error.unclosed_command"),
+                default_bg,
+            );
+            state.reset_to_default();
             i += 1;
             continue;
         }
 
-        push_char(&mut out, ch, &mut state, base);
+        push_char(&mut out, ch, &mut state);
         i += 1;
     }
 
     if state.fg_need_clear || state.bg_need_clear {
-        push_error(&mut out, &rt("rich_text.error.unterminated_style"), base);
-        reset_to_default(&mut state);
+        push_error(
+            &mut out,
+            &mut errors,
+            &rt("rich_text.error.unterminated_style"),
+            default_bg,
+        );
     }
 
-    styled_chars_to_lines(&out, width.max(1), base)
-}
-
-fn reset_to_default(state: &mut StyleState) {
-    state.fg = state.default_fg;
-    state.bg = state.default_bg;
-    state.fg_count = None;
-    state.bg_count = None;
-    state.fg_need_clear = false;
-    state.bg_need_clear = false;
+    (out, errors)
 }
 
 fn read_block(input: &[char]) -> Option<(String, usize)> {
@@ -178,131 +423,77 @@ fn split_unescaped(input: &str, sep: char) -> Vec<String> {
     out
 }
 
-fn apply_block(block: &str, state: &mut StyleState, rest: &[char]) -> Result<(), String> {
-    let commands = split_unescaped(block, '|');
-    if commands.is_empty() {
-        return Err(rt("rich_text.error.empty_command"));
-    }
-
-    for command in commands {
+fn apply_block(
+    block: &str,
+    state: &mut StyleState,
+    rest: &[char],
+    handler: &dyn CommandHandler,
+) -> Result<(), String> {
+    for command in split_unescaped(block, '|') {
         if command.trim().is_empty() {
             return Err(rt("rich_text.error.empty_command"));
         }
         let pair = split_unescaped(&command, ':');
-        if pair.len() != 2 {
-            return Err(rt("rich_text.error.invalid_param"));
+        if pair.len() != 2 || pair[0].trim().is_empty() {
+            return Err(rt("rich_text.error.missing_command_or_param"));
         }
 
         let cmd = pair[0].trim().to_ascii_lowercase();
         let params = split_unescaped(&pair[1], '>');
 
-        match cmd.as_str() {
-            "tc" => apply_color_command(params, true, state, rest)?,
-            "bg" => apply_color_command(params, false, state, rest)?,
-            _ => return Err(rt("rich_text.error.invalid_command")),
-        }
-    }
+        let effect = handler
+            .apply(&cmd, &params)
+            .or_else(|| BuiltinCommands.apply(&cmd, &params))
+            .unwrap_or_else(|| Err(rt("rich_text.error.invalid_command")))?;
 
-    Ok(())
-}
-
-fn apply_color_command(
-    params: Vec<String>,
-    is_fg: bool,
-    state: &mut StyleState,
-    rest: &[char],
-) -> Result<(), String> {
-    if params.is_empty() || params[0].is_empty() {
-        return Err(rt("rich_text.error.invalid_param"));
-    }
-
-    let cmd_name = if is_fg { "tc" } else { "bg" };
-
-    if params[0].eq_ignore_ascii_case("clear") {
-        if params.len() != 1 {
-            return Err(rt("rich_text.error.invalid_param"));
+        if !effect.clear && effect.count.is_none() && !has_future_clear(rest, &cmd, effect.target) {
+            return Err(rt("rich_text.error.unterminated_style"));
         }
-        if is_fg {
-            state.fg = state.default_fg;
-            state.fg_count = None;
-            state.fg_need_clear = false;
-        } else {
-            state.bg = state.default_bg;
-            state.bg_count = None;
-            state.bg_need_clear = false;
-        }
-        return Ok(());
-    }
-
-    let Some(color) = parse_color(&params[0]) else {
-        return Err(rt("rich_text.error.invalid_param"));
-    };
-
-    let count = if params.len() >= 2 && !params[1].trim().is_empty() {
-        match params[1].trim().parse::<usize>() {
-            Ok(v) if v > 0 => Some(v),
-            _ => return Err(rt("rich_text.error.invalid_param")),
-        }
-    } else {
-        None
-    };
-
-    if params.len() > 2 {
-        return Err(rt("rich_text.error.invalid_param"));
-    }
 
-    if count.is_none() && !has_future_clear(rest, cmd_name) {
-        return Err(rt("rich_text.error.unterminated_style"));
-    }
-
-    if is_fg {
-        state.fg = Some(color);
-        state.fg_count = count;
-        state.fg_need_clear = count.is_none();
-    } else {
-        state.bg = Some(color);
-        state.bg_count = count;
-        state.bg_need_clear = count.is_none();
+        state.apply(effect);
     }
 
     Ok(())
 }
 
-fn has_future_clear(rest: &[char], cmd: &str) -> bool {
+fn has_future_clear(rest: &[char], cmd: &str, target: StyleTarget) -> bool {
     let mut i = 0usize;
     while i < rest.len() {
         if rest[i] == '\\' {
             i += 2;
             continue;
         }
-        if rest[i] == '{' {
-            if let Some((block, consumed)) = read_block(&rest[i..]) {
-                for command in split_unescaped(&block, '|') {
-                    let pair = split_unescaped(&command, ':');
-                    if pair.len() != 2 {
-                        continue;
-                    }
-                    if pair[0].trim().eq_ignore_ascii_case(cmd) {
-                        let params = split_unescaped(&pair[1], '>');
-                        if params.len() == 1 && params[0].eq_ignore_ascii_case("clear") {
-                            return true;
-                        }
+        if rest[i] == '{'
+            && let Some((block, consumed)) = read_block(&rest[i..])
+        {
+            for command in split_unescaped(&block, '|') {
+                let pair = split_unescaped(&command, ':');
+                if pair.len() != 2 {
+                    continue;
+                }
+                let name = pair[0].trim().to_ascii_lowercase();
+                let same_channel = name == cmd || builtin_target(&name) == Some(target);
+                if same_channel {
+                    let params = split_unescaped(&pair[1], '>');
+                    if params.len() == 1 && params[0].eq_ignore_ascii_case("clear") {
+                        return true;
                     }
                 }
-                i += consumed;
-                continue;
             }
+            i += consumed;
+            continue;
         }
         i += 1;
     }
     false
 }
 
-fn push_char(out: &mut Vec<StyledChar>, ch: char, state: &mut StyleState, base: Style) {
-    let mut style = base;
-    style.fg = state.fg;
-    style.bg = state.bg;
-    out.push(StyledChar { ch, style });
+fn push_char(out: &mut Vec<StyledChar>, ch: char, state: &mut StyleState) {
+    out.push(StyledChar {
+        ch,
+        fg: state.fg,
+        bg: state.bg,
+    });
 
     if let Some(rem) = state.fg_count {
         if rem <= 1 {
@@ -324,18 +515,35 @@ fn push_char(out: &mut Vec<StyledChar>, ch: char, state: &mut StyleState, base:
 }
 
 fn rt(key: &str) -> String {
-    i18n::t(key).to_string()
+    i18n::t(key)
 }
 
-fn push_error(out: &mut Vec<StyledChar>, msg: &str, base: Style) {
-    let mut style = base;
-    style.fg = Some(Color::Red);
-    style.bg = base.bg;
+fn push_error(out: &mut Vec<StyledChar>, errors: &mut Vec<String>, msg: &str, bg: Option<Color>) {
+    errors.push(msg.to_string());
     for ch in format!("{{{msg}}}").chars() {
-        out.push(StyledChar { ch, style });
+        out.push(StyledChar {
+            ch,
+            fg: Some(Color::Red),
+            bg,
+        });
     }
 }
 
+fn push_span_char(spans: &mut Vec<RichSpan>, item: &StyledChar) {
+    if let Some(last) = spans.last_mut()
+        && last.fg == item.fg
+        && last.bg == item.bg
+    {
+        last.text.push(item.ch);
+        return;
+    }
+    spans.push(RichSpan {
+        text: item.ch.to_string(),
+        fg: item.fg,
+        bg: item.bg,
+    });
+}
+
 fn styled_chars_to_lines(chars: &[StyledChar], width: usize, base: Style) -> Vec<Line<'static>> {
     let mut lines: Vec<Line<'static>> = Vec::new();
     let mut segment: Vec<StyledChar> = Vec::new();
@@ -350,7 +558,7 @@ fn styled_chars_to_lines(chars: &[StyledChar], width: usize, base: Style) -> Vec
             }
             continue;
         }
-        segment.push(item.clone());
+        segment.push(*item);
     }
 
     if !segment.is_empty() {
@@ -363,12 +571,20 @@ fn styled_chars_to_lines(chars: &[StyledChar], width: usize, base: Style) -> Vec
     lines
 }
 
-fn wrap_segment_wordwise(segment: &[StyledChar], width: usize, base: Style, out: &mut Vec<Line<'static>>) {
+fn wrap_segment_wordwise(
+    segment: &[StyledChar],
+    width: usize,
+    base: Style,
+    out: &mut Vec<Line<'static>>,
+) {
     let mut remaining: Vec<StyledChar> = segment.to_vec();
     let width = width.max(1);
 
     while !remaining.is_empty() {
-        let total_w = remaining.iter().map(|c| UnicodeWidthChar::width(c.ch).unwrap_or(0)).sum::<usize>();
+        let total_w = remaining
+            .iter()
+            .map(|c| UnicodeWidthChar::width(c.ch).unwrap_or(0))
+            .sum::<usize>();
         if total_w <= width {
             out.push(build_line(&remaining, base));
             break;
@@ -389,13 +605,7 @@ fn wrap_segment_wordwise(segment: &[StyledChar], width: usize, base: Style, out:
             limit = 1;
         }
 
-        let mut break_at = None;
-        for i in (0..limit).rev() {
-            if remaining[i].ch.is_whitespace() {
-                break_at = Some(i);
-                break;
-            }
-        }
+        let break_at = (0..limit).rev().find(|&i| remaining[i].ch.is_whitespace());
 
         let cut = match break_at {
             Some(i) if i > 0 => i,
@@ -425,61 +635,22 @@ fn build_line(chars: &[StyledChar], base: Style) -> Line<'static> {
         return Line::default();
     }
 
-    let mut spans: Vec<Span<'static>> = Vec::new();
-    let mut cur = String::new();
-    let mut style = base;
-
+    let mut spans: Vec<RichSpan> = Vec::new();
     for item in chars {
-        if cur.is_empty() {
-            style = item.style;
-        } else if style != item.style {
-            spans.push(Span::styled(std::mem::take(&mut cur), style));
-            style = item.style;
-        }
-        cur.push(item.ch);
-    }
-
-    if !cur.is_empty() {
-        spans.push(Span::styled(cur, style));
-    }
-
-    Line::from(spans)
-}
-
-
-
-fn parse_color(raw: &str) -> Option<Color> {
-    let text = raw.trim();
-    if text.is_empty() {
-        return None;
-    }
-
-    if let Some(c) = parse_hex_color(text) {
-        return Some(c);
-    }
-    if let Some(c) = parse_rgb_color(text) {
-        return Some(c);
-    }
-
-    match text.to_ascii_lowercase().as_str() {
-        "black" => Some(Color::Black),
-        "white" => Some(Color::White),
-        "red" => Some(Color::Red),
-        "green" => Some(Color::Green),
-        "yellow" => Some(Color::Yellow),
-        "blue" => Some(Color::Blue),
-        "magenta" => Some(Color::Magenta),
-        "cyan" => Some(Color::Cyan),
-        "gray" | "grey" => Some(Color::Gray),
-        "dark_gray" | "dark_grey" => Some(Color::DarkGray),
-        "light_red" => Some(Color::LightRed),
-        "light_green" => Some(Color::LightGreen),
-        "light_yellow" => Some(Color::LightYellow),
-        "light_blue" => Some(Color::LightBlue),
-        "light_magenta" => Some(Color::LightMagenta),
-        "light_cyan" => Some(Color::LightCyan),
-        _ => None,
-    }
+        push_span_char(&mut spans, item);
+    }
+
+    Line::from(
+        spans
+            .into_iter()
+            .map(|span| {
+                let mut style = base;
+                style.fg = span.fg;
+                style.bg = span.bg;
+                Span::styled(span.text, style)
+            })
+            .collect::<Vec<_>>(),
+    )
 }
 
 fn parse_hex_color(raw: &str) -> Option<Color> {
@@ -522,3 +693,40 @@ fn parse_rgb_color(raw: &str) -> Option<Color> {
     Some(Color::Rgb(r, g, b))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Shout;
+
+    impl CommandHandler for Shout {
+        fn apply(&self, cmd: &str, _params: &[String]) -> Option<Result<CommandEffect, String>> {
+            (cmd == "warn").then_some(Ok(CommandEffect {
+                target: StyleTarget::Fg,
+                clear: false,
+                color: Some(Color::Yellow),
+                count: Some(2),
+            }))
+        }
+    }
+
+    #[test]
+    fn builtin_commands_produce_spans() {
+        let spans = parse_rich_text("f%{tc:red>2}abc", &BuiltinCommands, None, None);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "ab");
+        assert_eq!(spans[0].fg, Some(Color::LightRed));
+        assert_eq!(spans[1].text, "c");
+        assert_eq!(spans[1].fg, None);
+    }
+
+    #[test]
+    fn custom_handler_is_consulted_first() {
+        let spans = parse_rich_text("f%{warn:x}abc", &Shout, None, None);
+        assert_eq!(spans[0].text, "ab");
+        assert_eq!(spans[0].fg, Some(Color::Yellow));
+
+        let fallback = parse_rich_text("f%{warn:x}abc", &BuiltinCommands, None, None);
+        assert_eq!(fallback[0].fg, Some(Color::Red));
+    }
+}
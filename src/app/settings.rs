@@ -1,4 +1,4 @@
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
@@ -7,6 +7,9 @@ use ratatui::widgets::Paragraph;
 use unicode_width::UnicodeWidthStr;
 
 use crate::app::i18n;
+use crate::app::keymap::Keymap;
+use crate::app::settings_store::{self, SETTINGS, SettingKind, SettingValue};
+use crate::lua_bridge::script_loader::scan_scripts;
 
 const MAX_COLS: usize = 12;
 const H_GAP: u16 = 1;
@@ -16,6 +19,40 @@ const TRIANGLE: &str = "\u{25B6} ";
 pub enum SettingsPage {
     Hub,
     Language,
+    Options,
+}
+
+/// Entries of the settings hub, in display order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HubItem {
+    Language,
+    Options,
+    Controls,
+    ManageGames,
+    Backup,
+    Uninstall,
+}
+
+const HUB_ITEMS: [HubItem; 6] = [
+    HubItem::Language,
+    HubItem::Options,
+    HubItem::Controls,
+    HubItem::ManageGames,
+    HubItem::Backup,
+    HubItem::Uninstall,
+];
+
+impl HubItem {
+    fn label(self) -> String {
+        match self {
+            HubItem::Language => i18n::t("settings.hub.language"),
+            HubItem::Options => i18n::t("settings.hub.options"),
+            HubItem::Controls => i18n::t("settings.hub.controls"),
+            HubItem::ManageGames => i18n::t("settings.hub.manage_games"),
+            HubItem::Backup => i18n::t("settings.hub.backup"),
+            HubItem::Uninstall => i18n::t("settings.hub.uninstall"),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -23,12 +60,20 @@ pub struct SettingsState {
     pub page: SettingsPage,
     pub hub_selected: usize,
     pub lang_selected: usize,
+    option_selected: usize,
+    option_values: Vec<SettingValue>,
+    option_input: Option<String>,
+    option_error: Option<String>,
+    keymap: Keymap,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum SettingsAction {
     None,
     BackToMenu,
+    Controls,
+    ManageGames,
+    Backup,
     RunUninstall,
 }
 
@@ -46,8 +91,20 @@ impl SettingsState {
             page: SettingsPage::Hub,
             hub_selected: 0,
             lang_selected: default_selected_index(),
+            option_selected: 0,
+            option_values: Vec::new(),
+            option_input: None,
+            option_error: None,
+            keymap: Keymap::menu(),
         }
     }
+
+    /// Creates settings hub state with `item` selected.
+    pub fn at(item: HubItem) -> Self {
+        let mut state = Self::new();
+        state.hub_selected = HUB_ITEMS.iter().position(|i| *i == item).unwrap_or(0);
+        state
+    }
 }
 
 /// Returns the default selected language index (current active language).
@@ -61,11 +118,15 @@ pub fn default_selected_index() -> usize {
 }
 
 /// Handles keyboard input for settings pages.
-pub fn handle_key(state: &mut SettingsState, code: KeyCode) -> SettingsAction {
+pub fn handle_key(state: &mut SettingsState, key: KeyEvent) -> SettingsAction {
     match state.page {
-        SettingsPage::Hub => handle_hub_key(state, code),
+        SettingsPage::Hub => handle_hub_key(state, key),
         SettingsPage::Language => {
-            handle_language_key(state, code);
+            handle_language_key(state, key);
+            SettingsAction::None
+        }
+        SettingsPage::Options => {
+            handle_options_key(state, key);
             SettingsAction::None
         }
     }
@@ -74,16 +135,20 @@ pub fn handle_key(state: &mut SettingsState, code: KeyCode) -> SettingsAction {
 /// Returns minimum terminal size needed by the active settings page.
 pub fn minimum_size(state: &SettingsState) -> (u16, u16) {
     match state.page {
-        SettingsPage::Hub => minimum_size_hub(),
-        SettingsPage::Language => minimum_size_language(),
+        SettingsPage::Hub => minimum_size_hub(&state.keymap),
+        SettingsPage::Language => minimum_size_language(&state.keymap),
+        SettingsPage::Options => minimum_size_options(&state.keymap),
     }
 }
 
 /// Renders settings page based on current sub-page state.
 pub fn render(frame: &mut ratatui::Frame<'_>, state: &SettingsState) {
     match state.page {
-        SettingsPage::Hub => render_hub(frame, state.hub_selected),
-        SettingsPage::Language => render_language_selector(frame, state.lang_selected),
+        SettingsPage::Hub => render_hub(frame, state.hub_selected, &state.keymap),
+        SettingsPage::Language => {
+            render_language_selector(frame, state.lang_selected, &state.keymap)
+        }
+        SettingsPage::Options => render_options(frame, state),
     }
 }
 
@@ -99,7 +164,7 @@ pub fn grid_metrics(term_width: u16, languages: &[i18n::LanguagePack]) -> GridMe
 
     let max_name_width = languages
         .iter()
-        .map(|pack| UnicodeWidthStr::width(pack.name.as_str()))
+        .map(|pack| UnicodeWidthStr::width(language_label(pack).as_str()))
         .max()
         .unwrap_or(4);
 
@@ -161,41 +226,57 @@ pub fn move_selection(selected: usize, key: KeyCode, metrics: GridMetrics, total
     }
 }
 
-fn handle_hub_key(state: &mut SettingsState, code: KeyCode) -> SettingsAction {
-    match code {
-        KeyCode::Up | KeyCode::Char('k') => {
+// 方向、确认和返回按键位设置,数字固定
+fn handle_hub_key(state: &mut SettingsState, key: KeyEvent) -> SettingsAction {
+    match state.keymap.pick(key, &["up", "down", "confirm", "close"]) {
+        Some("up") => {
             if state.hub_selected > 0 {
                 state.hub_selected -= 1;
             }
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            if state.hub_selected < 1 {
+        Some("down") => {
+            if state.hub_selected + 1 < HUB_ITEMS.len() {
                 state.hub_selected += 1;
             }
         }
-        KeyCode::Char('1') => state.hub_selected = 0,
-        KeyCode::Char('2') => state.hub_selected = 1,
-        KeyCode::Enter => {
-            if state.hub_selected == 0 {
+        Some("confirm") => match HUB_ITEMS[state.hub_selected.min(HUB_ITEMS.len() - 1)] {
+            HubItem::Language => {
+                // 游戏自带的翻译在扫描时注册,完成度要和i18n-check一样算上它们
+                let _ = scan_scripts();
                 state.page = SettingsPage::Language;
                 state.lang_selected = default_selected_index();
-            } else {
-                return SettingsAction::RunUninstall;
+            }
+            HubItem::Options => {
+                state.page = SettingsPage::Options;
+                state.option_values = load_option_values();
+                state.option_input = None;
+                state.option_error = None;
+            }
+            HubItem::Controls => return SettingsAction::Controls,
+            HubItem::ManageGames => return SettingsAction::ManageGames,
+            HubItem::Backup => return SettingsAction::Backup,
+            HubItem::Uninstall => return SettingsAction::RunUninstall,
+        },
+        Some(_) => return SettingsAction::BackToMenu,
+        None => {
+            if let KeyCode::Char(c @ '1'..='9') = key.code {
+                let index = c as usize - '1' as usize;
+                if index < HUB_ITEMS.len() {
+                    state.hub_selected = index;
+                }
             }
         }
-        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
-            return SettingsAction::BackToMenu;
-        }
-        _ => {}
     }
 
     SettingsAction::None
 }
 
-fn handle_language_key(state: &mut SettingsState, code: KeyCode) {
+// 确认和返回按键位设置,语言网格用方向键移动
+fn handle_language_key(state: &mut SettingsState, key: KeyEvent) {
+    let action = state.keymap.pick(key, &["confirm", "close"]);
     let languages = i18n::available_languages();
     if languages.is_empty() {
-        if matches!(code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q')) {
+        if action == Some("close") {
             state.page = SettingsPage::Hub;
         }
         return;
@@ -208,41 +289,134 @@ fn handle_language_key(state: &mut SettingsState, code: KeyCode) {
     let (term_width, _) = crossterm::terminal::size().unwrap_or((80, 24));
     let metrics = grid_metrics(term_width, &languages);
 
-    match code {
-        KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
-            state.lang_selected =
-                move_selection(state.lang_selected, code, metrics, languages.len());
-        }
-        KeyCode::Enter => {
+    match action {
+        Some("confirm") => {
             if let Some(pack) = languages.get(state.lang_selected) {
                 let _ = i18n::set_language(&pack.code);
             }
         }
-        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
-            state.page = SettingsPage::Hub;
+        Some(_) => state.page = SettingsPage::Hub,
+        None => {
+            state.lang_selected =
+                move_selection(state.lang_selected, key.code, metrics, languages.len());
         }
-        _ => {}
     }
 }
 
-fn minimum_size_hub() -> (u16, u16) {
-    let label_lang = i18n::t("settings.hub.language");
-    let label_uninstall = i18n::t("settings.hub.uninstall");
+// 方向、确认和返回按键位设置,调整和恢复默认固定,输入文字时都按原样处理
+fn handle_options_key(state: &mut SettingsState, key: KeyEvent) {
+    if state.option_values.len() != SETTINGS.len() {
+        state.option_values = load_option_values();
+    }
+    let selected = state.option_selected.min(SETTINGS.len() - 1);
+    let def = &SETTINGS[selected];
+
+    if let Some(input) = &mut state.option_input {
+        match key.code {
+            KeyCode::Enter => {
+                let value = SettingValue::Text(input.trim().to_string());
+                if save_option(state, selected, value) {
+                    state.option_input = None;
+                }
+            }
+            KeyCode::Esc => {
+                state.option_input = None;
+                state.option_error = None;
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if !c.is_control() => input.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    let current = state.option_values[selected].clone();
+    match state.keymap.pick(key, &["up", "down", "confirm", "close"]) {
+        Some("up") => {
+            state.option_selected = selected.saturating_sub(1);
+            state.option_error = None;
+        }
+        Some("down") => {
+            state.option_selected = (selected + 1).min(SETTINGS.len() - 1);
+            state.option_error = None;
+        }
+        Some("confirm") => match (def.kind, current) {
+            (SettingKind::Text { .. }, SettingValue::Text(text)) => {
+                state.option_input = Some(text);
+                state.option_error = None;
+            }
+            (_, current) => {
+                save_option(state, selected, def.step(&current, true));
+            }
+        },
+        Some(_) => state.page = SettingsPage::Hub,
+        None => match key.code {
+            KeyCode::Left | KeyCode::Char('h') => {
+                save_option(state, selected, def.step(&current, false));
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                save_option(state, selected, def.step(&current, true));
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                save_option(state, selected, def.default_value());
+            }
+            _ => {}
+        },
+    }
+}
+
+fn load_option_values() -> Vec<SettingValue> {
+    SETTINGS
+        .iter()
+        .map(|def| settings_store::get(def.key).unwrap_or_else(|| def.default_value()))
+        .collect()
+}
+
+fn save_option(state: &mut SettingsState, index: usize, value: SettingValue) -> bool {
+    match settings_store::set(SETTINGS[index].key, value.clone()) {
+        Ok(()) => {
+            state.option_values[index] = value;
+            state.option_error = None;
+            true
+        }
+        Err(err) => {
+            state.option_error = Some(err.to_string());
+            false
+        }
+    }
+}
+
+fn minimum_size_hub(keymap: &Keymap) -> (u16, u16) {
     let enter_key = i18n::t("menu.enter_shortcut");
-    let back_hint = i18n::t("settings.hub.back_hint");
+    let back_hint = keymap.hint("settings.hub.back_hint");
 
-    let widths = [
-        UnicodeWidthStr::width(format!("{}[1] {}", TRIANGLE, label_lang).as_str()),
-        UnicodeWidthStr::width(format!("{}[2] {}", TRIANGLE, label_uninstall).as_str()),
-        UnicodeWidthStr::width(format!("{}{} {}", TRIANGLE, enter_key, label_uninstall).as_str()),
-        UnicodeWidthStr::width(back_hint.as_str()),
-    ];
+    let max_width = HUB_ITEMS
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let label = item.label();
+            let normal = format!("{}[{}] {}", TRIANGLE, idx + 1, label);
+            let enter = format!("{}{} {}", TRIANGLE, enter_key, label);
+            UnicodeWidthStr::width(normal.as_str()).max(UnicodeWidthStr::width(enter.as_str()))
+        })
+        .chain([UnicodeWidthStr::width(back_hint.as_str())])
+        .max()
+        .unwrap_or(30) as u16;
+    (max_width + 4, HUB_ITEMS.len() as u16 + 8)
+}
 
-    let max_width = widths.into_iter().max().unwrap_or(30) as u16;
-    (max_width + 4, 10)
+fn minimum_size_options(keymap: &Keymap) -> (u16, u16) {
+    let (label_width, value_width) = option_columns();
+    let hint_width = UnicodeWidthStr::width(keymap.hint("settings.options.hint").as_str());
+    let width = (2 + label_width + 2 + value_width + 4)
+        .max(hint_width)
+        .max(30) as u16;
+    (width + 2, SETTINGS.len() as u16 + 6)
 }
 
-fn minimum_size_language() -> (u16, u16) {
+fn minimum_size_language(keymap: &Keymap) -> (u16, u16) {
     let languages = i18n::available_languages();
     if languages.is_empty() {
         return (40, 8);
@@ -250,7 +424,7 @@ fn minimum_size_language() -> (u16, u16) {
 
     let max_name_width = languages
         .iter()
-        .map(|pack| UnicodeWidthStr::width(pack.name.as_str()))
+        .map(|pack| UnicodeWidthStr::width(language_label(pack).as_str()))
         .max()
         .unwrap_or(4);
 
@@ -261,7 +435,7 @@ fn minimum_size_language() -> (u16, u16) {
 
     let grid_width = cols * outer_width + cols.saturating_sub(1) * H_GAP;
     let grid_height = rows * 3;
-    let hint = i18n::t("confirm_language");
+    let hint = keymap.hint("confirm_language");
     let hint_width = UnicodeWidthStr::width(hint.as_str()) as u16;
 
     let min_w = grid_width.max(hint_width).max(30) + 2;
@@ -269,12 +443,13 @@ fn minimum_size_language() -> (u16, u16) {
     (min_w, min_h.max(10))
 }
 
-fn render_hub(frame: &mut ratatui::Frame<'_>, selected: usize) {
+fn render_hub(frame: &mut ratatui::Frame<'_>, selected: usize, keymap: &Keymap) {
     let area = frame.area();
-    let items = [
-        ("[1]", i18n::t("settings.hub.language")),
-        ("[2]", i18n::t("settings.hub.uninstall")),
-    ];
+    let items: Vec<(String, String)> = HUB_ITEMS
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| (format!("[{}]", idx + 1), item.label()))
+        .collect();
     let enter_hint = i18n::t("menu.enter_shortcut");
 
     let content_width = items
@@ -318,7 +493,7 @@ fn render_hub(frame: &mut ratatui::Frame<'_>, selected: usize) {
         let key = if is_selected {
             enter_hint.as_str()
         } else {
-            shortcut
+            shortcut.as_str()
         };
 
         lines.push(Line::from(vec![
@@ -331,7 +506,7 @@ fn render_hub(frame: &mut ratatui::Frame<'_>, selected: usize) {
 
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
-        i18n::t("settings.hub.back_hint"),
+        keymap.hint("settings.hub.back_hint"),
         Style::default().fg(Color::DarkGray),
     )));
 
@@ -339,7 +514,7 @@ fn render_hub(frame: &mut ratatui::Frame<'_>, selected: usize) {
     frame.render_widget(widget, menu_area);
 }
 
-fn render_language_selector(frame: &mut ratatui::Frame<'_>, selected: usize) {
+fn render_language_selector(frame: &mut ratatui::Frame<'_>, selected: usize, keymap: &Keymap) {
     let area = frame.area();
     let sections = Layout::default()
         .direction(Direction::Vertical)
@@ -363,7 +538,7 @@ fn render_language_selector(frame: &mut ratatui::Frame<'_>, selected: usize) {
     let selected_idx = selected.min(languages.len() - 1);
     let selected_pack = &languages[selected_idx];
     let title = i18n::t_for_code(&selected_pack.code, "language");
-    let hint = i18n::t("confirm_language");
+    let hint = keymap.hint("confirm_language");
 
     let title_widget = Paragraph::new(title)
         .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
@@ -378,6 +553,112 @@ fn render_language_selector(frame: &mut ratatui::Frame<'_>, selected: usize) {
     frame.render_widget(hint_widget, sections[3]);
 }
 
+fn render_options(frame: &mut ratatui::Frame<'_>, state: &SettingsState) {
+    let area = frame.area();
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    let title = Paragraph::new(i18n::t("settings.hub.options"))
+        .style(
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center);
+    frame.render_widget(title, sections[0]);
+
+    let (label_width, value_width) = option_columns();
+    let content_width = 2 + label_width + 2 + value_width + 4;
+    let left_pad = (sections[2].width as usize).saturating_sub(content_width) / 2;
+    let selected = state.option_selected.min(SETTINGS.len() - 1);
+
+    let mut lines = Vec::new();
+    for (idx, def) in SETTINGS.iter().enumerate() {
+        let is_selected = idx == selected;
+        let style = if is_selected {
+            Style::default()
+                .fg(Color::LightCyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let label = def.label();
+        let padding = label_width.saturating_sub(UnicodeWidthStr::width(label.as_str()));
+        let value = match (&state.option_input, state.option_values.get(idx)) {
+            (Some(input), _) if is_selected => format!("{input}_"),
+            (_, Some(value)) if is_selected && !matches!(def.kind, SettingKind::Text { .. }) => {
+                format!("< {} >", def.format_value(value))
+            }
+            (_, Some(value)) => def.format_value(value),
+            (_, None) => String::new(),
+        };
+        lines.push(Line::from(vec![
+            Span::raw(" ".repeat(left_pad)),
+            Span::styled(if is_selected { TRIANGLE } else { "  " }, style),
+            Span::styled(format!("{label}{}  ", " ".repeat(padding)), style),
+            Span::styled(value, Style::default().fg(Color::LightYellow)),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines), sections[2]);
+
+    if let Some(error) = &state.option_error {
+        let error = Paragraph::new(error.as_str())
+            .style(Style::default().fg(Color::LightRed))
+            .alignment(Alignment::Center);
+        frame.render_widget(error, sections[3]);
+    }
+
+    let hint = if state.option_input.is_some() {
+        i18n::t("settings.options.hint.input")
+    } else {
+        state.keymap.hint("settings.options.hint")
+    };
+    let hint_widget = Paragraph::new(Line::from(hint))
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Left);
+    frame.render_widget(hint_widget, sections[4]);
+}
+
+// 标签列和值列的宽度,值列按每个选项最长的取值算
+fn option_columns() -> (usize, usize) {
+    let label_width = SETTINGS
+        .iter()
+        .map(|def| UnicodeWidthStr::width(def.label().as_str()))
+        .max()
+        .unwrap_or(0);
+    let value_width = SETTINGS
+        .iter()
+        .map(|def| match def.kind {
+            SettingKind::Bool { .. } => [true, false]
+                .iter()
+                .map(|on| UnicodeWidthStr::width(&*def.format_value(&SettingValue::Bool(*on))))
+                .max()
+                .unwrap_or(0),
+            SettingKind::Choice { options, .. } => options
+                .iter()
+                .map(|o| {
+                    UnicodeWidthStr::width(&*def.format_value(&SettingValue::Text(o.to_string())))
+                })
+                .max()
+                .unwrap_or(0),
+            SettingKind::Int { min, max, .. } => min.to_string().len().max(max.to_string().len()),
+            SettingKind::Text { max_chars, .. } => (max_chars + 1).max(UnicodeWidthStr::width(
+                &*def.format_value(&SettingValue::Text(String::new())),
+            )),
+        })
+        .max()
+        .unwrap_or(0);
+    (label_width, value_width)
+}
+
 fn draw_language_grid(
     buffer: &mut Buffer,
     area: Rect,
@@ -414,7 +695,7 @@ fn draw_language_grid(
         };
 
         let border_style = Style::default().fg(Color::White);
-        let label = center_text(pack.name.as_str(), metrics.inner_width as usize);
+        let label = center_text(&language_label(pack), metrics.inner_width as usize);
 
         if is_selected {
             let top = format!(
@@ -440,6 +721,10 @@ fn draw_language_grid(
     }
 }
 
+fn language_label(pack: &i18n::LanguagePack) -> String {
+    format!("{} {}%", pack.name, i18n::translation_completeness(pack))
+}
+
 fn center_text(text: &str, width: usize) -> String {
     let current = UnicodeWidthStr::width(text);
     if current >= width {
@@ -0,0 +1,284 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+use crate::app::i18n;
+use crate::utils::path_utils;
+
+/// Type, allowed values and default of one option.
+#[derive(Clone, Copy, Debug)]
+pub enum SettingKind {
+    Bool {
+        default: bool,
+    },
+    Choice {
+        options: &'static [&'static str],
+        default: &'static str,
+    },
+    Int {
+        min: i64,
+        max: i64,
+        step: i64,
+        default: i64,
+    },
+    Text {
+        max_chars: usize,
+        default: &'static str,
+    },
+}
+
+/// A stored option value; choices are stored as text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SettingValue {
+    Bool(bool),
+    Int(i64),
+    Text(String),
+}
+
+/// One entry of the settings registry.
+pub struct SettingDef {
+    pub key: &'static str,
+    pub kind: SettingKind,
+    /// Extra check on top of the type and range of `kind`.
+    pub validate: Option<fn(&SettingValue) -> bool>,
+}
+
+/// Every option of the settings page, in display order.
+pub const SETTINGS: &[SettingDef] = &[
+    SettingDef {
+        key: "player_name",
+        kind: SettingKind::Text {
+            max_chars: 16,
+            default: "",
+        },
+        validate: Some(valid_player_name),
+    },
+    SettingDef {
+        key: "default_sort",
+        kind: SettingKind::Choice {
+            options: &["alphabetical", "most_played", "last_played"],
+            default: "alphabetical",
+        },
+        validate: None,
+    },
+    SettingDef {
+        key: "recent_games",
+        kind: SettingKind::Int {
+            min: 0,
+            max: 10,
+            step: 1,
+            default: 3,
+        },
+        validate: None,
+    },
+    SettingDef {
+        key: "toast_seconds",
+        kind: SettingKind::Int {
+            min: 1,
+            max: 10,
+            step: 1,
+            default: 3,
+        },
+        validate: None,
+    },
+    SettingDef {
+        key: "check_updates",
+        kind: SettingKind::Bool { default: true },
+        validate: None,
+    },
+];
+
+impl SettingDef {
+    /// Returns the translated name, `settings.option.<key>`.
+    pub fn label(&self) -> String {
+        i18n::t(&format!("settings.option.{}", self.key))
+    }
+
+    pub fn default_value(&self) -> SettingValue {
+        match self.kind {
+            SettingKind::Bool { default } => SettingValue::Bool(default),
+            SettingKind::Int { default, .. } => SettingValue::Int(default),
+            SettingKind::Choice { default, .. } | SettingKind::Text { default, .. } => {
+                SettingValue::Text(default.to_string())
+            }
+        }
+    }
+
+    /// Returns whether a value has the right type, is in range and passes the validator.
+    pub fn accepts(&self, value: &SettingValue) -> bool {
+        let fits = match (self.kind, value) {
+            (SettingKind::Bool { .. }, SettingValue::Bool(_)) => true,
+            (SettingKind::Choice { options, .. }, SettingValue::Text(text)) => {
+                options.contains(&text.as_str())
+            }
+            (SettingKind::Int { min, max, .. }, SettingValue::Int(n)) => (min..=max).contains(n),
+            (SettingKind::Text { max_chars, .. }, SettingValue::Text(text)) => {
+                text.chars().count() <= max_chars
+            }
+            _ => false,
+        };
+        fits && self.validate.is_none_or(|check| check(value))
+    }
+
+    /// Formats a value for display; choices use `settings.option.<key>.<value>`.
+    pub fn format_value(&self, value: &SettingValue) -> String {
+        match (self.kind, value) {
+            (_, SettingValue::Bool(true)) => i18n::t("settings.value.on"),
+            (_, SettingValue::Bool(false)) => i18n::t("settings.value.off"),
+            (_, SettingValue::Int(n)) => n.to_string(),
+            (SettingKind::Choice { .. }, SettingValue::Text(text)) => {
+                i18n::t_or(&format!("settings.option.{}.{text}", self.key), text)
+            }
+            (_, SettingValue::Text(text)) if text.is_empty() => i18n::t("settings.value.empty"),
+            (_, SettingValue::Text(text)) => text.clone(),
+        }
+    }
+
+    /// Moves a value one step: bools toggle, choices cycle and integers stay in range.
+    ///
+    /// Text is returned unchanged.
+    pub fn step(&self, value: &SettingValue, forward: bool) -> SettingValue {
+        match (self.kind, value) {
+            (SettingKind::Bool { .. }, SettingValue::Bool(on)) => SettingValue::Bool(!on),
+            (SettingKind::Choice { options, .. }, SettingValue::Text(text)) => {
+                let index = options.iter().position(|o| o == text).unwrap_or(0);
+                let next = if forward {
+                    (index + 1) % options.len()
+                } else {
+                    (index + options.len() - 1) % options.len()
+                };
+                SettingValue::Text(options[next].to_string())
+            }
+            (SettingKind::Int { min, max, step, .. }, SettingValue::Int(n)) => {
+                let delta = if forward { step } else { -step };
+                SettingValue::Int(n.saturating_add(delta).clamp(min, max))
+            }
+            _ => value.clone(),
+        }
+    }
+}
+
+/// Looks up an option by key.
+pub fn find(key: &str) -> Option<&'static SettingDef> {
+    SETTINGS.iter().find(|def| def.key == key)
+}
+
+/// Reads an option of the active profile; unset or invalid values give the default.
+pub fn get(key: &str) -> Option<SettingValue> {
+    let def = find(key)?;
+    let stored = load_file()
+        .remove(key)
+        .and_then(|raw| from_json(&raw))
+        .filter(|value| def.accepts(value));
+    Some(stored.unwrap_or_else(|| def.default_value()))
+}
+
+/// Reads a bool option; unknown keys read as `false`.
+pub fn get_bool(key: &str) -> bool {
+    matches!(get(key), Some(SettingValue::Bool(true)))
+}
+
+/// Reads an integer option; unknown keys read as 0.
+pub fn get_int(key: &str) -> i64 {
+    match get(key) {
+        Some(SettingValue::Int(n)) => n,
+        _ => 0,
+    }
+}
+
+/// Reads a text or choice option; unknown keys read as empty.
+pub fn get_text(key: &str) -> String {
+    match get(key) {
+        Some(SettingValue::Text(text)) => text,
+        _ => String::new(),
+    }
+}
+
+/// Checks and stores an option of the active profile.
+pub fn set(key: &str, value: SettingValue) -> Result<()> {
+    let def = find(key).ok_or_else(|| anyhow!("unknown setting: {key}"))?;
+    if !def.accepts(&value) {
+        return Err(anyhow!(i18n::t_args(
+            "settings.error.invalid",
+            &[("setting", def.label().into())]
+        )));
+    }
+    let mut file = load_file();
+    file.insert(key.to_string(), to_json(&value));
+    let path = settings_file_path();
+    path_utils::ensure_parent_dir(&path)?;
+    fs::write(path, serde_json::to_string_pretty(&file)?)?;
+    Ok(())
+}
+
+// 名字会显示在排行榜上,不允许控制字符和首尾空白
+fn valid_player_name(value: &SettingValue) -> bool {
+    match value {
+        SettingValue::Text(name) => !name.chars().any(char::is_control) && name.trim() == name,
+        _ => false,
+    }
+}
+
+fn to_json(value: &SettingValue) -> Value {
+    match value {
+        SettingValue::Bool(on) => Value::Bool(*on),
+        SettingValue::Int(n) => Value::from(*n),
+        SettingValue::Text(text) => Value::String(text.clone()),
+    }
+}
+
+fn from_json(raw: &Value) -> Option<SettingValue> {
+    match raw {
+        Value::Bool(on) => Some(SettingValue::Bool(*on)),
+        Value::Number(n) => n.as_i64().map(SettingValue::Int),
+        Value::String(text) => Some(SettingValue::Text(text.clone())),
+        _ => None,
+    }
+}
+
+// 未知的键原样保留,旧版本不会删掉新版本的设置
+fn load_file() -> BTreeMap<String, Value> {
+    fs::read_to_string(settings_file_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn settings_file_path() -> PathBuf {
+    match path_utils::settings_file() {
+        Ok(path) => path,
+        Err(_) => PathBuf::from("settings.json"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SettingValue, find};
+
+    #[test]
+    fn values_are_checked_and_stepped_within_their_kind() {
+        let recent = find("recent_games").unwrap();
+        assert!(recent.accepts(&SettingValue::Int(10)));
+        assert!(!recent.accepts(&SettingValue::Int(11)));
+        assert!(!recent.accepts(&SettingValue::Bool(true)));
+        assert_eq!(
+            recent.step(&SettingValue::Int(10), true),
+            SettingValue::Int(10)
+        );
+
+        let sort = find("default_sort").unwrap();
+        assert!(!sort.accepts(&SettingValue::Text("random".to_string())));
+        assert_eq!(
+            sort.step(&SettingValue::Text("alphabetical".to_string()), false),
+            SettingValue::Text("last_played".to_string())
+        );
+
+        let name = find("player_name").unwrap();
+        assert!(name.accepts(&SettingValue::Text("Ada".to_string())));
+        assert!(!name.accepts(&SettingValue::Text(" Ada".to_string())));
+        assert!(!name.accepts(&SettingValue::Text("x".repeat(17))));
+    }
+}
@@ -0,0 +1,245 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crossterm::event::KeyEvent;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Sparkline};
+
+use crate::app::activity;
+use crate::app::history::{self, GameSummary, HistoryEntry};
+use crate::app::i18n;
+use crate::app::keymap::Keymap;
+use crate::app::stats::format_duration;
+use crate::lua_bridge::script_loader::scan_scripts;
+
+const RECENT_SCORES: usize = 40;
+const RECENT_SESSIONS: usize = 5;
+
+pub struct StatisticsPage {
+    rows: Vec<StatRow>,
+    history: Vec<HistoryEntry>,
+    list_state: ListState,
+    keymap: Keymap,
+}
+
+struct StatRow {
+    game_id: String,
+    name: String,
+    summary: GameSummary,
+}
+
+pub enum StatisticsAction {
+    Back,
+}
+
+impl StatisticsPage {
+    /// Creates the statistics page from the recorded play history.
+    pub fn new() -> Self {
+        let history = history::load_history();
+        let names: HashMap<String, String> = scan_scripts()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|game| {
+                let name = i18n::t_or(&format!("game.{}.name", game.id), &game.name);
+                (game.id, name)
+            })
+            .collect();
+
+        let mut rows: Vec<StatRow> = history::summarize(&history, RECENT_SCORES)
+            .into_iter()
+            .map(|(game_id, summary)| StatRow {
+                name: names
+                    .get(&game_id)
+                    .cloned()
+                    .unwrap_or_else(|| game_id.clone()),
+                game_id,
+                summary,
+            })
+            .collect();
+        rows.sort_by_key(|row| Reverse(row.summary.last_played));
+
+        let mut list_state = ListState::default();
+        if !rows.is_empty() {
+            list_state.select(Some(0));
+        }
+        Self {
+            rows,
+            history,
+            list_state,
+            keymap: Keymap::menu(),
+        }
+    }
+
+    /// Handles keyboard events and returns high-level actions.
+    pub fn handle_event(&mut self, key: KeyEvent) -> Option<StatisticsAction> {
+        match self.keymap.pick(key, &["up", "down", "close"]) {
+            Some("up") => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                self.list_state.select(Some(selected.saturating_sub(1)));
+            }
+            Some("down") => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                let last = self.rows.len().saturating_sub(1);
+                self.list_state.select(Some((selected + 1).min(last)));
+            }
+            Some(_) => return Some(StatisticsAction::Back),
+            None => {}
+        }
+        None
+    }
+
+    /// Renders the statistics page.
+    pub fn render(&mut self, frame: &mut ratatui::Frame<'_>, area: Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(area);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                self.keymap.hint("statistics.hint"),
+                Style::default().fg(Color::DarkGray),
+            )),
+            rows[1],
+        );
+
+        if self.rows.is_empty() {
+            let empty = Paragraph::new(i18n::t("statistics.empty"))
+                .alignment(Alignment::Center)
+                .block(page_block(i18n::t("statistics.title")));
+            frame.render_widget(empty, rows[0]);
+            return;
+        }
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(rows[0]);
+
+        let items: Vec<ListItem<'_>> = self
+            .rows
+            .iter()
+            .map(|row| ListItem::new(format!("{} ({})", row.name, row.summary.sessions)))
+            .collect();
+        let list = List::new(items)
+            .block(page_block(i18n::t("statistics.title")))
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::LightBlue));
+        frame.render_stateful_widget(list, columns[0], &mut self.list_state);
+
+        let Some(row) = self
+            .list_state
+            .selected()
+            .and_then(|idx| self.rows.get(idx))
+        else {
+            return;
+        };
+        let detail = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(6)])
+            .split(columns[1]);
+
+        let mut lines = summary_lines(&row.summary);
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            i18n::t("statistics.recent_sessions"),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        lines.extend(
+            self.history
+                .iter()
+                .rev()
+                .filter(|entry| entry.game_id == row.game_id)
+                .take(RECENT_SESSIONS)
+                .map(session_line),
+        );
+        frame.render_widget(
+            Paragraph::new(lines).block(page_block(row.name.clone())),
+            detail[0],
+        );
+
+        let scores = &row.summary.recent_scores;
+        let floor = scores.iter().copied().min().unwrap_or(0).min(0);
+        let data: Vec<u64> = scores.iter().map(|score| (score - floor) as u64).collect();
+        let sparkline = Sparkline::default()
+            .block(page_block(i18n::t("statistics.recent_scores")))
+            .data(&data)
+            .style(Style::default().fg(Color::LightCyan));
+        frame.render_widget(sparkline, detail[1]);
+    }
+
+    /// Returns the minimum terminal size needed for stable layout.
+    pub fn minimum_size(&self) -> (u16, u16) {
+        (70, 20)
+    }
+}
+
+fn page_block(title: String) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_set(symbols::border::DOUBLE)
+        .border_style(Style::default().fg(Color::White))
+        .title(format!(" {title} "))
+}
+
+fn summary_lines(summary: &GameSummary) -> Vec<Line<'static>> {
+    let score = |value: Option<i64>| value.map_or_else(|| "-".to_string(), |v| v.to_string());
+    let average_duration = summary.total_sec / summary.sessions.max(1) as u64;
+    vec![
+        stat_line(
+            "statistics.sessions",
+            i18n::t_args(
+                "statistics.sessions_value",
+                &[
+                    ("total", (summary.sessions as u64).into()),
+                    ("finished", (summary.finished as u64).into()),
+                ],
+            ),
+        ),
+        stat_line("statistics.total_time", format_duration(summary.total_sec)),
+        stat_line("statistics.average_time", format_duration(average_duration)),
+        stat_line(
+            "statistics.average_score",
+            summary
+                .average_score
+                .map_or_else(|| "-".to_string(), |v| format!("{v:.1}")),
+        ),
+        stat_line("statistics.best_score", score(summary.best_score)),
+        stat_line("statistics.worst_score", score(summary.worst_score)),
+        stat_line(
+            "statistics.last_played",
+            summary
+                .last_played
+                .map_or_else(|| "-".to_string(), activity::format_last_played),
+        ),
+    ]
+}
+
+fn stat_line(key: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("{} ", i18n::t(key)),
+            Style::default().fg(Color::Gray),
+        ),
+        Span::styled(value, Style::default().fg(Color::White)),
+    ])
+}
+
+fn session_line(entry: &HistoryEntry) -> Line<'static> {
+    let mut parts = vec![
+        activity::format_last_played(entry.started_at),
+        format_duration(entry.duration_sec),
+    ];
+    if let Some(mode) = &entry.mode {
+        parts.push(mode.clone());
+    }
+    if let Some(score) = entry.score {
+        parts.push(score.to_string());
+    }
+    parts.push(entry.outcome.label());
+    Line::from(Span::styled(
+        format!("  {}", parts.join(" · ")),
+        Style::default().fg(Color::Gray),
+    ))
+}
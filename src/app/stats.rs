@@ -1,10 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::PathBuf;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use serde_json::Value as JsonValue;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::app::i18n;
 use crate::utils::path_utils;
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
@@ -13,61 +15,148 @@ pub struct GameStats {
     pub max_duration_sec: u64,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct LightsOutBest {
-    pub max_size: usize,
-    pub min_steps: u64,
-    pub min_time_sec: u64,
+/// Record name that mirrors `GameStats::high_score`.
+pub const HIGH_SCORE_RECORD: &str = "high_score";
+/// Record name that mirrors `GameStats::max_duration_sec`.
+pub const LONGEST_PLAY_RECORD: &str = "longest_play";
+
+/// Best values of one game: record name -> dimension value -> best value.
+///
+/// Records without a dimension are stored under the empty string.
+pub type GameRecords = BTreeMap<String, BTreeMap<String, i64>>;
+
+/// What a record value measures, which decides how it is displayed.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordUnit {
+    #[default]
+    Score,
+    Seconds,
+    Moves,
+    Size,
+}
+
+impl RecordUnit {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "score" => Some(Self::Score),
+            "seconds" => Some(Self::Seconds),
+            "moves" => Some(Self::Moves),
+            "size" => Some(Self::Size),
+            _ => None,
+        }
+    }
+
+    /// Formats a value measured in this unit.
+    pub fn format(self, value: i64) -> String {
+        match self {
+            RecordUnit::Seconds => format_duration(value.max(0) as u64),
+            RecordUnit::Size => format!("{value}x{value}"),
+            RecordUnit::Moves => i18n::t_args("stats.unit.moves", &[("count", value.into())]),
+            RecordUnit::Score => value.to_string(),
+        }
+    }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct MemoryFlipBest {
-    pub difficulty: usize,
-    pub min_steps: u64,
-    pub min_time_sec: u64,
+/// Which direction of a record value counts as an improvement.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordBetter {
+    #[default]
+    Higher,
+    Lower,
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
-pub struct MinesweeperBest {
-    pub d1_min_time_sec: Option<u64>,
-    pub d2_min_time_sec: Option<u64>,
-    pub d3_min_time_sec: Option<u64>,
+impl RecordBetter {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "higher" => Some(Self::Higher),
+            "lower" => Some(Self::Lower),
+            _ => None,
+        }
+    }
+
+    /// Returns whether `value` strictly improves on `best`.
+    pub fn is_better(self, value: i64, best: i64) -> bool {
+        match self {
+            RecordBetter::Higher => value > best,
+            RecordBetter::Lower => value < best,
+        }
+    }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct MazeEscapeBest {
-    pub max_area: usize,
-    pub max_cols: usize,
-    pub max_rows: usize,
-    pub max_mode: usize,
-    pub min_time_sec: Option<u64>,
+/// One record a game declares in `GAME_META.records` or its manifest.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct RecordSpec {
+    pub name: String,
+    pub label: Option<String>,
+    pub unit: RecordUnit,
+    pub better: RecordBetter,
+    pub dimension: Option<String>,
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
-pub struct SolitaireBest {
-    pub freecell_min_time_sec: Option<u64>,
-    pub klondike_min_time_sec: Option<u64>,
-    pub spider_min_time_sec: Option<u64>,
+impl RecordSpec {
+    /// Records shown for games that declare none: best score and longest session.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self {
+                name: HIGH_SCORE_RECORD.to_string(),
+                ..Self::default()
+            },
+            Self {
+                name: LONGEST_PLAY_RECORD.to_string(),
+                unit: RecordUnit::Seconds,
+                ..Self::default()
+            },
+        ]
+    }
+
+    /// Returns the translated label, defaulting to `game.<id>.record.<name>`.
+    pub fn label(&self, game_id: &str) -> String {
+        let key = match (&self.label, self.name.as_str()) {
+            (Some(key), _) => key.clone(),
+            (None, HIGH_SCORE_RECORD) => "game_selection.label.high_score".to_string(),
+            (None, LONGEST_PLAY_RECORD) => "game_selection.label.longest_play".to_string(),
+            (None, name) => format!("game.{game_id}.record.{name}"),
+        };
+        i18n::t_or(&key, &self.name)
+    }
+
+    /// Returns the translated text of one dimension value.
+    pub fn dimension_label(&self, game_id: &str, value: &str) -> String {
+        match &self.dimension {
+            Some(dimension) => dimension_label(game_id, dimension, value),
+            None => value.to_string(),
+        }
+    }
+
+    /// Formats a value according to the record unit.
+    pub fn format_value(&self, value: i64) -> String {
+        self.unit.format(value)
+    }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct SudokuBest {
-    pub difficulty: usize,
-    pub min_time_sec: u64,
+/// Returns the translated text of one value of a game's dimension, such as a difficulty.
+///
+/// Looks up `game.<id>.<dimension>.<value>` first and falls back to the
+/// dimension name followed by the raw value.
+pub fn dimension_label(game_id: &str, dimension: &str, value: &str) -> String {
+    let name = i18n::t_or(&format!("game.{game_id}.{dimension}"), dimension);
+    i18n::t_or(
+        &format!("game.{game_id}.{dimension}.{value}"),
+        &format!("{name} {value}"),
+    )
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 struct StatsFile {
     #[serde(default)]
     games: HashMap<String, GameStats>,
-}
-
-/// Loads per-game stats from local config file.
-pub fn load_stats() -> HashMap<String, GameStats> {
-    match load_stats_inner() {
-        Ok(map) => map,
-        Err(_) => HashMap::new(),
-    }
+    #[serde(default)]
+    records: HashMap<String, GameRecords>,
+    #[serde(default)]
+    legacy_bests_migrated: bool,
 }
 
 /// Updates per-game stats using max(high_score) and max(max_duration_sec).
@@ -91,230 +180,311 @@ pub fn update_game_stats(game_id: &str, score: u32, duration_sec: u64) -> Result
     Ok(())
 }
 
-fn load_stats_inner() -> Result<HashMap<String, GameStats>> {
+/// Removes the stats entry of one game, returning whether it existed.
+pub fn remove_game_stats(game_id: &str) -> Result<bool> {
     let path = stats_file_path();
     if !path.exists() {
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        fs::write(&path, "{\n  \"games\": {}\n}\n")?;
-        return Ok(HashMap::new());
+        return Ok(false);
     }
 
-    let content = fs::read_to_string(path)?;
-    let parsed: StatsFile = serde_json::from_str(&content).unwrap_or_default();
-    Ok(parsed.games)
-}
+    let content = fs::read_to_string(&path)?;
+    let mut parsed: StatsFile = serde_json::from_str(&content).unwrap_or_default();
+    let before = parsed.games.len() + parsed.records.len();
+    parsed.games.retain(|id, _| !id.eq_ignore_ascii_case(game_id));
+    parsed.records.retain(|id, _| !id.eq_ignore_ascii_case(game_id));
+    if parsed.games.len() + parsed.records.len() == before {
+        return Ok(false);
+    }
 
-/// Formats duration seconds into HH:MM:SS.
-pub fn format_duration(seconds: u64) -> String {
-    let h = seconds / 3600;
-    let m = (seconds % 3600) / 60;
-    let s = seconds % 60;
-    format!("{h:02}:{m:02}:{s:02}")
+    let payload = serde_json::to_string_pretty(&parsed)?;
+    fs::write(path, payload)?;
+    Ok(true)
 }
 
-/// Loads lights_out best record from shared Lua save file.
-pub fn load_lights_out_best() -> Option<LightsOutBest> {
-    let path = lua_saves_file_path();
-    if !path.exists() {
-        return None;
+/// Loads the declared records of all games.
+///
+/// `high_score` and `longest_play` are filled from the plain stats so games
+/// that only call `update_game_stats` show them too.
+pub fn load_records() -> HashMap<String, GameRecords> {
+    let Ok(mut parsed) = read_stats_file() else {
+        return HashMap::new();
+    };
+    for (game_id, stats) in parsed.games {
+        let records = parsed.records.entry(game_id).or_default();
+        if stats.high_score > 0 {
+            records
+                .entry(HIGH_SCORE_RECORD.to_string())
+                .or_default()
+                .insert(String::new(), i64::from(stats.high_score));
+        }
+        if stats.max_duration_sec > 0 {
+            records
+                .entry(LONGEST_PLAY_RECORD.to_string())
+                .or_default()
+                .insert(String::new(), stats.max_duration_sec as i64);
+        }
     }
+    parsed.records
+}
 
-    let raw = fs::read_to_string(path).ok()?;
-    let root = serde_json::from_str::<JsonValue>(&raw).ok()?;
-    let object = root.as_object()?;
-    let best = object.get("lights_out_best")?.as_object()?;
-
-    let max_size = best.get("max_size")?.as_u64()? as usize;
-    let min_steps = best.get("min_steps")?.as_u64()?;
-    let min_time_sec = best.get("min_time_sec")?.as_u64()?;
+/// Stores a record value if it beats the current best, returning whether it did.
+///
+/// `high_score` and `longest_play` update the plain stats instead.
+pub fn submit_record(
+    game_id: &str,
+    spec: &RecordSpec,
+    dimension: Option<&str>,
+    value: i64,
+) -> Result<bool> {
+    let mut parsed = read_stats_file()?;
+    let improved = match spec.name.as_str() {
+        HIGH_SCORE_RECORD => {
+            let entry = parsed.games.entry(game_id.to_string()).or_default();
+            let score = value.clamp(0, i64::from(u32::MAX)) as u32;
+            let improved = score > entry.high_score;
+            entry.high_score = entry.high_score.max(score);
+            improved
+        }
+        LONGEST_PLAY_RECORD => {
+            let entry = parsed.games.entry(game_id.to_string()).or_default();
+            let duration = value.max(0) as u64;
+            let improved = duration > entry.max_duration_sec;
+            entry.max_duration_sec = entry.max_duration_sec.max(duration);
+            improved
+        }
+        name => {
+            let values = parsed
+                .records
+                .entry(game_id.to_string())
+                .or_default()
+                .entry(name.to_string())
+                .or_default();
+            let dimension = dimension.unwrap_or_default().to_string();
+            match values.get(&dimension) {
+                Some(best) if !spec.better.is_better(value, *best) => false,
+                _ => {
+                    values.insert(dimension, value);
+                    true
+                }
+            }
+        }
+    };
 
-    Some(LightsOutBest {
-        max_size,
-        min_steps,
-        min_time_sec,
-    })
+    if improved {
+        let path = stats_file_path();
+        path_utils::ensure_parent_dir(&path)?;
+        fs::write(path, serde_json::to_string_pretty(&parsed)?)?;
+    }
+    Ok(improved)
 }
 
-/// Loads memory_flip best record from shared Lua save file.
-pub fn load_memory_flip_best() -> Option<MemoryFlipBest> {
-    let path = lua_saves_file_path();
-    if !path.exists() {
-        return None;
+/// Merges an imported stats file into the current one, keeping the best of each value.
+///
+/// `better` tells which direction counts as an improvement for a game's record.
+/// Returns the merged content and how many values it changes.
+pub fn merge_stats(
+    current: &str,
+    incoming: &str,
+    better: impl Fn(&str, &str) -> RecordBetter,
+) -> Result<(String, usize)> {
+    let mut merged: StatsFile = serde_json::from_str(current).unwrap_or_default();
+    let incoming: StatsFile = serde_json::from_str(incoming)?;
+    let mut changes = 0;
+    for (game_id, stats) in incoming.games {
+        let entry = merged.games.entry(game_id).or_default();
+        if stats.high_score > entry.high_score || stats.max_duration_sec > entry.max_duration_sec {
+            entry.high_score = entry.high_score.max(stats.high_score);
+            entry.max_duration_sec = entry.max_duration_sec.max(stats.max_duration_sec);
+            changes += 1;
+        }
     }
-
-    let raw = fs::read_to_string(path).ok()?;
-    let root = serde_json::from_str::<JsonValue>(&raw).ok()?;
-    let object = root.as_object()?;
-    let best = object.get("memory_flip_best")?.as_object()?;
-
-    let difficulty = best.get("difficulty")?.as_u64()? as usize;
-    let min_steps = best.get("min_steps")?.as_u64()?;
-    let min_time_sec = best.get("min_time_sec")?.as_u64()?;
-
-    Some(MemoryFlipBest {
-        difficulty,
-        min_steps,
-        min_time_sec,
-    })
+    for (game_id, records) in incoming.records {
+        for (name, values) in records {
+            let direction = better(&game_id, &name);
+            let best_values = merged
+                .records
+                .entry(game_id.clone())
+                .or_default()
+                .entry(name)
+                .or_default();
+            for (dimension, value) in values {
+                match best_values.get(&dimension) {
+                    Some(best) if !direction.is_better(value, *best) => {}
+                    _ => {
+                        best_values.insert(dimension, value);
+                        changes += 1;
+                    }
+                }
+            }
+        }
+    }
+    Ok((serde_json::to_string_pretty(&merged)?, changes))
 }
 
-/// Loads minesweeper best record (official difficulties) from shared Lua save file.
-pub fn load_minesweeper_best() -> Option<MinesweeperBest> {
-    let path = lua_saves_file_path();
-    if !path.exists() {
-        return None;
+/// Copies the best values older versions kept in `lua_saves.json` into the declared records.
+///
+/// Runs once per profile; afterwards the detail panel shows those bests even
+/// before the game is launched again.
+pub fn migrate_legacy_bests() -> Result<()> {
+    let mut parsed = read_stats_file()?;
+    if parsed.legacy_bests_migrated {
+        return Ok(());
     }
+    let saves_path = path_utils::lua_saves_file()?;
+    if !saves_path.exists() {
+        return Ok(());
+    }
+    let saves: Map<String, JsonValue> =
+        serde_json::from_str(&fs::read_to_string(saves_path)?).unwrap_or_default();
+    apply_legacy_bests(&mut parsed, &saves);
+    parsed.legacy_bests_migrated = true;
 
-    let raw = fs::read_to_string(path).ok()?;
-    let root = serde_json::from_str::<JsonValue>(&raw).ok()?;
-    let object = root.as_object()?;
-    let best = object.get("minesweeper_best")?.as_object()?;
-
-    Some(MinesweeperBest {
-        d1_min_time_sec: best.get("1").and_then(JsonValue::as_u64),
-        d2_min_time_sec: best.get("2").and_then(JsonValue::as_u64),
-        d3_min_time_sec: best.get("3").and_then(JsonValue::as_u64),
-    })
+    let path = stats_file_path();
+    path_utils::ensure_parent_dir(&path)?;
+    fs::write(path, serde_json::to_string_pretty(&parsed)?)?;
+    Ok(())
 }
 
-/// Loads maze_escape best record from shared Lua save file.
-pub fn load_maze_escape_best() -> Option<MazeEscapeBest> {
-    let path = lua_saves_file_path();
-    if !path.exists() {
-        return None;
+// 旧版本由宿主读取的最佳记录,按脚本现在提交的记录名和维度写进records,已有更好的值时保留
+fn apply_legacy_bests(parsed: &mut StatsFile, saves: &Map<String, JsonValue>) {
+    for (game_id, name, better, dimension, value) in legacy_bests(saves) {
+        let values = parsed
+            .records
+            .entry(game_id.to_string())
+            .or_default()
+            .entry(name.to_string())
+            .or_default();
+        match values.get(&dimension) {
+            Some(best) if !better.is_better(value, *best) => {}
+            _ => {
+                values.insert(dimension, value);
+            }
+        }
     }
-
-    let raw = fs::read_to_string(path).ok()?;
-    let root = serde_json::from_str::<JsonValue>(&raw).ok()?;
-    let object = root.as_object()?;
-    let best = object.get("maze_escape_best")?.as_object()?;
-
-    let max_area = best.get("max_area").and_then(JsonValue::as_u64)? as usize;
-    let max_cols = best.get("max_cols").and_then(JsonValue::as_u64).unwrap_or(0) as usize;
-    let max_rows = best.get("max_rows").and_then(JsonValue::as_u64).unwrap_or(0) as usize;
-    let max_mode = best.get("max_mode").and_then(JsonValue::as_u64)? as usize;
-    let min_time_sec = best.get("min_time_sec").and_then(JsonValue::as_u64);
-
-    Some(MazeEscapeBest {
-        max_area,
-        max_cols,
-        max_rows,
-        max_mode,
-        min_time_sec,
-    })
 }
 
-/// Loads solitaire best records (FreeCell/Klondike/Spider) from shared Lua save file.
-pub fn load_solitaire_best() -> Option<SolitaireBest> {
-    let path = lua_saves_file_path();
-    if !path.exists() {
-        return None;
+// 从旧存档里取出各游戏的最佳记录: (游戏, 记录名, 比较方向, 维度, 值)
+// 键名和字段和被移除的load_*_best一致
+fn legacy_bests(
+    saves: &Map<String, JsonValue>,
+) -> Vec<(&'static str, &'static str, RecordBetter, String, i64)> {
+    use RecordBetter::{Higher, Lower};
+
+    let field = |best: &JsonValue, names: &[&str]| -> Option<i64> {
+        names
+            .iter()
+            .find_map(|name| best.get(*name).and_then(JsonValue::as_i64))
+            .filter(|value| *value > 0)
+    };
+    let mut out = Vec::new();
+
+    if let Some(best) = saves.get("lights_out_best")
+        && let Some(size) = field(best, &["max_size"])
+    {
+        let dimension = format!("{size}x{size}");
+        out.push(("lights_out", "max_size", Higher, String::new(), size));
+        if let Some(steps) = field(best, &["min_steps"]) {
+            out.push(("lights_out", "min_steps", Lower, dimension.clone(), steps));
+        }
+        if let Some(time) = field(best, &["min_time_sec"]) {
+            out.push(("lights_out", "min_time", Lower, dimension, time));
+        }
     }
 
-    let raw = fs::read_to_string(path).ok()?;
-    let root = serde_json::from_str::<JsonValue>(&raw).ok()?;
-    let object = root.as_object()?;
-
-    // New format: solitaire_best_v2
-    if let Some(best) = object.get("solitaire_best_v2").and_then(JsonValue::as_object) {
-        let freecell = best.get("freecell").and_then(JsonValue::as_u64);
-        let klondike = best.get("klondike").and_then(JsonValue::as_u64);
-        let spider1 = best.get("spider1").and_then(JsonValue::as_u64);
-        let spider2 = best.get("spider2").and_then(JsonValue::as_u64);
-        let spider3 = best.get("spider3").and_then(JsonValue::as_u64);
-        let spider = [spider1, spider2, spider3]
-            .into_iter()
-            .flatten()
-            .filter(|v| *v > 0)
-            .min();
-
-        return Some(SolitaireBest {
-            freecell_min_time_sec: freecell.filter(|v| *v > 0),
-            klondike_min_time_sec: klondike.filter(|v| *v > 0),
-            spider_min_time_sec: spider,
-        });
+    if let Some(best) = saves.get("memory_flip_best")
+        && let Some(difficulty) = field(best, &["difficulty"])
+    {
+        let dimension = difficulty.to_string();
+        out.push((
+            "memory_flip",
+            "max_difficulty",
+            Higher,
+            String::new(),
+            difficulty,
+        ));
+        if let Some(steps) = field(best, &["min_steps"]) {
+            out.push(("memory_flip", "min_steps", Lower, dimension.clone(), steps));
+        }
+        if let Some(time) = field(best, &["min_time_sec"]) {
+            out.push(("memory_flip", "min_time", Lower, dimension, time));
+        }
     }
 
-    // Legacy format fallback: solitaire_best
-    if let Some(best) = object.get("solitaire_best").and_then(JsonValue::as_object) {
-        let freecell = best
-            .get("freecell")
-            .and_then(JsonValue::as_u64)
-            .or_else(|| best.get("foundation").and_then(JsonValue::as_u64));
-        let klondike = best
-            .get("klondike")
-            .and_then(JsonValue::as_u64)
-            .or_else(|| best.get("tableau").and_then(JsonValue::as_u64));
-        let spider = best.get("spider").and_then(JsonValue::as_u64);
-
-        return Some(SolitaireBest {
-            freecell_min_time_sec: freecell.filter(|v| *v > 0),
-            klondike_min_time_sec: klondike.filter(|v| *v > 0),
-            spider_min_time_sec: spider.filter(|v| *v > 0),
-        });
+    if let Some(best) = saves.get("minesweeper_best") {
+        for difficulty in ["1", "2", "3"] {
+            if let Some(time) = field(best, &[difficulty]) {
+                out.push((
+                    "minesweeper",
+                    "best_time",
+                    Lower,
+                    difficulty.to_string(),
+                    time,
+                ));
+            }
+        }
     }
 
-    None
-}
-
-/// Loads sudoku best record from shared Lua save file.
-pub fn load_sudoku_best() -> Option<SudokuBest> {
-    let path = lua_saves_file_path();
-    if !path.exists() {
-        return None;
+    if let Some(best) = saves.get("maze_escape_best")
+        && field(best, &["max_area"]).is_some()
+    {
+        if let Some(mode) = field(best, &["max_mode"]) {
+            out.push(("maze_escape", "max_mode", Higher, String::new(), mode));
+        }
+        if let Some(time) = field(best, &["min_time_sec"]) {
+            out.push(("maze_escape", "fastest", Lower, String::new(), time));
+        }
     }
 
-    let raw = fs::read_to_string(path).ok()?;
-    let root = serde_json::from_str::<JsonValue>(&raw).ok()?;
-    let object = root.as_object()?;
-    let best = object.get("sudoku_best")?.as_object()?;
-
-    let difficulty = best
-        .get("difficulty")
-        .and_then(JsonValue::as_u64)
-        .or_else(|| best.get("d").and_then(JsonValue::as_u64))? as usize;
-    let min_time_sec = best
-        .get("min_time_sec")
-        .and_then(JsonValue::as_u64)
-        .or_else(|| best.get("t").and_then(JsonValue::as_u64))?;
-
-    if !(1..=5).contains(&difficulty) || min_time_sec == 0 {
-        return None;
+    if let Some(best) = saves.get("solitaire_best_v2") {
+        for mode in ["freecell", "klondike", "spider1", "spider2", "spider3"] {
+            if let Some(time) = field(best, &[mode]) {
+                out.push(("solitaire", "best_time", Lower, mode.to_string(), time));
+            }
+        }
+    } else if let Some(best) = saves.get("solitaire_best") {
+        // 最早的格式没有记蜘蛛纸牌的花色数,对不上现在的维度,只迁移另外两种
+        let modes = [("freecell", "foundation"), ("klondike", "tableau")];
+        for (mode, old_name) in modes {
+            if let Some(time) = field(best, &[mode, old_name]) {
+                out.push(("solitaire", "best_time", Lower, mode.to_string(), time));
+            }
+        }
     }
 
-    Some(SudokuBest {
-        difficulty,
-        min_time_sec,
-    })
-}
-
-/// Loads 24-points best time from shared Lua save file.
-pub fn load_twenty_four_best_time() -> Option<u64> {
-    let path = lua_saves_file_path();
-    if !path.exists() {
-        return None;
+    if let Some(best) = saves.get("sudoku_best")
+        && let Some(difficulty) = field(best, &["difficulty", "d"]).filter(|d| *d <= 5)
+        && let Some(time) = field(best, &["min_time_sec", "t"])
+    {
+        out.push(("sudoku", "best_time", Lower, difficulty.to_string(), time));
     }
 
-    let raw = fs::read_to_string(path).ok()?;
-    let root = serde_json::from_str::<JsonValue>(&raw).ok()?;
-    let object = root.as_object()?;
-    let best = object.get("twenty_four_best_time")?;
-
-    if let Some(sec) = best.as_u64() {
-        return (sec > 0).then_some(sec);
+    if let Some(best) = saves.get("twenty_four_best_time") {
+        let time = best
+            .as_i64()
+            .filter(|value| *value > 0)
+            .or_else(|| field(best, &["time_sec", "best_time_sec"]));
+        if let Some(time) = time {
+            out.push(("twenty_four", "best_time", Lower, String::new(), time));
+        }
     }
+    out
+}
 
-    if let Some(best_obj) = best.as_object() {
-        let sec = best_obj
-            .get("time_sec")
-            .and_then(JsonValue::as_u64)
-            .or_else(|| best_obj.get("best_time_sec").and_then(JsonValue::as_u64))?;
-        return (sec > 0).then_some(sec);
+fn read_stats_file() -> Result<StatsFile> {
+    let path = stats_file_path();
+    if !path.exists() {
+        return Ok(StatsFile::default());
     }
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
 
-    None
+/// Formats duration seconds into HH:MM:SS.
+pub fn format_duration(seconds: u64) -> String {
+    let h = seconds / 3600;
+    let m = (seconds % 3600) / 60;
+    let s = seconds % 60;
+    format!("{h:02}:{m:02}:{s:02}")
 }
 
 fn stats_file_path() -> PathBuf {
@@ -324,20 +494,55 @@ fn stats_file_path() -> PathBuf {
     }
 }
 
-fn lua_saves_file_path() -> PathBuf {
-    match path_utils::lua_saves_file() {
-        Ok(path) => path,
-        Err(_) => PathBuf::from("lua_saves.json"),
-    }
-}
-
 #[cfg(test)]
 mod tests {
-    use super::format_duration;
+    use super::{RecordBetter, StatsFile, apply_legacy_bests, format_duration, merge_stats};
 
     #[test]
     fn format_duration_works() {
         assert_eq!(format_duration(1250), "00:20:50");
         assert_eq!(format_duration(3661), "01:01:01");
     }
+
+    #[test]
+    fn merge_stats_keeps_best_values() {
+        let current = r#"{"games":{"tetris":{"high_score":500,"max_duration_sec":90}},
+            "records":{"minesweeper":{"best_time":{"easy":40,"hard":300}}}}"#;
+        let incoming = r#"{"games":{"tetris":{"high_score":300,"max_duration_sec":120}},
+            "records":{"minesweeper":{"best_time":{"easy":35,"hard":310,"medium":99}}}}"#;
+        let (merged, changes) = merge_stats(current, incoming, |_, _| RecordBetter::Lower).unwrap();
+        let merged: serde_json::Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(changes, 3);
+        assert_eq!(merged["games"]["tetris"]["high_score"], 500);
+        assert_eq!(merged["games"]["tetris"]["max_duration_sec"], 120);
+        assert_eq!(merged["records"]["minesweeper"]["best_time"]["easy"], 35);
+        assert_eq!(merged["records"]["minesweeper"]["best_time"]["hard"], 300);
+        assert_eq!(merged["records"]["minesweeper"]["best_time"]["medium"], 99);
+    }
+
+    #[test]
+    fn legacy_bests_move_into_declared_records() {
+        let saves = serde_json::json!({
+            "lights_out_best": { "max_size": 6, "min_steps": 14, "min_time_sec": 75 },
+            "minesweeper_best": { "1": 42, "3": 0 },
+            "solitaire_best_v2": { "freecell": 300, "klondike": 0, "spider2": 900 },
+            "sudoku_best": { "d": 4, "t": 610 },
+            "twenty_four_best_time": { "time_sec": 9 }
+        });
+        let mut parsed: StatsFile =
+            serde_json::from_str(r#"{"records":{"sudoku":{"best_time":{"4":500}}}}"#).unwrap();
+        apply_legacy_bests(&mut parsed, saves.as_object().unwrap());
+
+        let records = &parsed.records;
+        assert_eq!(records["lights_out"]["max_size"][""], 6);
+        assert_eq!(records["lights_out"]["min_steps"]["6x6"], 14);
+        assert_eq!(records["lights_out"]["min_time"]["6x6"], 75);
+        assert_eq!(records["minesweeper"]["best_time"].len(), 1);
+        assert_eq!(records["solitaire"]["best_time"]["freecell"], 300);
+        assert_eq!(records["solitaire"]["best_time"]["spider2"], 900);
+        assert!(!records["solitaire"]["best_time"].contains_key("klondike"));
+        // 已有更好的成绩时不被旧值覆盖
+        assert_eq!(records["sudoku"]["best_time"]["4"], 500);
+        assert_eq!(records["twenty_four"]["best_time"][""], 9);
+    }
 }
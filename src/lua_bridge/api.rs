@@ -1,29 +1,180 @@
 ﻿use std::collections::BTreeMap;
+use std::collections::VecDeque;
 use std::fs;
 use std::io::{Stdout, Write, stdout};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Mutex, MutexGuard};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Result, anyhow};
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
 use crossterm::queue;
-use crossterm::style::{
-    Color as CColor, Print, ResetColor, SetBackgroundColor, SetForegroundColor,
-};
-use mlua::{Function, Lua, Table, Value};
+use crossterm::style::{Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use mlua::{Lua, Table, Value};
 use once_cell::sync::Lazy;
+use ratatui::backend::IntoCrossterm;
+use ratatui::style::Color;
 use serde_json::{Map, Number, Value as JsonValue};
-use unicode_width::UnicodeWidthStr;
-
-use crate::app::{i18n, stats};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::app::achievements::{self, AchievementSpec};
+use crate::app::history::SessionOutcome;
+use crate::app::rich_text;
+use crate::app::i18n::{self, I18nArg};
+use crate::app::keymap::{self, Keymap};
+use crate::app::leaderboard::{self, LeaderboardEntry};
+use crate::app::settings_store::{self, SettingValue};
+use crate::app::stats::{self, RecordSpec};
+use crate::lua_bridge::compat;
+use crate::lua_bridge::script_loader::GameMeta;
+use crate::lua_bridge::text_commands::{LuaCommands, load_text_functions};
+use crate::terminal::renderer;
 use crate::utils::path_utils;
 
-const EXIT_GAME_SENTINEL: &str = "__TUI_GAME_EXIT__"; // 游戏退出标记
 static OUT: Lazy<Mutex<Stdout>> = Lazy::new(|| Mutex::new(stdout())); // 终端输出的全局锁
 static TERMINAL_DIRTY_FROM_LUA: AtomicBool = AtomicBool::new(false); // Lua 是否修改了终端
 static RNG_STATE: AtomicU64 = AtomicU64::new(0); // 随机数生成器状态
+static SESSION_SCORE: Mutex<Option<i64>> = Mutex::new(None); // 本局脚本上报过的最高分
+static TOAST: Mutex<ToastState> = Mutex::new(ToastState::new()); // 成就解锁提示
+static PLAY_CLOCK: Mutex<PlayClock> = Mutex::new(PlayClock::new()); // 本局实际游玩的时长
+static CURRENT_GAME: Mutex<Option<String>> = Mutex::new(None); // 正在运行的游戏,save_data按它记录键的归属
+
+// 右上角的提示框,排队一条一条显示
+struct ToastState {
+    queue: VecDeque<String>,
+    current: Option<(String, Instant)>, // 正在显示的文本和消失时间
+    drawn: Option<(u16, u16)>,          // 上次画出的位置x和宽度,消失时要擦掉
+}
+
+impl ToastState {
+    const fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            current: None,
+            drawn: None,
+        }
+    }
+}
+
+// 宿主自己计的游玩时长,暂停和尺寸警告期间不计
+// 终端比游戏需要的最小尺寸小时由宿主自己暂停,不依赖脚本调用set_game_paused
+struct PlayClock {
+    played: Duration,
+    running_since: Option<Instant>, // 暂停时为None
+    paused: bool,                   // 游戏自己的暂停,以及宿主弹出的输入框
+    too_small: bool,                // 终端小于min_size
+    declared_size: (u16, u16),      // GAME_META.min_size,没有声明时为0
+    min_size: (u16, u16),           // 当前要求的最小尺寸,set_min_size只能调大
+}
+
+impl PlayClock {
+    const fn new() -> Self {
+        Self {
+            played: Duration::ZERO,
+            running_since: None,
+            paused: true,
+            too_small: false,
+            declared_size: (0, 0),
+            min_size: (0, 0),
+        }
+    }
+
+    // 返回之前是否处于暂停
+    fn set_paused(&mut self, paused: bool) -> bool {
+        let was_paused = self.paused;
+        self.paused = paused;
+        self.refresh();
+        was_paused
+    }
+
+    // 脚本按当前布局报告的最小尺寸,不会低于声明的尺寸
+    fn set_min_size(&mut self, width: u16, height: u16) {
+        self.min_size = (
+            width.max(self.declared_size.0),
+            height.max(self.declared_size.1),
+        );
+    }
+
+    // 按终端当前的尺寸决定是否计时
+    fn check_size(&mut self, width: u16, height: u16) {
+        self.too_small = width < self.min_size.0 || height < self.min_size.1;
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let running = !self.paused && !self.too_small;
+        match (running, self.running_since) {
+            (false, Some(since)) => {
+                self.played += since.elapsed();
+                self.running_since = None;
+            }
+            (true, None) => self.running_since = Some(Instant::now()),
+            _ => {}
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.played + self.running_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+}
+
+// 一局游戏的结局,由exit_game报告或者宿主推断,交给main.rs统一记录
+#[derive(Clone, Debug)]
+pub struct GameOutcome {
+    pub outcome: SessionOutcome,
+    pub score: Option<i64>,
+    pub mode: Option<String>,
+    pub started_at: u64,
+    pub duration_sec: u64,
+    pub error: Option<String>, // 脚本出错时的错误信息
+}
+
+// exit_game抛出的错误,带着脚本报告的结局一路传回run_game_script
+#[derive(Clone, Debug, Default)]
+struct GameExit {
+    outcome: Option<SessionOutcome>,
+    score: Option<i64>,
+    mode: Option<String>,
+}
+
+impl GameExit {
+    // exit_game({outcome = "won", score = 100, mode = "hard"}),三项都可以省略
+    fn from_table(table: &Table) -> mlua::Result<Self> {
+        let outcome = match table.get::<Option<String>>("outcome")? {
+            Some(raw) => Some(SessionOutcome::parse(&raw).ok_or_else(|| {
+                mlua::Error::RuntimeError(format!("exit_game: unknown outcome '{raw}'"))
+            })?),
+            None => None,
+        };
+        Ok(Self {
+            outcome,
+            score: table
+                .get::<Option<f64>>("score")?
+                .map(|score| score.round() as i64),
+            mode: table.get("mode")?,
+        })
+    }
+}
+
+impl std::fmt::Display for GameExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "game exited")
+    }
+}
+
+impl std::error::Error for GameExit {}
+
+// draw_text_ex的参数: x, y, 文本, 前景色, 背景色, 最大宽度, 对齐方式
+type DrawTextExArgs = (
+    i64,
+    i64,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+    Option<String>,
+);
 
 // 启动游戏模式的枚举
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -34,7 +185,7 @@ pub enum LaunchMode {
 
 //
 impl LaunchMode {
-    fn as_str(self) -> &'static str {
+    pub fn as_str(self) -> &'static str {
         match self {
             Self::New => "new",
             Self::Continue => "continue",
@@ -44,28 +195,7 @@ impl LaunchMode {
 
 // 将API注册，让Lua可调用
 pub fn register_api(lua: &Lua, mode: LaunchMode) -> mlua::Result<()> {
-    let get_key = lua.create_function(|_, blocking: bool| {
-        flush_output()?;
-
-        if blocking {
-            loop {
-                if let Event::Key(key) = event::read().map_err(mlua::Error::external)? {
-                    if key.kind == KeyEventKind::Press {
-                        return decode_key_event(key);
-                    }
-                }
-            }
-        }
-
-        if event::poll(Duration::from_millis(0)).map_err(mlua::Error::external)? {
-            if let Event::Key(key) = event::read().map_err(mlua::Error::external)? {
-                if key.kind == KeyEventKind::Press {
-                    return decode_key_event(key);
-                }
-            }
-        }
-        Ok(String::new())
-    })?;
+    let get_key = lua.create_function(|_, blocking: bool| read_key(blocking))?;
     lua.globals().set("get_key", get_key)?;
 
     let clear = lua.create_function(|_, ()| {
@@ -88,40 +218,31 @@ pub fn register_api(lua: &Lua, mode: LaunchMode) -> mlua::Result<()> {
     lua.globals().set("draw_text", draw_text)?;
 
     let draw_text_ex = lua.create_function(
-        |lua,
-         (x, y, text, fg, bg, max_width, align): (
-            i64,
-            i64,
-            String,
-            Option<String>,
-            Option<String>,
-            Option<i64>,
-            Option<String>,
-        )| {
-            let width = max_width.unwrap_or(text.len() as i64).max(0) as usize;
-            let mut rendered = text.clone();
-            if width > 0 {
-                let w = UnicodeWidthStr::width(text.as_str());
-                if w < width {
-                    let pad = width - w;
-                    match align.unwrap_or_else(|| "left".to_string()).as_str() {
-                        "center" => {
-                            let left = pad / 2;
-                            let right = pad - left;
-                            rendered = format!("{}{}{}", " ".repeat(left), text, " ".repeat(right));
-                        }
-                        "right" => rendered = format!("{}{}", " ".repeat(pad), text),
-                        _ => {}
-                    }
-                }
-            }
-            draw_text_rich_impl(lua, x, y, &rendered, fg.as_deref(), bg.as_deref())
+        |lua, (x, y, text, fg, bg, max_width, align): DrawTextExArgs| {
+            draw_text_ex_impl(
+                lua,
+                (x, y),
+                &text,
+                fg.as_deref(),
+                bg.as_deref(),
+                max_width,
+                TextAlign::parse(align.as_deref()),
+            )
         },
     )?;
     lua.globals().set("draw_text_ex", draw_text_ex)?;
 
+    let draw_text_box = lua.create_function(
+        |lua, (x, y, w, h, text, opts): (i64, i64, i64, i64, String, Option<Table>)| {
+            let opts = TextBoxOptions::from_table(opts.as_ref());
+            draw_text_box_impl(lua, (x, y, w, h), &text, &opts)
+        },
+    )?;
+    lua.globals().set("draw_text_box", draw_text_box)?;
+
     let sleep = lua.create_function(|_, ms: i64| {
         flush_output()?;
+        track_terminal_size();
         let ms = ms.max(0) as u64;
         std::thread::sleep(Duration::from_millis(ms));
         if ms >= 200 {
@@ -146,13 +267,38 @@ pub fn register_api(lua: &Lua, mode: LaunchMode) -> mlua::Result<()> {
     })?;
     lua.globals().set("random", random)?;
 
-    let exit_game = lua.create_function(|_, ()| -> mlua::Result<()> {
-        Err(mlua::Error::RuntimeError(EXIT_GAME_SENTINEL.to_string()))
+    let exit_game = lua.create_function(|_, result: Option<Table>| -> mlua::Result<()> {
+        let exit = match result {
+            Some(table) => GameExit::from_table(&table)?,
+            None => GameExit::default(),
+        };
+        Err(mlua::Error::external(exit))
     })?;
     lua.globals().set("exit_game", exit_game)?;
 
-    let translate = lua.create_function(|_, key: String| Ok(i18n::t(&key)))?;
-    lua.globals().set("translate", translate)?;
+    register_translate(lua)?;
+
+    let engine_version =
+        lua.create_function(|_, ()| Ok(compat::engine_version().to_string()))?;
+    lua.globals().set("engine_version", engine_version)?;
+
+    let api_level = lua.create_function(|_, ()| Ok(compat::API_LEVEL))?;
+    lua.globals().set("api_level", api_level)?;
+
+    let has_capability =
+        lua.create_function(|_, name: String| Ok(compat::has_capability(&name)))?;
+    lua.globals().set("has_capability", has_capability)?;
+
+    // 读取玩家的设置项,没有这个设置时返回nil
+    let get_setting = lua.create_function(|lua, key: String| {
+        Ok(match settings_store::get(&key) {
+            Some(SettingValue::Bool(on)) => Value::Boolean(on),
+            Some(SettingValue::Int(n)) => Value::Integer(n),
+            Some(SettingValue::Text(text)) => Value::String(lua.create_string(&text)?),
+            None => Value::Nil,
+        })
+    })?;
+    lua.globals().set("get_setting", get_setting)?;
 
     let get_terminal_size = lua.create_function(|_, ()| {
         let (w, h) = crossterm::terminal::size().map_err(mlua::Error::external)?;
@@ -161,19 +307,49 @@ pub fn register_api(lua: &Lua, mode: LaunchMode) -> mlua::Result<()> {
     lua.globals().set("get_terminal_size", get_terminal_size)?;
 
     let get_text_width =
-        lua.create_function(|_, text: String| Ok(UnicodeWidthStr::width(text.as_str()) as i64))?;
+        lua.create_function(|lua, text: String| Ok(measure_text_impl(lua, &text).0 as i64))?;
     lua.globals().set("get_text_width", get_text_width)?;
 
+    let measure_text = lua.create_function(|lua, text: String| {
+        let (width, height) = measure_text_impl(lua, &text);
+        Ok((width as i64, height as i64))
+    })?;
+    lua.globals().set("measure_text", measure_text)?;
+
+    // 按wrap_text的规则换行,富文本按可见字符换行
+    // 富文本的每一行都是独立的富文本,颜色写成tc/bg指令,可以直接交给draw_text
+    let wrap_text = lua.create_function(|lua, (text, width): (String, i64)| {
+        let width = width.max(0) as usize;
+        if !rich_text::is_rich_text(&text) {
+            return lua.create_sequence_from(renderer::wrap_text(&text, width));
+        }
+        let cells = styled_cells(lua, &text, None, None);
+        lua.create_sequence_from(
+            renderer::wrap_cells(&cells, width)
+                .iter()
+                .map(|line| cells_to_rich_text(line)),
+        )
+    })?;
+    lua.globals().set("wrap_text", wrap_text)?;
+
     let get_launch_mode = lua.create_function(move |_, ()| Ok(mode.as_str().to_string()))?;
     lua.globals().set("get_launch_mode", get_launch_mode)?;
 
     let save_data = lua.create_function(|_, (key, value): (String, Value)| {
+        if is_host_key(&key) {
+            return Ok(false);
+        }
         save_lua_data(&key, &value)?;
         Ok(true)
     })?;
     lua.globals().set("save_data", save_data)?;
 
-    let load_data = lua.create_function(|lua, key: String| load_lua_data(lua, &key))?;
+    let load_data = lua.create_function(|lua, key: String| {
+        if is_host_key(&key) {
+            return Ok(Value::Nil);
+        }
+        load_lua_data(lua, &key)
+    })?;
     lua.globals().set("load_data", load_data)?;
 
     let save_game_slot = lua.create_function(|_, (game_id, value): (String, Value)| {
@@ -190,17 +366,76 @@ pub fn register_api(lua: &Lua, mode: LaunchMode) -> mlua::Result<()> {
         lua.create_function(|_, (game_id, score, duration_sec): (String, i64, i64)| {
             let score_u32 = score.max(0).min(u32::MAX as i64) as u32;
             let duration_u64 = duration_sec.max(0) as u64;
+            if let Ok(mut best) = SESSION_SCORE.lock() {
+                *best = Some(best.map_or(score, |best| best.max(score)));
+            }
             stats::update_game_stats(&game_id, score_u32, duration_u64)
                 .map_err(mlua::Error::external)?;
             Ok(true)
         })?;
     lua.globals().set("update_game_stats", update_game_stats)?;
 
+    // 游戏自己暂停时调用set_game_paused(true),这段时间不计入游玩时长
+    // 终端太小的时间宿主会自己扣掉,不需要为尺寸警告调用
+    let set_game_paused = lua.create_function(|_, paused: bool| {
+        set_play_clock_paused(paused);
+        Ok(())
+    })?;
+    lua.globals().set("set_game_paused", set_game_paused)?;
+
+    // 布局随模式或棋盘大小变化的游戏用set_min_size报告当前需要的尺寸
+    let set_min_size = lua.create_function(|_, (width, height): (i64, i64)| {
+        if let Ok(mut clock) = PLAY_CLOCK.lock() {
+            clock.set_min_size(
+                width.clamp(0, i64::from(u16::MAX)) as u16,
+                height.clamp(0, i64::from(u16::MAX)) as u16,
+            );
+        }
+        track_terminal_size();
+        Ok(())
+    })?;
+    lua.globals().set("set_min_size", set_min_size)?;
+
     Ok(())
 }
 
+// 注册translate(key, args)
+// args是可选的参数表,例如translate("game.x.moves", {count = 5})
+// 数字参数可以配合{count:number}、{time:duration}格式化
+pub fn register_translate(lua: &Lua) -> mlua::Result<()> {
+    let translate = lua.create_function(|_, (key, args): (String, Option<Table>)| {
+        let Some(args) = args else {
+            return Ok(i18n::t(&key));
+        };
+
+        let mut values = Vec::new();
+        for pair in args.pairs::<String, Value>() {
+            let (name, value) = pair?;
+            let value = match value {
+                Value::Integer(n) => I18nArg::from(n),
+                Value::Number(n) => I18nArg::from(n),
+                Value::String(s) => I18nArg::from(s.to_str()?.to_string()),
+                Value::Boolean(b) => I18nArg::from(b.to_string()),
+                _ => continue,
+            };
+            values.push((name, value));
+        }
+        Ok(i18n::t_args(&key, &values))
+    })?;
+    lua.globals().set("translate", translate)
+}
+
 // 启动游戏脚本，并处理程序控制权
-pub fn run_game_script(script_path: &Path, mode: LaunchMode) -> Result<()> {
+pub fn run_game_script(game: &GameMeta, mode: LaunchMode) -> Result<GameOutcome> {
+    // 不兼容的游戏在碰终端之前就拒绝
+    if let Some(block) = compat::launch_block(game) {
+        return Err(anyhow!(i18n::t_args(
+            "game_selection.blocked.refused",
+            &[("game", game.name.as_str().into()), ("reason", block.message().into())],
+        )));
+    }
+
+    let script_path = game.script_path.as_path();
     drain_input_events();
     let source = fs::read_to_string(script_path)?;
     let source = source.trim_start_matches('\u{feff}');
@@ -208,20 +443,412 @@ pub fn run_game_script(script_path: &Path, mode: LaunchMode) -> Result<()> {
     register_api(&lua, mode).map_err(|e| anyhow!("Lua API registration error: {e}"))?;
     load_text_functions(&lua, script_path)
         .map_err(|e| anyhow!("Lua text command registration error: {e}"))?;
+    register_asset_path(&lua, game)
+        .map_err(|e| anyhow!("Lua API registration error: {e}"))?;
+    register_records(&lua, game).map_err(|e| anyhow!("Lua API registration error: {e}"))?;
+    register_achievements(&lua, game)
+        .map_err(|e| anyhow!("Lua API registration error: {e}"))?;
+    register_leaderboard(&lua, game).map_err(|e| anyhow!("Lua API registration error: {e}"))?;
+    register_actions(&lua, game).map_err(|e| anyhow!("Lua API registration error: {e}"))?;
+    if let Ok(mut toast) = TOAST.lock() {
+        *toast = ToastState::new();
+    }
 
-    let result = match lua
+    if let Ok(mut best) = SESSION_SCORE.lock() {
+        *best = None;
+    }
+    if let Ok(mut current) = CURRENT_GAME.lock() {
+        *current = Some(game.id.trim().to_ascii_lowercase());
+    }
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if let Ok(mut clock) = PLAY_CLOCK.lock() {
+        *clock = PlayClock::new();
+        if let Some(size) = game.min_size {
+            clock.declared_size = (size.width, size.height);
+            clock.set_min_size(size.width, size.height);
+        }
+        clock.set_paused(false);
+    }
+    track_terminal_size();
+
+    let (exit, error) = match lua
         .load(source)
         .set_name(script_path.to_string_lossy())
         .exec()
     {
-        Ok(()) => Ok(()),
-        Err(err) if err.to_string().contains(EXIT_GAME_SENTINEL) => Ok(()),
-        Err(err) => Err(anyhow!("Lua runtime error: {err}")),
+        Ok(()) => (GameExit::default(), None),
+        Err(err) => match game_exit(&err) {
+            Some(exit) => (exit.clone(), None),
+            None => (GameExit::default(), Some(format!("Lua runtime error: {err}"))),
+        },
     };
 
     finalize_terminal_after_script();
     TERMINAL_DIRTY_FROM_LUA.store(true, Ordering::Release);
-    result
+    if let Ok(mut current) = CURRENT_GAME.lock() {
+        *current = None;
+    }
+
+    // exit_game报告的结局优先,没有报告时上报过分数的算打完了一局
+    let score = exit
+        .score
+        .or_else(|| SESSION_SCORE.lock().ok().and_then(|best| *best));
+    let outcome = match (&error, exit.outcome, score) {
+        (Some(_), _, _) => SessionOutcome::Error,
+        (None, Some(outcome), _) => outcome,
+        (None, None, Some(_)) => SessionOutcome::Finished,
+        (None, None, None) => SessionOutcome::Quit,
+    };
+    Ok(GameOutcome {
+        outcome,
+        score,
+        mode: exit.mode,
+        started_at,
+        duration_sec: play_clock_secs(),
+        error,
+    })
+}
+
+// 从错误链里找exit_game抛出的退出,只认类型不认错误文本
+fn game_exit(err: &mlua::Error) -> Option<&GameExit> {
+    err.chain().find_map(|cause| cause.downcast_ref::<GameExit>())
+}
+
+// 注册asset_path(name)
+// 资源相对游戏包目录查找,单文件脚本相对脚本所在目录,找不到就原样返回,兼容老的相对路径写法
+// 绝对路径和跑出这个目录的路径直接报错
+fn register_asset_path(lua: &Lua, game: &GameMeta) -> mlua::Result<()> {
+    let base = match &game.package_dir {
+        Some(dir) => dir.clone(),
+        None => game
+            .script_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    let asset_path = lua.create_function(move |_, name: String| {
+        let Some(candidate) = path_utils::join_inside(&base, &name) else {
+            return Err(mlua::Error::external(format!(
+                "asset_path: path outside the game directory: {name}"
+            )));
+        };
+        if candidate.exists() {
+            Ok(candidate.to_string_lossy().into_owned())
+        } else {
+            Ok(name)
+        }
+    })?;
+    lua.globals().set("asset_path", asset_path)
+}
+
+// 注册submit_record(name, value, dimension)
+// 只能提交GAME_META.records里声明过的记录,没声明时是默认的high_score和longest_play
+// 刷新了最好成绩返回true
+fn register_records(lua: &Lua, game: &GameMeta) -> mlua::Result<()> {
+    let game_id = game.id.clone();
+    let specs = game.records.clone().unwrap_or_else(RecordSpec::defaults);
+    let submit_record = lua.create_function(
+        move |_, (name, value, dimension): (String, f64, Option<String>)| {
+            let spec = specs.iter().find(|spec| spec.name == name).ok_or_else(|| {
+                mlua::Error::runtime(format!("record '{name}' is not declared in GAME_META.records"))
+            })?;
+            stats::submit_record(&game_id, spec, dimension.as_deref(), value.round() as i64)
+                .map_err(mlua::Error::external)
+        },
+    )?;
+    lua.globals().set("submit_record", submit_record)
+}
+
+// 注册unlock_achievement(id)和add_achievement_progress(id, amount)
+// 只能用GAME_META.achievements里声明过的id,刚解锁时返回true并在右上角提示
+fn register_achievements(lua: &Lua, game: &GameMeta) -> mlua::Result<()> {
+    let game_id = game.id.clone();
+    let specs = game.achievements.clone();
+    let unlock_achievement = lua.create_function(move |_, id: String| {
+        let spec = find_achievement(&specs, &id)?;
+        let unlocked = achievements::unlock(&game_id, spec).map_err(mlua::Error::external)?;
+        if unlocked {
+            push_achievement_toast(&game_id, spec);
+        }
+        Ok(unlocked)
+    })?;
+    lua.globals().set("unlock_achievement", unlock_achievement)?;
+
+    let game_id = game.id.clone();
+    let specs = game.achievements.clone();
+    let add_achievement_progress =
+        lua.create_function(move |_, (id, amount): (String, Option<i64>)| {
+            let spec = find_achievement(&specs, &id)?;
+            let amount = amount.unwrap_or(1).max(0) as u64;
+            let unlocked =
+                achievements::add_progress(&game_id, spec, amount).map_err(mlua::Error::external)?;
+            if unlocked {
+                push_achievement_toast(&game_id, spec);
+            }
+            Ok(unlocked)
+        })?;
+    lua.globals()
+        .set("add_achievement_progress", add_achievement_progress)
+}
+
+fn find_achievement<'a>(specs: &'a [AchievementSpec], id: &str) -> mlua::Result<&'a AchievementSpec> {
+    specs.iter().find(|spec| spec.id == id).ok_or_else(|| {
+        mlua::Error::runtime(format!("achievement '{id}' is not declared in GAME_META.achievements"))
+    })
+}
+
+fn push_achievement_toast(game_id: &str, spec: &AchievementSpec) {
+    let text = i18n::t_args(
+        "achievements.toast",
+        &[("title", spec.title(game_id).into())],
+    );
+    if let Ok(mut toast) = TOAST.lock() {
+        toast.queue.push_back(text);
+    }
+}
+
+// 注册get_action(blocking)、get_action_key(blocking, raw)和action_keys(name)
+// 按玩家的键位把按键换成GAME_META.actions里的动作,键位在启动时读取
+fn register_actions(lua: &Lua, game: &GameMeta) -> mlua::Result<()> {
+    let keymap = Keymap::load(&game.id, &game.actions);
+
+    // 返回按键所绑动作的第一个默认键和原始按键,按默认键写的脚本不用改逻辑
+    // raw为true时(确认框、输入数字)第一个值也是原始按键
+    let bindings = keymap.clone();
+    let actions = game.actions.clone();
+    let get_action_key = lua.create_function(move |_, (blocking, raw): (bool, Option<bool>)| {
+        let key = read_key(blocking)?;
+        let mapped = if raw.unwrap_or(false) {
+            key.clone()
+        } else {
+            bindings.default_key(&key, &actions)
+        };
+        Ok((mapped, key))
+    })?;
+    lua.globals().set("get_action_key", get_action_key)?;
+
+    let bindings = keymap.clone();
+    let get_action = lua.create_function(move |_, blocking: bool| {
+        let key = read_key(blocking)?;
+        let action = bindings.action(&key).unwrap_or_default().to_string();
+        Ok((action, key))
+    })?;
+    lua.globals().set("get_action", get_action)?;
+
+    let action_keys = lua.create_function(move |_, name: String| Ok(keymap.describe(&name)))?;
+    lua.globals().set("action_keys", action_keys)
+}
+
+// 注册leaderboard_qualifies(result)和submit_leaderboard(result)
+// result是{score=, time=, mode=, seed=, name=},time默认是游戏开始到现在的秒数
+// submit_leaderboard能上榜时弹出输入名字的框,返回名次,没上榜或者放弃输入返回nil
+fn register_leaderboard(lua: &Lua, game: &GameMeta) -> mlua::Result<()> {
+    let game_id = game.id.clone();
+    let spec = game.leaderboard.clone().unwrap_or_default();
+    let leaderboard_qualifies = lua.create_function(move |_, table: Table| {
+        let result = LeaderboardResult::from_table(&table)?;
+        Ok(leaderboard::qualifying_rank(
+            &game_id,
+            &spec,
+            &result.mode,
+            result.score,
+            result.duration_sec,
+        )
+        .is_some())
+    })?;
+    lua.globals()
+        .set("leaderboard_qualifies", leaderboard_qualifies)?;
+
+    let game_id = game.id.clone();
+    let spec = game.leaderboard.clone().unwrap_or_default();
+    let submit_leaderboard = lua.create_function(move |_, table: Table| {
+        let result = LeaderboardResult::from_table(&table)?;
+        let Some(rank) = leaderboard::qualifying_rank(
+            &game_id,
+            &spec,
+            &result.mode,
+            result.score,
+            result.duration_sec,
+        ) else {
+            return Ok(None);
+        };
+        let name = match result.name {
+            Some(name) => name,
+            None => {
+                // 优先用设置里的玩家名字,其次非默认档案直接用档案名
+                let player_name = settings_store::get_text("player_name");
+                let profile = path_utils::active_profile();
+                let default = if !player_name.is_empty() {
+                    player_name
+                } else if profile == path_utils::DEFAULT_PROFILE {
+                    leaderboard::last_player_name().unwrap_or_default()
+                } else {
+                    profile
+                };
+                let title =
+                    i18n::t_args("leaderboard.prompt_title", &[("rank", (rank as u64).into())]);
+                match prompt_player_name(&title, &default)? {
+                    Some(name) => name,
+                    None => return Ok(None),
+                }
+            }
+        };
+        let entry = LeaderboardEntry {
+            name,
+            score: result.score,
+            duration_sec: result.duration_sec,
+            date: 0,
+            seed: result.seed,
+        };
+        leaderboard::submit(&game_id, &spec, &result.mode, entry).map_err(mlua::Error::external)
+    })?;
+    lua.globals().set("submit_leaderboard", submit_leaderboard)
+}
+
+// 排行榜接口的参数
+struct LeaderboardResult {
+    score: i64,
+    duration_sec: u64,
+    mode: String,
+    seed: Option<String>,
+    name: Option<String>,
+}
+
+impl LeaderboardResult {
+    fn from_table(table: &Table) -> mlua::Result<Self> {
+        let score: f64 = table.get("score")?;
+        let duration_sec = match table.get::<Option<f64>>("time")? {
+            Some(time) => time.max(0.0).round() as u64,
+            None => play_clock_secs(),
+        };
+        let name = table
+            .get::<Option<String>>("name")?
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+        Ok(Self {
+            score: score.round() as i64,
+            duration_sec,
+            mode: table.get::<Option<String>>("mode")?.unwrap_or_default(),
+            seed: table.get::<Option<String>>("seed")?,
+            name,
+        })
+    }
+}
+
+// 在屏幕中间弹出输入玩家名字的框
+// Enter确认,Esc放弃,关闭后清屏,游戏需要自己重画
+fn prompt_player_name(title: &str, default: &str) -> mlua::Result<Option<String>> {
+    const MAX_NAME_WIDTH: usize = 16;
+    let mut name = default.to_string();
+    // 输入名字的时间不算游玩时长
+    let was_paused = set_play_clock_paused(true);
+    drain_input_events();
+    let result = loop {
+        draw_name_prompt(title, &name, MAX_NAME_WIDTH)?;
+        let Event::Key(key) = event::read().map_err(mlua::Error::external)? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter if !name.trim().is_empty() => break Some(name.trim().to_string()),
+            KeyCode::Esc => break None,
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Char(c)
+                if !c.is_control() && name.width() + c.width().unwrap_or(0) <= MAX_NAME_WIDTH =>
+            {
+                name.push(c);
+            }
+            _ => {}
+        }
+    };
+    set_play_clock_paused(was_paused);
+
+    let mut out = lock_out()?;
+    queue!(
+        out,
+        ResetColor,
+        crossterm::terminal::Clear(crossterm::terminal::ClearType::All)
+    )
+    .map_err(mlua::Error::external)?;
+    out.flush().map_err(mlua::Error::external)?;
+    Ok(result)
+}
+
+fn draw_name_prompt(title: &str, name: &str, max_width: usize) -> mlua::Result<()> {
+    let hint = i18n::t("leaderboard.prompt_hint");
+    let label = i18n::t("leaderboard.prompt_name");
+    let input = format!("{label} {name}_");
+    let inner = title
+        .width()
+        .max(hint.width())
+        .max(label.width() + max_width + 2);
+    let pad = |text: &str| format!(" {text}{} ", " ".repeat(inner - text.width()));
+    let border = "─".repeat(inner + 2);
+    let lines = [
+        format!("┌{border}┐"),
+        format!("│{}│", pad(title)),
+        format!("│{}│", pad("")),
+        format!("│{}│", pad(&input)),
+        format!("│{}│", pad("")),
+        format!("│{}│", pad(&hint)),
+        format!("└{border}┘"),
+    ];
+
+    let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+    let x = cols.saturating_sub(inner as u16 + 4) / 2;
+    let y = rows.saturating_sub(lines.len() as u16) / 2;
+    {
+        let mut out = lock_out()?;
+        for (row, line) in lines.iter().enumerate() {
+            queue!(
+                out,
+                crossterm::cursor::MoveTo(x, y + row as u16),
+                SetForegroundColor(Color::White.into_crossterm()),
+                SetBackgroundColor(Color::Black.into_crossterm()),
+                Print(line),
+                ResetColor
+            )
+            .map_err(mlua::Error::external)?;
+        }
+    }
+    flush_output()
+}
+
+// 暂停或继续本局计时,返回之前是否处于暂停
+fn set_play_clock_paused(paused: bool) -> bool {
+    PLAY_CLOCK
+        .lock()
+        .map(|mut clock| clock.set_paused(paused))
+        .unwrap_or(paused)
+}
+
+// 对照终端的实际尺寸暂停或继续本局计时
+// 每次读按键和sleep时检查,脚本不配合也能扣掉尺寸警告的时间
+fn track_terminal_size() {
+    if let Ok((width, height)) = crossterm::terminal::size() {
+        track_size(width, height);
+    }
+}
+
+fn track_size(width: u16, height: u16) {
+    if let Ok(mut clock) = PLAY_CLOCK.lock() {
+        clock.check_size(width, height);
+    }
+}
+
+// 本局到现在的游玩秒数
+fn play_clock_secs() -> u64 {
+    PLAY_CLOCK
+        .lock()
+        .map(|clock| clock.elapsed().as_secs())
+        .unwrap_or(0)
 }
 
 // 检查这段时间Lua是否对终端有输入行为
@@ -239,10 +866,10 @@ pub fn latest_saved_game_id() -> Option<String> {
         }
     }
     for key in store.keys() {
-        if let Some(id) = key.strip_prefix("game:") {
-            if !id.trim().is_empty() {
-                return Some(id.to_string());
-            }
+        if let Some(id) = key.strip_prefix("game:")
+            && !id.trim().is_empty()
+        {
+            return Some(id.to_string());
         }
     }
     None
@@ -257,134 +884,72 @@ pub fn clear_active_game_save() -> Result<()> {
     write_json_store(&store).map_err(|e| anyhow!("failed to write lua save store after clear: {e}"))
 }
 
-// 富文本块结构体
-#[derive(Clone, Debug)]
-struct StyledChunk {
-    text: String,
-    fg: Option<String>, // 前景色名称
-    bg: Option<String>, // 背景色名称
+// 删除某个游戏留下的全部数据
+// 包括存档槽位和以"<id>_"开头的键,返回删掉的键数量
+pub fn clear_game_data(game_id: &str, installed_ids: &[String]) -> Result<usize> {
+    let mut store = load_json_store()
+        .map_err(|e| anyhow!("failed to load lua save store for clearing: {e}"))?;
+    let removed = remove_game_keys(&mut store, game_id, installed_ids);
+    write_json_store(&store)
+        .map_err(|e| anyhow!("failed to write lua save store after clear: {e}"))?;
+    Ok(removed)
 }
 
-// 富文本样式结构体状态机
-#[derive(Clone, Debug)]
-struct RichStyleState {
-    default_fg: Option<String>, // 默认前景色（从draw_text参数传入）
-    default_bg: Option<String>, // 默认背景色（从draw_text参数传入）
-    fg: Option<String>,         // 当前前景色
-    bg: Option<String>,         // 当前背景色
-    fg_count: Option<usize>,    // 前景色剩余生效字符数
-    bg_count: Option<usize>,    // 背景色剩余生效字符数
-    fg_need_clear: bool,        // 是否需要自动清除前景色（当count为None时）
-    bg_need_clear: bool,        // 是否需要自动清除背景色（当count为None时）
-}
-
-// 富文本命令返回结果结构体
-#[derive(Clone, Debug)]
-struct TextCommandResult {
-    clear: bool,           // true=清除当前颜色，false=设置新颜色
-    color: Option<String>, // 要设置的颜色名称
-    count: Option<usize>,  // 颜色生效的字符数（None表示无限）
-}
-
-fn rich_text_error(key: &str) -> String {
-    i18n::t(key).to_string()
-}
-
-// 加载并注册所有文本命令函数
-fn load_text_functions(lua: &Lua, script_path: &Path) -> mlua::Result<()> {
-    // 获取Lua的全局环境
-    let globals = lua.globals();
-    // 检查是否存在TEXT_COMMANDS表
-    if globals.get::<Table>("TEXT_COMMANDS").is_err() {
-        // 不存在就创建空表
-        globals.set("TEXT_COMMANDS", lua.create_table()?)?;
-    }
-
-    // 给Lua注册函数，用于添加自定文本命令
-    let register = lua.create_function(|lua, (name, func): (String, Function)| {
-        let globals = lua.globals();
-        // 获取 TEXT_COMMANDS 表
-        let table = match globals.get::<Table>("TEXT_COMMANDS") {
-            Ok(t) => t,
-            Err(_) => {
-                let t = lua.create_table()?;
-                globals.set("TEXT_COMMANDS", t.clone())?;
-                t
-            }
-        };
-        // 将函数存入表中
-        table.set(name.trim().to_ascii_lowercase(), func)?;
-        Ok(true)
-    })?;
-    globals.set("register_text_command", register)?;
-
-    // 构建搜索路径
-    let mut dirs = Vec::<PathBuf>::new();
-    if let Some(parent) = script_path.parent() {
-        dirs.push(parent.join("text_function"));
-        if parent.file_name().and_then(|s| s.to_str()) == Some("game") {
-            if let Some(root) = parent.parent() {
-                dirs.push(root.join("text_function"));
-            }
-        }
-    }
-    if let Ok(scripts_dir) = path_utils::scripts_dir() {
-        dirs.push(scripts_dir.join("text_function"));
-    }
+// 删掉属于这个游戏的键,返回删掉的数量
+// 有归属记录的键只看记录;没有记录的旧键按"id_"前缀认领,
+// 但前缀同时是另一个已安装游戏的"id_"时让给那个更长的id,避免solitaire删掉solitaire_x的存档
+fn remove_game_keys(
+    store: &mut Map<String, JsonValue>,
+    game_id: &str,
+    installed_ids: &[String],
+) -> usize {
+    let game_id = game_id.trim().to_ascii_lowercase();
+    let prefix = format!("{game_id}_");
+    let slot = game_slot_key(&game_id);
+    let longer_prefixes: Vec<String> = installed_ids
+        .iter()
+        .map(|id| id.trim().to_ascii_lowercase())
+        .filter(|id| id.starts_with(&prefix))
+        .map(|id| format!("{id}_"))
+        .collect();
+    let mut owners = match store.remove(KEY_OWNERS) {
+        Some(JsonValue::Object(owners)) => owners,
+        _ => Map::new(),
+    };
 
-    // 移除重复的目录路径
-    let mut unique_dirs = Vec::<PathBuf>::new();
-    for dir in dirs {
-        if !unique_dirs.iter().any(|d| d == &dir) {
-            unique_dirs.push(dir);
+    let owned_by_game = |key: &str| -> bool {
+        if let Some(owner) = owners.get(key).and_then(JsonValue::as_str) {
+            return owner.eq_ignore_ascii_case(&game_id);
         }
+        let key = key.to_ascii_lowercase();
+        key == slot
+            || key == game_id
+            || (key.starts_with(&prefix) && !longer_prefixes.iter().any(|p| key.starts_with(p)))
+    };
+    let doomed: Vec<String> = store
+        .keys()
+        .filter(|key| owned_by_game(key))
+        .cloned()
+        .collect();
+    for key in &doomed {
+        store.remove(key);
+        owners.remove(key);
     }
-
-    // 加载所有Lua文件
-    let mut loaded_any = false;
-    // 遍历
-    for dir in unique_dirs {
-        // 不存在就跳过
-        if !dir.exists() || !dir.is_dir() {
-            continue;
-        }
-
-        // 过滤lua文件并排序
-        let mut entries: Vec<PathBuf> = fs::read_dir(&dir)
-            .map_err(mlua::Error::external)?
-            .filter_map(|entry| entry.ok().map(|e| e.path()))
-            .filter(|path| {
-                path.extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| ext.eq_ignore_ascii_case("lua"))
-                    .unwrap_or(false)
-            })
-            .collect();
-        entries.sort();
-
-        // 逐个加载文件并执行代码
-        for file in entries {
-            let source = fs::read_to_string(&file).map_err(mlua::Error::external)?;
-            let source = source.trim_start_matches('\u{feff}');
-            lua.load(source)
-                .set_name(file.to_string_lossy().as_ref())
-                .exec()?;
-            loaded_any = true;
-        }
+    // 最近存档指向这个游戏时一起清掉,否则主菜单还会显示继续
+    if let Some(JsonValue::String(id)) = store.get("__latest_save_game")
+        && id.eq_ignore_ascii_case(&game_id)
+    {
+        store.remove("__latest_save_game");
     }
-
-    // 如果没有加载任何文件，确保TEXT_COMMANDS表存在，保证没有富文本指令也可以渲染
-    if !loaded_any {
-        let globals = lua.globals();
-        if globals.get::<Table>("TEXT_COMMANDS").is_err() {
-            globals.set("TEXT_COMMANDS", lua.create_table()?)?;
-        }
+    if !owners.is_empty() {
+        store.insert(KEY_OWNERS.to_string(), JsonValue::Object(owners));
     }
-
-    Ok(())
+    doomed.len()
 }
 
-// 富文本解析核心函数
+// 富文本绘制入口
+// 解析交给统一的富文本模块,自定义指令从当前Lua环境的TEXT_COMMANDS中查找
+// 文本中的换行会从同一个x坐标开始画下一行
 fn draw_text_rich_impl(
     lua: &Lua,
     x: i64,
@@ -393,528 +958,278 @@ fn draw_text_rich_impl(
     fg: Option<&str>,
     bg: Option<&str>,
 ) -> mlua::Result<()> {
-    // 不是f%开头的走普通渲染
-    if !text.starts_with("f%") {
+    let fg = fg.and_then(rich_text::parse_color);
+    let bg = bg.and_then(rich_text::parse_color);
+
+    // 不是f%开头也没有换行的走普通渲染
+    if !rich_text::is_rich_text(text) && !text.contains('\n') {
         return draw_text_impl(x, y, text, fg, bg);
     }
 
-    // 样式初始化
-    let default_fg = fg.map(|v| v.to_string());
-    let default_bg = bg.map(|v| v.to_string());
-
-    let mut state = RichStyleState {
-        default_fg: default_fg.clone(), // 保存默认前景色
-        default_bg: default_bg.clone(), // 保存默认背景色
-        fg: default_fg,                 // 当前前景色初始为默认值
-        bg: default_bg,                 // 当前背景色初始为默认值
-        fg_count: None,                 // 前景色无次数限制
-        bg_count: None,                 // 背景色无次数限制
-        fg_need_clear: false,           // 不需要清理前景
-        bg_need_clear: false,           // 不需要清理背景
-    };
+    let cells = styled_cells(lua, text, fg, bg);
+    for (idx, line) in renderer::split_cell_lines(&cells).into_iter().enumerate() {
+        draw_cells(x, y + idx as i64, line)?;
+    }
+    Ok(())
+}
 
-    // 去掉开头的f%声明
-    let body = &text[2..];
-    // 存储解析出的样式块
-    let mut chunks = Vec::<StyledChunk>::new();
-
-    // 当前解析的位置
-    let mut i = 0usize;
-    // 遍历每个字符
-    while i < body.len() {
-        // 获取当前字符
-        let mut iter = body[i..].chars();
-        let ch = match iter.next() {
-            Some(c) => c,
-            None => break,
+// 按行绘制,每行宽度不足时截断加省略号,有多余宽度时按对齐方式补空格
+// 文本中的换行和draw_text一样从同一个x坐标开始画下一行
+fn draw_text_ex_impl(
+    lua: &Lua,
+    (x, y): (i64, i64),
+    text: &str,
+    fg: Option<&str>,
+    bg: Option<&str>,
+    max_width: Option<i64>,
+    align: TextAlign,
+) -> mlua::Result<()> {
+    let fg = fg.and_then(rich_text::parse_color);
+    let bg = bg.and_then(rich_text::parse_color);
+    let cells = styled_cells(lua, text, fg, bg);
+    for (idx, line) in renderer::split_cell_lines(&cells).into_iter().enumerate() {
+        let width = match max_width {
+            Some(w) => w.max(0) as usize,
+            None => renderer::cells_width(line),
         };
-        // 字符的编码字节长度
-        let ch_len = ch.len_utf8();
-
-        // 处理转义符
-        // \\, \{, \}
-        if ch == '\\' {
-            if let Some(next_ch) = iter.next() {
-                push_styled_char(&mut chunks, next_ch, &mut state);
-                i += ch_len + next_ch.len_utf8();
-            } else {
-                push_styled_char(&mut chunks, '\\', &mut state);
-                i += ch_len;
-            }
-            continue;
-        }
-
-        // 遇到{开始处理命令
-        if ch == '{' {
-            // 读取完整的命令块
-            if let Some((inner, consumed)) = read_command_block(body, i)? {
-                // 如果为空则抛出异常
-                if inner.trim().is_empty() {
-                    push_error(&mut chunks, &rich_text_error("rich_text.error.empty_command"));
-                    i += consumed;
-                    continue;
-                }
-
-                // 正常就保存到结构体状态机当中
-                match apply_command_block(lua, &inner, &mut state) {
-                    Ok(()) => {}
-                    Err(msg) => push_error(&mut chunks, &msg.to_string()),
-                }
-
-                i += consumed;
-                continue;
-            }
-
-            // 如果没有}就抛出异常
-            push_error(
-                &mut chunks,
-                &rich_text_error("rich_text.error.unclosed_command"),
-            );
-            i += ch_len;
-            continue;
-        }
+        let mut line = renderer::truncate_cells(line, width, true);
+
+        // 补空格,保证背景色能铺满整个宽度
+        let pad = width.saturating_sub(renderer::cells_width(&line));
+        let (left, right) = match align {
+            TextAlign::Left => (0, 0),
+            TextAlign::Center => (pad / 2, pad - pad / 2),
+            TextAlign::Right => (pad, 0),
+        };
+        let blank = (' ', (fg, bg));
+        line.splice(0..0, std::iter::repeat_n(blank, left));
+        line.extend(std::iter::repeat_n(blank, right));
 
-        // 如果只有}就抛出异常
-        if ch == '}' {
-            push_error(
-                &mut chunks,
-                &rich_text_error("rich_text.error.unclosed_command"),
-            );
-            i += ch_len;
-            continue;
-        }
+        draw_cells(x, y + idx as i64, &line)?;
+    }
+    Ok(())
+}
 
-        // 将普通字符添加到当前样式块里
-        push_styled_char(&mut chunks, ch, &mut state);
-        i += ch_len;
+// 在矩形区域内排版文本
+// 支持自动换行、水平和垂直对齐,超出区域的部分被裁掉
+// 返回(实际绘制的行数, 排版后的总行数)
+fn draw_text_box_impl(
+    lua: &Lua,
+    rect: (i64, i64, i64, i64),
+    text: &str,
+    opts: &TextBoxOptions,
+) -> mlua::Result<(i64, i64)> {
+    let (x, y, w, h) = rect;
+    let width = w.max(0) as usize;
+    let height = h.max(0) as usize;
+    if width == 0 || height == 0 {
+        return Ok((0, 0));
     }
 
-    // 检查未被清理的样式，未被清理的抛出异常
-    if state.fg_need_clear || state.bg_need_clear {
-        push_error(
-            &mut chunks,
-            &rich_text_error("rich_text.error.unterminated_style"),
-        );
+    let cells = styled_cells(lua, text, opts.fg, opts.bg);
+    let mut lines: Vec<Vec<(char, CellStyle)>> = if opts.wrap {
+        renderer::wrap_cells(&cells, width)
+    } else {
+        renderer::split_cell_lines(&cells)
+            .into_iter()
+            .map(|line| renderer::truncate_cells(line, width, opts.ellipsis))
+            .collect()
+    };
+    let total = lines.len();
+
+    // 垂直裁剪,被裁掉内容时在最后一行末尾放省略号
+    if lines.len() > height {
+        lines.truncate(height);
+        if opts.ellipsis
+            && let Some(last) = lines.last_mut()
+        {
+            let style = last.last().map(|c| c.1).unwrap_or((opts.fg, opts.bg));
+            last.push(('\u{2026}', style));
+            *last = renderer::truncate_cells(last, width, true);
+        }
     }
 
-    // 绘制
-    draw_styled_chunks(x, y, &chunks)
-}
+    let top = match opts.valign {
+        TextVAlign::Top => 0,
+        TextVAlign::Middle => (height - lines.len()) / 2,
+        TextVAlign::Bottom => height - lines.len(),
+    };
 
-// 读取完整的指令{XXX}
-fn read_command_block(input: &str, start: usize) -> mlua::Result<Option<(String, usize)>> {
-    // 从{开始
-    let mut i = start + '{'.len_utf8();
-    let mut escape = false; // 转义标记
-    while i < input.len() {
-        // 获取当前字符位置
-        let c = match input[i..].chars().next() {
-            Some(v) => v,
-            None => break,
+    for (idx, line) in lines.iter().enumerate() {
+        let pad = width.saturating_sub(renderer::cells_width(line));
+        let offset = match opts.align {
+            TextAlign::Left => 0,
+            TextAlign::Center => pad / 2,
+            TextAlign::Right => pad,
         };
-        let clen = c.len_utf8(); // 字符的编码字节长度
-
-        // 处理转义状态
-        if escape {
-            escape = false; // 重置转义标记
-            i += clen; // 跳过转义的字符
-            continue;
-        }
+        draw_cells(x + offset as i64, y + (top + idx) as i64, line)?;
+    }
 
-        // 遇到转义字符
-        if c == '\\' {
-            escape = true; // 标记转义
-            i += clen;
-            continue;
-        }
+    Ok((lines.len() as i64, total as i64))
+}
 
-        // 遇到}结束，提取内容
-        if c == '}' {
-            let inner = input[start + 1..i].to_string();
-            return Ok(Some((inner, i + clen - start)));
-        }
+// 单个字符的样式: (前景色, 背景色)
+type CellStyle = (Option<Color>, Option<Color>);
 
-        // 普通字符就继续向下遍历
-        i += clen;
-    }
-    Ok(None)
+// 水平对齐方式
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum TextAlign {
+    Left,
+    Center,
+    Right,
 }
 
-// 根据符号分割字符
-fn split_unescaped(input: &str, sep: char) -> Vec<String> {
-    let mut out = Vec::<String>::new(); // 存储分割后的片段
-    let mut cur = String::new(); // 当前正在构建的片段
-    let mut escape = false; // 转义标记
-
-    // 开始遍历字符串
-    for c in input.chars() {
-        if escape {
-            // 转义状态：直接添加字符，不当作特殊字符
-            cur.push(c);
-            escape = false;
-            continue;
-        }
-        if c == '\\' {
-            // 遇到转义符：标记转义状态
-            escape = true;
-            continue;
+impl TextAlign {
+    fn parse(raw: Option<&str>) -> Self {
+        match raw.unwrap_or("left").trim().to_ascii_lowercase().as_str() {
+            "center" => Self::Center,
+            "right" => Self::Right,
+            _ => Self::Left,
         }
-        if c == sep {
-            // 遇到未转义的分隔符：保存当前片段
-            out.push(cur.trim().to_string());
-            cur.clear();
-            continue;
-        }
-
-        // 普通字符，添加到当前片段
-        cur.push(c);
-    }
-
-    // 处理末尾残留的转义符
-    if escape {
-        cur.push('\\');
     }
+}
 
-    // 添加最后一个片段
-    out.push(cur.trim().to_string());
-    out
+// 垂直对齐方式
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum TextVAlign {
+    Top,
+    Middle,
+    Bottom,
 }
 
-// 分割多指令
-fn apply_command_block(lua: &Lua, block: &str, state: &mut RichStyleState) -> mlua::Result<()> {
-    // 按照 | 分割多指令
-    let entries = split_unescaped(block, '|');
-    for entry in entries {
-        // 跳过空指令
-        if entry.trim().is_empty() {
-            return Err(mlua::Error::external(rich_text_error(
-                "rich_text.error.empty_command",
-            )));
-        }
-
-        // 按照 : 分割指令和参数
-        let mut parts = split_unescaped(&entry, ':');
-        if parts.len() != 2 {
-            return Err(mlua::Error::external(rich_text_error(
-                "rich_text.error.missing_command_or_param",
-            )));
-        }
-
-        // 提取指令
-        let cmd = parts.remove(0).trim().to_ascii_lowercase();
-
-        // 按照 > 分割参数
-        let param_expr = parts.remove(0);
-        let params = split_unescaped(&param_expr, '>');
-
-        // 为空就报错
-        if cmd.is_empty() {
-            return Err(mlua::Error::external(rich_text_error(
-                "rich_text.error.missing_command_or_param",
-            )));
-        }
-
-        // 执行指令
-        let result = apply_single_command(lua, &cmd, &params)?;
-
-        // 应用于状态机
-        apply_command_result(&cmd, result, state)?;
-    }
-    Ok(())
+// draw_text_box的可选参数表
+struct TextBoxOptions {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    align: TextAlign,
+    valign: TextVAlign,
+    wrap: bool,     // 是否自动换行,关闭时每行单独截断
+    ellipsis: bool, // 内容被裁掉时是否显示省略号
 }
 
-// 执行指令
-fn apply_single_command(
-    lua: &Lua,
-    cmd: &str,
-    params: &[String],
-) -> mlua::Result<TextCommandResult> {
-    // 优先尝试使用Lua的自定义指令
-    if let Some(via_lua) = apply_command_via_lua(lua, cmd, params)? {
-        return Ok(via_lua);
-    }
-
-    // 内部指令解析器(一个备用方案)
-    // 检查参数是否为空
-    if params.is_empty() || params[0].trim().is_empty() {
-        return Err(mlua::Error::external(rich_text_error(
-            "rich_text.error.missing_param",
-        )));
-    }
-
-    // 处理clear参数
-    let first = params[0].trim();
-    if first.eq_ignore_ascii_case("clear") {
-        if params.len() != 1 {
-            return Err(mlua::Error::external(rich_text_error(
-                "rich_text.error.unterminated_style",
-            )));
-        }
-        return Ok(TextCommandResult {
-            clear: true,
-            color: None,
-            count: None,
-        });
-    }
-
-    // 检查颜色代码是否符合标准
-    if parse_color(Some(first)).is_none() {
-        return Err(mlua::Error::external(rich_text_error(
-            "rich_text.error.invalid_param",
-        )));
-    }
-
-    // 第二参数数字有效性
-    let count = if params.len() >= 2 && !params[1].trim().is_empty() {
-        let raw = params[1]
+impl TextBoxOptions {
+    fn from_table(opts: Option<&Table>) -> Self {
+        let get = |key: &str| opts.and_then(|t| t.get::<String>(key).ok());
+        let get_bool = |key: &str, default: bool| {
+            opts.and_then(|t| t.get::<Option<bool>>(key).ok().flatten())
+                .unwrap_or(default)
+        };
+        let valign = match get("valign")
+            .unwrap_or_default()
             .trim()
-            .parse::<usize>()
-            .map_err(|_| mlua::Error::external(rich_text_error("rich_text.error.invalid_param")))?;
-        if raw == 0 {
-            return Err(mlua::Error::external(rich_text_error(
-                "rich_text.error.invalid_param",
-            )));
-        }
-        Some(raw)
-    } else {
-        None
-    };
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "middle" | "center" => TextVAlign::Middle,
+            "bottom" => TextVAlign::Bottom,
+            _ => TextVAlign::Top,
+        };
 
-    // 检查是否有多余参数
-    if params.len() > 2 {
-        return Err(mlua::Error::external(rich_text_error(
-            "rich_text.error.invalid_param",
-        )));
-    }
-
-    // 返回结构
-    Ok(TextCommandResult {
-        clear: false,
-        color: Some(first.to_string()),
-        count,
-    })
+        Self {
+            fg: get("fg").as_deref().and_then(rich_text::parse_color),
+            bg: get("bg").as_deref().and_then(rich_text::parse_color),
+            align: TextAlign::parse(get("align").as_deref()),
+            valign,
+            wrap: get_bool("wrap", true),
+            ellipsis: get_bool("ellipsis", true),
+        }
+    }
 }
 
-// 调用Lua自定义指令
-fn apply_command_via_lua(
+// 将文本拆成带样式的字符,普通文本和富文本统一处理
+fn styled_cells(
     lua: &Lua,
-    cmd: &str,
-    params: &[String],
-) -> mlua::Result<Option<TextCommandResult>> {
-    // 获取TEXT_COMMANDS表
-    let globals = lua.globals();
-    let commands = match globals.get::<Table>("TEXT_COMMANDS") {
-        Ok(t) => t,
-        Err(_) => return Ok(None), // 没有注册任何命令
-    };
-
-    // 获取对应指令的函数
-    let func = match commands.get::<Function>(cmd) {
-        Ok(f) => f,
-        Err(_) => return Ok(None), // 没有找到这个指令
-    };
-
-    // 将参数列表转换为Lua表
-    let ptable = lua.create_table()?;
-    for (idx, p) in params.iter().enumerate() {
-        ptable.set((idx + 1) as i64, p.as_str())?;
-    }
-
-    // 调用Lua函数
-    let ret = func.call::<Value>(ptable)?;
-    // 验证返回值是否是一个表
-    let t = match ret {
-        Value::Table(t) => t,
-        _ => {
-            return Err(mlua::Error::external(rich_text_error(
-                "rich_text.error.invalid_return_value",
-            )));
-        }
-    };
-
-    // 检查是否有错误
-    if let Ok(msg) = t.get::<String>("error") {
-        if !msg.trim().is_empty() {
-            return Err(mlua::Error::external(rich_text_error(
-                "rich_text.error.invalid_custom_command",
-            )));
-        }
-    }
-
-    // 解析返回值
-    let clear = t.get::<bool>("clear").unwrap_or(false);
-    let color = t.get::<String>("color").ok();
-    let count = t
-        .get::<i64>("count")
-        .ok()
-        .and_then(|v| if v > 0 { Some(v as usize) } else { None });
-
-    // 验证返回值的有效性
-    if !clear {
-        if let Some(c) = color.as_deref() {
-            if parse_color(Some(c)).is_none() {
-                return Err(mlua::Error::external(rich_text_error(
-                    "rich_text.error.invalid_param",
-                )));
-            }
-        } else {
-            return Err(mlua::Error::external(rich_text_error(
-                "rich_text.error.invalid_param",
-            )));
-        }
-    }
-
-    Ok(Some(TextCommandResult {
-        clear,
-        color,
-        count,
-    }))
-}
-
-// 将指令执行结果应用
-fn apply_command_result(
-    cmd: &str,
-    result: TextCommandResult,
-    state: &mut RichStyleState,
-) -> mlua::Result<()> {
-    match cmd {
-        // 处理文字颜色
-        "tc" => {
-            if result.clear {
-                // clear 恢复文字颜色
-                state.fg = state.default_fg.clone();
-                state.fg_count = None;
-                state.fg_need_clear = false;
-                return Ok(());
-            }
-            // 设置新的文字颜色
-            let color = result
-                .color
-                .ok_or_else(|| {
-                    mlua::Error::external(rich_text_error("rich_text.error.missing_param"))
-                })?;
-            state.fg = Some(color);
-            state.fg_count = result.count;
-            // 如果没有指定第二参数,标记需要后续自动清理
-            state.fg_need_clear = result.count.is_none();
-            Ok(())
-        }
+    text: &str,
+    fg: Option<Color>,
+    bg: Option<Color>,
+) -> Vec<(char, CellStyle)> {
+    if !rich_text::is_rich_text(text) {
+        return text.chars().map(|ch| (ch, (fg, bg))).collect();
+    }
 
-        // 处理背景色
-        "bg" => {
-            if result.clear {
-                // clear 恢复背景色
-                state.bg = state.default_bg.clone();
-                state.bg_count = None;
-                state.bg_need_clear = false;
-                return Ok(());
-            }
-            // 设置新的背景色
-            let color = result
-                .color
-                .ok_or_else(|| {
-                    mlua::Error::external(rich_text_error("rich_text.error.missing_param"))
-                })?;
-            state.bg = Some(color);
-            state.bg_count = result.count;
-            // 如果没有指定第二参数,标记需要后续自动清理
-            state.bg_need_clear = result.count.is_none();
-            Ok(())
-        }
-        _ => Err(mlua::Error::external(rich_text_error(
-            "rich_text.error.unknown_command",
-        ))),
-    }
-}
-
-// 抛出异常
-fn push_error(chunks: &mut Vec<StyledChunk>, message: &str) {
-    push_styled_text(
-        chunks,
-        &format!("{{{message}}}"),
-        Some("red".to_string()),
-        None,
-    );
+    rich_text::parse_rich_text(text, &LuaCommands(lua), fg, bg)
+        .into_iter()
+        .flat_map(|span| {
+            let style = (span.fg, span.bg);
+            span.text
+                .chars()
+                .map(move |ch| (ch, style))
+                .collect::<Vec<_>>()
+        })
+        .collect()
 }
 
-// 处理字符渲染长度
-fn push_styled_char(chunks: &mut Vec<StyledChunk>, ch: char, state: &mut RichStyleState) {
-    // 将字符转换为字符串并添加到块列表
-    let mut s = String::new();
-    s.push(ch);
-    push_styled_text(chunks, &s, state.fg.clone(), state.bg.clone());
-
-    // 处理字体颜色
-    if let Some(rem) = state.fg_count {
-        if rem <= 1 {
-            state.fg_count = None;
-            state.fg = state.default_fg.clone();
-        } else {
-            state.fg_count = Some(rem - 1);
+// 把一行带样式的字符写回富文本,花括号和反斜杠转义
+fn cells_to_rich_text(cells: &[(char, CellStyle)]) -> String {
+    let mut out = String::from("f%");
+    let mut current: CellStyle = (None, None);
+    for &(ch, style) in cells {
+        if style != current {
+            push_style_change(&mut out, current, style);
+            current = style;
         }
-    }
-
-    // 处理背景颜色
-    if let Some(rem) = state.bg_count {
-        if rem <= 1 {
-            state.bg_count = None;
-            state.bg = state.default_bg.clone();
-        } else {
-            state.bg_count = Some(rem - 1);
+        if matches!(ch, '\\' | '{' | '}') {
+            out.push('\\');
         }
+        out.push(ch);
     }
+    push_style_change(&mut out, current, (None, None));
+    out
 }
 
-// 文本添加和合并,减少终端的调用和命令执行提高效率
-fn push_styled_text(
-    chunks: &mut Vec<StyledChunk>,
-    text: &str,
-    fg: Option<String>,
-    bg: Option<String>,
-) {
-    // 忽略空文本
-    if text.is_empty() {
-        return;
-    }
-
-    // 检查是否可以合并
-    if let Some(last) = chunks.last_mut() {
-        if last.fg == fg && last.bg == bg {
-            last.text.push_str(text);
-            return;
+// 写出从from切换到to需要的tc/bg指令
+fn push_style_change(out: &mut String, from: CellStyle, to: CellStyle) {
+    let mut commands = Vec::new();
+    for (cmd, old, new) in [("tc", from.0, to.0), ("bg", from.1, to.1)] {
+        if old == new {
+            continue;
         }
+        match new.and_then(rich_text::color_name) {
+            Some(name) => commands.push(format!("{cmd}:{name}")),
+            None if old.is_some() => commands.push(format!("{cmd}:clear")),
+            None => {}
+        }
+    }
+    if !commands.is_empty() {
+        out.push_str(&format!("{{{}}}", commands.join("|")));
     }
+}
 
-    // 样式不同就创建新的块
-    chunks.push(StyledChunk {
-        text: text.to_string(),
-        fg,
-        bg,
-    });
+// 测量文本的显示尺寸: (最宽一行的宽度, 行数)
+// 富文本的指令不计入宽度
+fn measure_text_impl(lua: &Lua, text: &str) -> (usize, usize) {
+    let cells = styled_cells(lua, text, None, None);
+    let lines = renderer::split_cell_lines(&cells);
+    let width = lines
+        .iter()
+        .map(|line| renderer::cells_width(line))
+        .max()
+        .unwrap_or(0);
+    (width, lines.len().max(1))
 }
 
-// 计算样式块样式块渲染
-fn draw_styled_chunks(x: i64, y: i64, chunks: &[StyledChunk]) -> mlua::Result<()> {
-    // 当前光标未知
+// 绘制一行带样式的字符,相同样式合并成一段减少终端调用
+fn draw_cells(x: i64, y: i64, cells: &[(char, CellStyle)]) -> mlua::Result<()> {
+    // 当前光标位置
     let mut cursor_x = x;
-
-    for chunk in chunks {
-        // 跳过空块
-        if chunk.text.is_empty() {
-            continue;
+    let mut run = String::new();
+    let mut run_style: Option<CellStyle> = None;
+
+    for (ch, style) in cells {
+        if run_style != Some(*style) && !run.is_empty() {
+            let (fg, bg) = run_style.unwrap_or_default();
+            draw_text_impl(cursor_x, y, &run, fg, bg)?;
+            // 计算文本的实际宽度并移动光标
+            cursor_x += UnicodeWidthStr::width(run.as_str()) as i64;
+            run.clear();
         }
+        run_style = Some(*style);
+        run.push(*ch);
+    }
 
-        // 绘制当前块
-        draw_text_impl(
-            cursor_x,
-            y,
-            &chunk.text,
-            chunk.fg.as_deref(),
-            chunk.bg.as_deref(),
-        )?;
-
-        // 计算文本的实际宽度并移动光标
-        cursor_x += UnicodeWidthStr::width(chunk.text.as_str()) as i64;
+    if !run.is_empty() {
+        let (fg, bg) = run_style.unwrap_or_default();
+        draw_text_impl(cursor_x, y, &run, fg, bg)?;
     }
     Ok(())
 }
@@ -924,20 +1239,20 @@ fn draw_text_impl(
     x: i64,
     y: i64,
     text: &str,
-    fg: Option<&str>,
-    bg: Option<&str>,
+    fg: Option<Color>,
+    bg: Option<Color>,
 ) -> mlua::Result<()> {
     // 获取终端输出的锁
     let mut out = lock_out()?;
 
     // 设置文字颜色
-    if let Some(color) = parse_color(fg) {
-        queue!(out, SetForegroundColor(color)).map_err(mlua::Error::external)?;
+    if let Some(color) = fg {
+        queue!(out, SetForegroundColor(color.into_crossterm())).map_err(mlua::Error::external)?;
     }
 
     // 设置背景色
-    if let Some(color) = parse_color(bg) {
-        queue!(out, SetBackgroundColor(color)).map_err(mlua::Error::external)?;
+    if let Some(color) = bg {
+        queue!(out, SetBackgroundColor(color.into_crossterm())).map_err(mlua::Error::external)?;
     }
 
     // 移动光标并输出文本，然后重置颜色
@@ -960,9 +1275,66 @@ fn lock_out() -> mlua::Result<MutexGuard<'static, Stdout>> {
 // 强制将缓冲区的内容输出到终端
 fn flush_output() -> mlua::Result<()> {
     let mut out = lock_out()?;
+    draw_toast(&mut out)?;
     out.flush().map_err(mlua::Error::external)
 }
 
+// 在右上角画成就提示框
+// 每次输出前都重画一遍,游戏自己清屏也不会把它盖掉
+fn draw_toast(out: &mut Stdout) -> mlua::Result<()> {
+    let Ok(mut toast) = TOAST.lock() else {
+        return Ok(());
+    };
+    let now = Instant::now();
+    if toast.current.as_ref().is_some_and(|(_, until)| *until <= now) {
+        toast.current = None;
+        // 到时间后擦掉提示框,下面的内容等游戏下一帧重画
+        if let Some((x, width)) = toast.drawn.take() {
+            for row in 0..3 {
+                queue!(
+                    out,
+                    crossterm::cursor::MoveTo(x, row),
+                    Print(" ".repeat(width as usize))
+                )
+                .map_err(mlua::Error::external)?;
+            }
+        }
+    }
+    if toast.current.is_none()
+        && let Some(text) = toast.queue.pop_front()
+    {
+        let seconds = settings_store::get_int("toast_seconds").max(1) as u64;
+        toast.current = Some((text, now + Duration::from_secs(seconds)));
+    }
+    let Some((text, _)) = &toast.current else {
+        return Ok(());
+    };
+
+    let (cols, _) = crossterm::terminal::size().unwrap_or((80, 24));
+    let text_width = text.width() as u16;
+    let width = (text_width + 4).min(cols);
+    let x = cols.saturating_sub(width + 1);
+    let border = "─".repeat(width.saturating_sub(2) as usize);
+    let lines = [
+        format!("┌{border}┐"),
+        format!("│ {text} │"),
+        format!("└{border}┘"),
+    ];
+    for (row, line) in lines.iter().enumerate() {
+        queue!(
+            out,
+            crossterm::cursor::MoveTo(x, row as u16),
+            SetForegroundColor(Color::Yellow.into_crossterm()),
+            SetBackgroundColor(Color::Black.into_crossterm()),
+            Print(line),
+            ResetColor
+        )
+        .map_err(mlua::Error::external)?;
+    }
+    toast.drawn = Some((x, width));
+    Ok(())
+}
+
 // Lua执行完后,重置终端状态并清空输入缓冲区
 fn finalize_terminal_after_script() {
     if let Ok(mut out) = OUT.lock() {
@@ -975,40 +1347,42 @@ fn finalize_terminal_after_script() {
 
 // 清空输入缓冲区
 fn drain_input_events() {
-    loop {
-        match event::poll(Duration::from_millis(0)) {
-            Ok(true) => {
-                let _ = event::read();
+    while let Ok(true) = event::poll(Duration::from_millis(0)) {
+        let _ = event::read();
+    }
+}
+
+// 读取一个按键,非阻塞时没有按键返回空字符串
+fn read_key(blocking: bool) -> mlua::Result<String> {
+    flush_output()?;
+    track_terminal_size();
+
+    if blocking {
+        loop {
+            match event::read().map_err(mlua::Error::external)? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    return decode_key_event(key);
+                }
+                Event::Resize(width, height) => track_size(width, height),
+                _ => {}
             }
-            _ => break,
         }
     }
-}
 
-// 将crossterm的KeyCode枚举转换为Lua可识别的字符串
-fn keycode_to_string(code: KeyCode) -> String {
-    match code {
-        KeyCode::Up => "up".to_string(),
-        KeyCode::Down => "down".to_string(),
-        KeyCode::Left => "left".to_string(),
-        KeyCode::Right => "right".to_string(),
-        KeyCode::Backspace => "backspace".to_string(),
-        KeyCode::Delete => "delete".to_string(),
-        KeyCode::Enter => "enter".to_string(),
-        KeyCode::Tab => "tab".to_string(),
-        KeyCode::BackTab => "tab".to_string(),
-        KeyCode::Esc => "esc".to_string(),
-        KeyCode::Char(' ') => "space".to_string(),
-        KeyCode::Char(c) => c.to_ascii_lowercase().to_string(),
-        _ => String::new(),
+    if event::poll(Duration::from_millis(0)).map_err(mlua::Error::external)?
+        && let Event::Key(key) = event::read().map_err(mlua::Error::external)?
+        && key.kind == KeyEventKind::Press
+    {
+        return decode_key_event(key);
     }
+    Ok(String::new())
 }
 
 // 处理按键事件监听
 fn decode_key_event(key: KeyEvent) -> mlua::Result<String> {
     // 不是ESC则直接转换
     if key.code != KeyCode::Esc {
-        return Ok(keycode_to_string(key.code));
+        return Ok(keymap::key_name(key.code));
     }
 
     // 如果是ESC看是否需要特殊转换
@@ -1070,88 +1444,6 @@ fn coord_to_terminal(v: i64) -> u16 {
     }
 }
 
-// 颜色解析
-fn parse_color(name: Option<&str>) -> Option<CColor> {
-    let raw = name.unwrap_or("").trim();
-
-    // 解析十六进制
-    if let Some(hex) = parse_hex_color(raw) {
-        return Some(hex);
-    }
-
-    // 解析RGB
-    if let Some(rgb) = parse_rgb_color(raw) {
-        return Some(rgb);
-    }
-
-    // 解析预设颜色名
-    match raw.to_ascii_lowercase().as_str() {
-        "black" => Some(CColor::Black),
-        "white" => Some(CColor::White),
-        "red" => Some(CColor::Red),
-        "light_red" => Some(CColor::Red),
-        "dark_red" => Some(CColor::DarkRed),
-        "yellow" => Some(CColor::Yellow),
-        "light_yellow" => Some(CColor::Yellow),
-        "dark_yellow" => Some(CColor::DarkYellow),
-        "orange" => Some(CColor::DarkYellow),
-        "green" => Some(CColor::Green),
-        "light_green" => Some(CColor::Green),
-        "blue" => Some(CColor::Blue),
-        "light_blue" => Some(CColor::Blue),
-        "cyan" => Some(CColor::Cyan),
-        "light_cyan" => Some(CColor::Cyan),
-        "magenta" => Some(CColor::Magenta),
-        "light_magenta" => Some(CColor::Magenta),
-        "grey" | "gray" => Some(CColor::Grey),
-        "dark_grey" | "dark_gray" => Some(CColor::DarkGrey),
-        _ => None, // 未知颜色
-    }
-}
-
-// 解析十六进制
-fn parse_hex_color(raw: &str) -> Option<CColor> {
-    // 是7个字符并且以#开头
-    if raw.len() != 7 || !raw.starts_with('#') {
-        return None;
-    }
-    // 解析十六进制数
-    let r = u8::from_str_radix(&raw[1..3], 16).ok()?;
-    let g = u8::from_str_radix(&raw[3..5], 16).ok()?;
-    let b = u8::from_str_radix(&raw[5..7], 16).ok()?;
-
-    // RGB
-    Some(CColor::Rgb { r, g, b })
-}
-
-// 解析RGB
-fn parse_rgb_color(raw: &str) -> Option<CColor> {
-    let lower = raw.to_ascii_lowercase();
-
-    // 格式检查
-    if !lower.starts_with("rgb(") || !lower.ends_with(')') {
-        return None;
-    }
-
-    // 提取内容
-    let inner = &lower[4..lower.len() - 1];
-
-    // 按逗号分割并解析未u8
-    let mut parts = inner.split(',').map(|s| s.trim().parse::<u8>().ok());
-
-    let r = parts.next()??;
-    let g = parts.next()??;
-    let b = parts.next()??;
-
-    // 确保没有多余的部分
-    if parts.next().is_some() {
-        return None;
-    }
-
-    // RGB
-    Some(CColor::Rgb { r, g, b })
-}
-
 // 随机数生成器
 // 线程安全，使用了xorshift算法
 fn next_random_u64() -> u64 {
@@ -1259,6 +1551,16 @@ fn save_lua_data(key: &str, value: &Value) -> mlua::Result<()> {
     // 所以说 键值对 和 键对值 应该是一个意思吧
     store.insert(key.to_string(), json);
 
+    // 记下这个键是哪个游戏写的,卸载时只删自己的键
+    if let Some(game_id) = CURRENT_GAME.lock().ok().and_then(|current| current.clone()) {
+        let owners = store
+            .entry(KEY_OWNERS)
+            .or_insert_with(|| JsonValue::Object(Map::new()));
+        if let JsonValue::Object(owners) = owners {
+            owners.insert(key.to_string(), JsonValue::String(game_id));
+        }
+    }
+
     // 写回文件
     write_json_store(&store)
 }
@@ -1290,6 +1592,14 @@ fn clear_game_slots(store: &mut Map<String, JsonValue>) {
     store.retain(|key, _| key != "__latest_save_game" && !key.starts_with("game:"));
 }
 
+// save_data写入的键归属哪个游戏,存在lua_saves.json里
+const KEY_OWNERS: &str = "__key_owners";
+
+// 双下划线开头的键是宿主自己的记录,脚本不能读写
+fn is_host_key(key: &str) -> bool {
+    key.starts_with("__")
+}
+
 // 将游戏ID转换为存储键名
 fn game_slot_key(game_id: &str) -> String {
     format!("game:{}", game_id.trim().to_ascii_lowercase())
@@ -1419,3 +1729,125 @@ fn json_to_lua(lua: &Lua, value: &JsonValue) -> mlua::Result<Value> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mlua::Lua;
+
+    use serde_json::{Map, json};
+
+    use super::{KEY_OWNERS, LaunchMode, PlayClock, game_exit, register_api, remove_game_keys};
+    use crate::app::history::SessionOutcome;
+
+    #[test]
+    fn play_clock_stops_while_the_terminal_is_too_small() {
+        let mut clock = PlayClock::new();
+        clock.declared_size = (40, 20);
+        clock.set_min_size(40, 20);
+        clock.set_paused(false);
+        assert!(clock.running_since.is_some());
+
+        // 脚本没有调用set_game_paused,宿主按尺寸自己停
+        clock.check_size(39, 30);
+        assert!(clock.running_since.is_none());
+        clock.check_size(40, 20);
+        assert!(clock.running_since.is_some());
+
+        // 报告的尺寸不会低于声明的尺寸
+        clock.set_min_size(10, 10);
+        assert_eq!(clock.min_size, (40, 20));
+        clock.set_min_size(60, 10);
+        clock.check_size(50, 30);
+        assert!(clock.running_since.is_none());
+
+        // 尺寸恢复后仍然保持游戏自己的暂停
+        clock.set_paused(true);
+        clock.check_size(80, 30);
+        assert!(clock.running_since.is_none());
+    }
+
+    #[test]
+    fn clearing_a_game_keeps_keys_of_ids_it_prefixes() {
+        let mut store = Map::new();
+        store.insert("solitaire_best".into(), json!(120));
+        store.insert("solitaire".into(), json!({ "cards": [] }));
+        store.insert("game:solitaire".into(), json!({}));
+        store.insert("solitaire_x_best".into(), json!(90));
+        store.insert("solitaire_xmas".into(), json!(1));
+        store.insert(
+            KEY_OWNERS.into(),
+            json!({ "solitaire_xmas": "solitaire_x", "solitaire_best": "solitaire" }),
+        );
+        let installed = vec!["solitaire".to_string(), "solitaire_x".to_string()];
+
+        let removed = remove_game_keys(&mut store, "solitaire", &installed);
+
+        assert_eq!(removed, 3);
+        assert!(store.contains_key("solitaire_x_best"));
+        assert!(store.contains_key("solitaire_xmas"));
+        assert!(!store.contains_key("solitaire_best"));
+        assert_eq!(
+            store[KEY_OWNERS],
+            json!({ "solitaire_xmas": "solitaire_x" })
+        );
+    }
+
+    #[test]
+    fn scripts_cannot_touch_host_keys() {
+        let lua = Lua::new();
+        register_api(&lua, LaunchMode::New).unwrap();
+
+        let (saved, loaded): (bool, mlua::Value) = lua
+            .load(r#"return save_data("__key_owners", {}), load_data("__latest_save_game")"#)
+            .eval()
+            .unwrap();
+        assert!(!saved);
+        assert!(loaded.is_nil());
+    }
+
+    #[test]
+    fn exit_game_is_recognised_by_type_not_message() {
+        let lua = Lua::new();
+        register_api(&lua, LaunchMode::New).unwrap();
+
+        let err = lua
+            .load(r#"exit_game({ outcome = "won", score = 41.6, mode = "hard" })"#)
+            .exec()
+            .unwrap_err();
+        let exit = game_exit(&err).expect("exit_game should be recognised");
+        assert_eq!(exit.outcome, Some(SessionOutcome::Won));
+        assert_eq!(exit.score, Some(42));
+        assert_eq!(exit.mode.as_deref(), Some("hard"));
+
+        let err = lua.load(r#"error("__TUI_GAME_EXIT__")"#).exec().unwrap_err();
+        assert!(game_exit(&err).is_none());
+
+        let err = lua
+            .load(r#"exit_game({ outcome = "error" })"#)
+            .exec()
+            .unwrap_err();
+        assert!(game_exit(&err).is_none());
+    }
+
+    #[test]
+    fn wrap_text_keeps_rich_text_markup_on_each_line() {
+        let lua = Lua::new();
+        register_api(&lua, LaunchMode::New).unwrap();
+
+        let lines: Vec<String> = lua
+            .load(r#"return wrap_text("f%ab{tc:red}cd\\{e{tc:clear}f", 3)"#)
+            .eval()
+            .unwrap();
+        assert_eq!(
+            lines,
+            [
+                "f%ab{tc:red}c{tc:clear}",
+                "f%{tc:red}d\\{e{tc:clear}",
+                "f%f"
+            ]
+        );
+
+        let lines: Vec<String> = lua.load(r#"return wrap_text("abcd", 3)"#).eval().unwrap();
+        assert_eq!(lines, ["abc", "d"]);
+    }
+}
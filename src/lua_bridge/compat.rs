@@ -0,0 +1,159 @@
+use semver::Version;
+
+use crate::app::i18n;
+use crate::lua_bridge::script_loader::GameMeta;
+use crate::updater::github::CURRENT_VERSION_TAG;
+
+// 引擎的Lua接口等级
+// 给Lua新增接口或者改了已有接口的行为时加一
+// 2: engine_version、api_level、has_capability
+// 3: submit_record和GAME_META.records
+// 4: unlock_achievement、add_achievement_progress和GAME_META.achievements
+// 5: leaderboard_qualifies、submit_leaderboard和GAME_META.leaderboard
+// 6: set_game_paused
+// 7: exit_game的结局表
+// 8: get_setting
+// 9: get_action、action_keys和GAME_META.actions
+// 10: set_min_size和GAME_META.min_size,终端太小时由宿主暂停计时
+// 11: get_action_key
+pub const API_LEVEL: u32 = 11;
+
+// 引擎固定提供的能力,游戏可以在GAME_META.capabilities里声明需要哪些
+// truecolor取决于终端,单独检测
+// mouse、key_release、clipboard目前还不支持
+pub const CAPABILITIES: &[&str] = &[
+    "color",
+    "rich_text",
+    "unicode",
+    "text_layout",
+    "translate",
+    "save_data",
+    "stats",
+    "records",
+    "achievements",
+    "leaderboard",
+    "play_clock",
+    "settings",
+    "actions",
+];
+
+// 游戏不能启动的原因
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LaunchBlock {
+    Disabled,
+    ApiLevel(u32),
+    EngineVersion(String),
+    Capability(String),
+}
+
+impl LaunchBlock {
+    // 给玩家看的原因
+    pub fn message(&self) -> String {
+        match self {
+            Self::Disabled => i18n::t("game_selection.blocked.disabled"),
+            Self::ApiLevel(level) => i18n::t_args(
+                "game_selection.blocked.api_level",
+                &[("level", u64::from(*level).into())],
+            ),
+            Self::EngineVersion(version) => i18n::t_args(
+                "game_selection.blocked.engine_version",
+                &[("version", version.as_str().into())],
+            ),
+            Self::Capability(name) => i18n::t_args(
+                "game_selection.blocked.capability",
+                &[("capability", name.as_str().into())],
+            ),
+        }
+    }
+}
+
+// 检查游戏能不能在当前引擎上启动,可以启动返回None
+pub fn launch_block(game: &GameMeta) -> Option<LaunchBlock> {
+    if !game.enabled {
+        return Some(LaunchBlock::Disabled);
+    }
+
+    if let Some(level) = game.api_level
+        && level > API_LEVEL
+    {
+        return Some(LaunchBlock::ApiLevel(level));
+    }
+
+    if let Some(required) = game.min_engine_version.as_deref()
+        && let (Some(required_v), Some(current_v)) =
+            (parse_version(required), parse_version(engine_version()))
+        && required_v > current_v
+    {
+        return Some(LaunchBlock::EngineVersion(required.to_string()));
+    }
+
+    game.capabilities
+        .iter()
+        .find(|name| !has_capability(name))
+        .map(|name| LaunchBlock::Capability(name.clone()))
+}
+
+// 引擎是否提供某个能力,不区分大小写
+pub fn has_capability(name: &str) -> bool {
+    let name = name.trim().to_ascii_lowercase();
+    match name.as_str() {
+        "truecolor" => supports_truecolor(),
+        _ => CAPABILITIES.contains(&name.as_str()),
+    }
+}
+
+// 当前的程序版本
+pub fn engine_version() -> &'static str {
+    CURRENT_VERSION_TAG
+}
+
+// 终端通过COLORTERM声明是否支持24位色
+fn supports_truecolor() -> bool {
+    std::env::var("COLORTERM")
+        .map(|v| matches!(v.to_ascii_lowercase().as_str(), "truecolor" | "24bit"))
+        .unwrap_or(false)
+}
+
+// 兼容v前缀和只写了主次版本号的写法
+fn parse_version(raw: &str) -> Option<Version> {
+    let raw = raw.trim().trim_start_matches(['v', 'V']);
+    Version::parse(raw)
+        .or_else(|_| Version::parse(&format!("{raw}.0")))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{API_LEVEL, LaunchBlock, launch_block};
+    use crate::lua_bridge::script_loader::GameMeta;
+
+    #[test]
+    fn launch_block_reports_first_unmet_requirement() {
+        let mut game = GameMeta {
+            enabled: true,
+            ..GameMeta::default()
+        };
+        assert_eq!(launch_block(&game), None);
+
+        game.capabilities = vec!["Color".to_string(), "mouse".to_string()];
+        assert_eq!(
+            launch_block(&game),
+            Some(LaunchBlock::Capability("mouse".to_string()))
+        );
+
+        game.min_engine_version = Some("v999.0".to_string());
+        assert_eq!(
+            launch_block(&game),
+            Some(LaunchBlock::EngineVersion("v999.0".to_string()))
+        );
+
+        game.api_level = Some(API_LEVEL + 1);
+        assert_eq!(
+            launch_block(&game),
+            Some(LaunchBlock::ApiLevel(API_LEVEL + 1))
+        );
+
+        game.enabled = false;
+        assert_eq!(launch_block(&game), Some(LaunchBlock::Disabled));
+    }
+}
@@ -1,5 +1,7 @@
 ﻿// 模块入口
-// 对外暴露两个模块
+// 对外暴露的模块
 // 只负责“模块组织”，不负责业务逻辑
 pub mod api;
+pub mod compat;
 pub mod script_loader;
+pub mod text_commands;
@@ -1,60 +1,267 @@
-﻿use std::fs;
+use std::collections::HashMap;
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
-use anyhow::Result;
-use mlua::{Lua, Table};
+use anyhow::{Result, anyhow};
+use mlua::{HookTriggers, Lua, LuaOptions, StdLib, Table, Value};
+use serde::{Deserialize, Serialize};
 
+use crate::app::achievements::AchievementSpec;
+use crate::app::i18n;
+use crate::app::keymap::ActionSpec;
+use crate::app::leaderboard::LeaderboardSpec;
+use crate::app::stats::{RecordBetter, RecordSpec, RecordUnit};
 use crate::utils::path_utils;
 
+// 游戏包的清单文件名
+pub const MANIFEST_FILE: &str = "game.json";
+
 // 游戏数据结构
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct GameMeta {
-    pub id: String, // 游戏ID
-    pub name: String, // 游戏显示名称
-    pub description: String, // 游戏描述
-    pub script_path: PathBuf, // 脚本文件的完整路径
+    pub id: String,                           // 游戏ID
+    pub name: String,                         // 游戏显示名称
+    pub description: String,                  // 游戏描述
+    pub script_path: PathBuf,                 // 脚本文件的完整路径
+    pub package_dir: Option<PathBuf>,         // 游戏包目录,单文件脚本为None
+    pub version: Option<String>,              // 游戏版本
+    pub authors: Vec<String>,                 // 作者列表
+    pub license: Option<String>,              // 许可证
+    pub min_engine_version: Option<String>,   // 需要的最低程序版本
+    pub assets: Vec<String>,                  // 游戏包内的资源路径
+    pub languages: Vec<String>,               // 自带翻译的语言
+    pub tags: Vec<String>,                    // 标签
+    pub warnings: Vec<String>,                // 扫描时发现的问题(i18n键)
+    pub enabled: bool,                        // 是否允许启动
+    pub api_level: Option<u32>,               // 需要的最低引擎API等级
+    pub capabilities: Vec<String>,            // 需要引擎提供的能力
+    pub source: GameSource,                   // 游戏来自哪个目录
+    pub records: Option<Vec<RecordSpec>>,     // 声明的成绩记录,None时显示默认的最高分和最长时长
+    pub achievements: Vec<AchievementSpec>,   // 声明的成就
+    pub leaderboard: Option<LeaderboardSpec>, // 排行榜的排序方式,None时按分数从高到低
+    pub actions: Vec<ActionSpec>,             // 声明的按键动作
+    pub min_size: Option<MinSize>,            // 需要的最小终端尺寸,更小时宿主暂停计时
+}
+
+// 游戏需要的最小终端尺寸
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+pub struct MinSize {
+    pub width: u16,
+    pub height: u16,
+}
+
+// 游戏的来源
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GameSource {
+    #[default]
+    Bundled, // 程序自带的scripts目录
+    User, // 用户游戏库
+}
+
+impl GameSource {
+    pub fn label(self) -> String {
+        match self {
+            GameSource::Bundled => i18n::t("game_selection.source.bundled"),
+            GameSource::User => i18n::t("game_selection.source.user"),
+        }
+    }
+}
+
+// game.json清单
+// 除了id以外都可以省略
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GameManifest {
+    pub id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub authors: Vec<String>,
+    pub license: Option<String>,
+    pub min_engine_version: Option<String>,
+    pub entry: Option<String>, // 入口脚本,默认main.lua
+    pub assets: Vec<String>,
+    pub languages: Vec<String>,
+    pub tags: Vec<String>,
+    pub enabled: Option<bool>,
+    pub api_level: Option<u32>,
+    pub capabilities: Vec<String>,
+    pub records: Option<Vec<RecordSpec>>,
+    pub achievements: Vec<AchievementSpec>,
+    pub leaderboard: Option<LeaderboardSpec>,
+    pub actions: Vec<ActionSpec>,
+    pub min_size: Option<MinSize>,
+}
+
+impl GameManifest {
+    // 读取并校验清单
+    pub fn load(package_dir: &Path) -> Result<Self> {
+        let path = package_dir.join(MANIFEST_FILE);
+        let content = fs::read_to_string(&path)?;
+        let manifest: Self = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+            .map_err(|e| anyhow!("{}: {e}", path.display()))?;
+        if manifest.id.trim().is_empty() {
+            return Err(anyhow!("{}: missing id", path.display()));
+        }
+        // 入口和资源都只能指向游戏包里面的文件
+        let entry = manifest.entry.as_deref().unwrap_or("main.lua");
+        if let Some(name) = std::iter::once(entry)
+            .chain(manifest.assets.iter().map(String::as_str))
+            .find(|name| path_utils::join_inside(package_dir, name).is_none())
+        {
+            return Err(anyhow!(
+                "{}: path outside the package: {name}",
+                path.display()
+            ));
+        }
+        Ok(manifest)
+    }
+
+    // 入口脚本的完整路径,load已经检查过不会跑出游戏包
+    pub fn entry_path(&self, package_dir: &Path) -> PathBuf {
+        package_dir.join(self.entry.as_deref().unwrap_or("main.lua"))
+    }
+}
+
+// 一次扫描的结果
+// warnings是跳过的游戏包等全局提示,已经翻译好可以直接显示
+#[derive(Clone, Debug, Default)]
+pub struct ScanReport {
+    pub games: Vec<GameMeta>,
+    pub warnings: Vec<String>,
+}
+
+// 缓存格式版本,CachedMeta增加字段时加一让旧缓存失效
+const META_CACHE_VERSION: u32 = 8;
+
+// 元数据缓存文件
+// 按脚本路径和修改时间缓存从GAME_META读到的内容
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct MetaCache {
+    version: u32,
+    entries: HashMap<String, CachedMeta>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct CachedMeta {
+    mtime_ns: u64,
+    name: Option<String>,
+    description: Option<String>,
+    enabled: Option<bool>,
+    api_level: Option<u32>,
+    capabilities: Vec<String>,
+    tags: Vec<String>,
+    records: Option<Vec<RecordSpec>>,
+    achievements: Vec<AchievementSpec>,
+    leaderboard: Option<LeaderboardSpec>,
+    actions: Vec<ActionSpec>,
+    min_size: Option<MinSize>,
+    warnings: Vec<String>, // i18n键
 }
 
 // 扫描脚本目录找到游戏脚本目录
+// 只返回游戏列表,不关心扫描提示
+pub fn scan_scripts() -> Result<Vec<GameMeta>> {
+    Ok(scan_games()?.games)
+}
+
+// 扫描脚本目录找到游戏
 // 在0.10.0更新了文件目录
 // 但也向老版本兼容了
-pub fn scan_scripts() -> Result<Vec<GameMeta>> {
+// 同一个ID按 用户游戏库 > scripts/game > scripts 的顺序只保留第一个
+pub fn scan_games() -> Result<ScanReport> {
     // 获取目录
     let scripts_dir = path_utils::scripts_dir()?;
 
-    let mut games = Vec::new();
+    let mut cache = load_meta_cache();
+    let mut used = MetaCache {
+        version: META_CACHE_VERSION,
+        ..MetaCache::default()
+    };
+    let mut report = ScanReport::default();
+
+    // 按优先级排好要扫描的目录
+    // 用户游戏库优先,可以覆盖自带的同名游戏,目录读不了不影响自带游戏
+    let mut roots = Vec::new();
+    match path_utils::user_games_dir() {
+        Ok(user_dir) => roots.push((user_dir, GameSource::User)),
+        Err(err) => report.warnings.push(i18n::t_args(
+            "scan.warning.user_dir",
+            &[("error", err.to_string().into())],
+        )),
+    }
+    // 然后是新版本路径,最后是老版本兼容
+    roots.push((scripts_dir.join("game"), GameSource::Bundled));
+    roots.push((scripts_dir, GameSource::Bundled));
 
-    // 优先扫描新版本路径
-    let game_dir = scripts_dir.join("game");
-    if game_dir.exists() {
-        games.extend(scan_scripts_in(&game_dir)?);
+    for (dir, source) in &roots {
+        scan_scripts_in(dir, *source, &mut cache, &mut used, &mut report)?;
     }
 
-    // 老版本兼容
-    games.extend(scan_scripts_in(&scripts_dir)?);
+    // 只保留这次用到的缓存,删掉的脚本不再占位置
+    let _ = save_meta_cache(&used);
 
     // ID去重,保留第一个
-    let mut dedup = Vec::new();
-    for g in games {
-        if !dedup.iter().any(|x: &GameMeta| x.id == g.id) {
-            dedup.push(g);
+    // 用户游戏盖住自带游戏时记一条提示,方便排查
+    let mut dedup: Vec<GameMeta> = Vec::new();
+    for g in report.games {
+        match dedup.iter_mut().find(|x| x.id == g.id) {
+            Some(kept) => {
+                let key = "scan.warning.overrides_bundled".to_string();
+                if kept.source == GameSource::User
+                    && g.source == GameSource::Bundled
+                    && !kept.warnings.contains(&key)
+                {
+                    kept.warnings.push(key);
+                }
+            }
+            None => dedup.push(g),
         }
     }
+    report.games = dedup;
 
-    Ok(dedup)
+    // 去重之后再注册翻译,被覆盖的游戏不会把翻译也带进来
+    for game in &report.games {
+        i18n::register_game_language_packs(&game.id, &game_lang_dirs(game));
+    }
+
+    Ok(report)
 }
 
-// 在目录里寻找脚本
+// 游戏自带翻译的目录
+// 游戏包在包内的lang目录,单文件游戏在同级的lang/<id>目录
+fn game_lang_dirs(game: &GameMeta) -> Vec<PathBuf> {
+    match (&game.package_dir, game.script_path.parent()) {
+        (Some(package_dir), _) => vec![package_dir.join("lang")],
+        (None, Some(parent)) => vec![parent.join("lang").join(&game.id)],
+        (None, None) => Vec::new(),
+    }
+}
+
+// 在目录里寻找游戏
+// 带game.json的子目录是游戏包,散放的lua文件是单文件游戏
 // 也带有排序的功能
-fn scan_scripts_in(dir: &Path) -> Result<Vec<GameMeta>> {
+fn scan_scripts_in(
+    dir: &Path,
+    source: GameSource,
+    cache: &mut MetaCache,
+    used: &mut MetaCache,
+    report: &mut ScanReport,
+) -> Result<()> {
     if !dir.exists() {
-        return Ok(Vec::new()); // 目标不存在,返回空列表
+        return Ok(()); // 目标不存在
     }
 
-    // 收集所有的Lua并排序
+    // 收集所有的游戏包和Lua并排序
     let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
         .filter_map(|entry| entry.ok().map(|e| e.path()))
         .filter(|path| {
+            if path.is_dir() {
+                return path.join(MANIFEST_FILE).is_file();
+            }
             path.extension()
                 .and_then(|ext| ext.to_str())
                 .map(|ext| ext.eq_ignore_ascii_case("lua"))
@@ -64,54 +271,660 @@ fn scan_scripts_in(dir: &Path) -> Result<Vec<GameMeta>> {
 
     entries.sort(); // 按文件名排序,保证顺序一致
 
-    let mut games = Vec::new();
     for path in entries {
-        // 从文件名获取ID
-        let id = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| "unknown".to_string());
+        let game = if path.is_dir() {
+            // 清单写错的包跳过,并记下原因
+            match load_package(&path, cache, used) {
+                Ok(game) => game,
+                Err(err) => {
+                    report.warnings.push(i18n::t_args(
+                        "scan.warning.invalid_package",
+                        &[
+                            ("path", path.display().to_string().into()),
+                            ("error", err.to_string().into()),
+                        ],
+                    ));
+                    continue;
+                }
+            }
+        } else {
+            load_script(path, cache, used)
+        };
+
+        // 把结果添加到列表
+        report.games.push(GameMeta { source, ..game });
+    }
+
+    Ok(())
+}
+
+// 读取游戏包
+// 名字和描述优先用清单里的,没写的话再去读脚本的GAME_META
+fn load_package(
+    package_dir: &Path,
+    cache: &mut MetaCache,
+    used: &mut MetaCache,
+) -> Result<GameMeta> {
+    let manifest = GameManifest::load(package_dir)?;
+    let script_path = manifest.entry_path(package_dir);
+    if !script_path.is_file() {
+        return Err(anyhow!("{}: entry script not found", script_path.display()));
+    }
+
+    let name = manifest.name.filter(|v| !v.trim().is_empty());
+    let description = manifest.description.filter(|v| !v.trim().is_empty());
+    let mut meta = read_script_meta(&script_path, cache, used);
+    // 清单已经写全了名字和描述,GAME_META的问题就不用提示了
+    if name.is_some() && description.is_some() {
+        meta.warnings.clear();
+    }
+
+    let id = manifest.id.trim().to_string();
+    Ok(GameMeta {
+        name: name.or(meta.name).unwrap_or_else(|| id.replace('_', " ")),
+        description: description
+            .or(meta.description)
+            .unwrap_or_else(|| "No description available.".to_string()),
+        id,
+        script_path,
+        package_dir: Some(package_dir.to_path_buf()),
+        version: manifest.version,
+        authors: manifest.authors,
+        license: manifest.license,
+        min_engine_version: manifest.min_engine_version,
+        assets: manifest.assets,
+        languages: manifest.languages,
+        tags: normalize_tags(if manifest.tags.is_empty() {
+            meta.tags
+        } else {
+            manifest.tags
+        }),
+        warnings: meta.warnings,
+        enabled: manifest.enabled.or(meta.enabled).unwrap_or(true),
+        api_level: manifest.api_level.or(meta.api_level),
+        capabilities: if manifest.capabilities.is_empty() {
+            meta.capabilities
+        } else {
+            manifest.capabilities
+        },
+        records: manifest.records.or(meta.records),
+        achievements: if manifest.achievements.is_empty() {
+            meta.achievements
+        } else {
+            manifest.achievements
+        },
+        leaderboard: manifest.leaderboard.or(meta.leaderboard),
+        actions: if manifest.actions.is_empty() {
+            meta.actions
+        } else {
+            manifest.actions
+        },
+        min_size: manifest.min_size.or(meta.min_size),
+        ..GameMeta::default()
+    })
+}
 
+// 读取单文件游戏
+fn load_script(path: PathBuf, cache: &mut MetaCache, used: &mut MetaCache) -> GameMeta {
+    // 从文件名获取ID
+    let id = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let meta = read_script_meta(&path, cache, used);
+
+    GameMeta {
         // 默认名字会将下划线替换为空格
-        let mut name = id.replace('_', " ");
-        let mut description = "No description available.".to_string();
-
-        if let Ok(content) = fs::read_to_string(&path) {
-            // 去除UTF-8BOM
-            let content = content.trim_start_matches('\u{feff}');
-
-            // 创建临时Lua环境执行脚本
-            let lua = Lua::new();
-            if lua.load(content).exec().is_ok() {
-                let globals = lua.globals();
-
-                // 查找GAME_META表
-                if let Ok(meta) = globals.get::<Table>("GAME_META") {
-                    // 读取name字段
-                    if let Ok(v) = meta.get::<String>("name") {
-                        if !v.trim().is_empty() {
-                            name = v;
+        name: meta.name.unwrap_or_else(|| id.replace('_', " ")),
+        description: meta
+            .description
+            .unwrap_or_else(|| "No description available.".to_string()),
+        id,
+        script_path: path,
+        warnings: meta.warnings,
+        enabled: meta.enabled.unwrap_or(true),
+        api_level: meta.api_level,
+        capabilities: meta.capabilities,
+        tags: normalize_tags(meta.tags),
+        records: meta.records,
+        achievements: meta.achievements,
+        leaderboard: meta.leaderboard,
+        actions: meta.actions,
+        min_size: meta.min_size,
+        ..GameMeta::default()
+    }
+}
+
+// 标签统一成小写并去掉空白和重复的
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+// 读取脚本的GAME_META,修改时间没变就直接用缓存
+fn read_script_meta(path: &Path, cache: &mut MetaCache, used: &mut MetaCache) -> CachedMeta {
+    let key = path.to_string_lossy().into_owned();
+    let mtime_ns = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+
+    let meta = match cache.entries.remove(&key) {
+        Some(cached) if cached.mtime_ns == mtime_ns && mtime_ns != 0 => cached,
+        _ => {
+            let mut meta = match fs::read_to_string(path) {
+                // 去除UTF-8BOM
+                Ok(content) => static_game_meta(content.trim_start_matches('\u{feff}')),
+                Err(_) => CachedMeta {
+                    warnings: vec!["scan.warning.unreadable".to_string()],
+                    ..CachedMeta::default()
+                },
+            };
+            meta.mtime_ns = mtime_ns;
+            meta
+        }
+    };
+
+    used.entries.insert(key, meta.clone());
+    meta
+}
+
+// 不执行整个脚本,只把GAME_META = {...}这一段拿出来
+// 放到没有标准库的Lua里用空的环境表求值,基础库的dofile、load、print也拿不到
+// 并限制指令数和内存
+fn static_game_meta(source: &str) -> CachedMeta {
+    let mut meta = CachedMeta::default();
+
+    let Some(table_src) = find_game_meta_table(source) else {
+        meta.warnings.push("scan.warning.meta_missing".to_string());
+        return meta;
+    };
+
+    let result = Lua::new_with(StdLib::NONE, LuaOptions::default()).and_then(|lua| {
+        lua.set_memory_limit(1 << 20)?;
+        lua.set_hook(HookTriggers::new().every_nth_instruction(10_000), |_, _| {
+            Err(mlua::Error::runtime("GAME_META evaluation is too long"))
+        })?;
+        let table: Table = lua
+            .load(format!("return {table_src}"))
+            .set_environment(lua.create_table()?)
+            .eval()?;
+        let get = |key: &str| {
+            table
+                .get::<String>(key)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+        };
+        meta.name = get("name");
+        meta.description = get("description");
+        meta.enabled = table.get::<Option<bool>>("enabled")?;
+        meta.api_level = table.get::<Option<u32>>("api_level")?;
+        meta.capabilities = table
+            .get::<Option<Vec<String>>>("capabilities")?
+            .unwrap_or_default();
+        meta.tags = table
+            .get::<Option<Vec<String>>>("tags")?
+            .unwrap_or_default();
+        if let Some(records) = table.get::<Option<Table>>("records")? {
+            // 写错的记录跳过,其余的照常显示
+            let mut specs = Vec::new();
+            for entry in records.sequence_values::<Value>() {
+                let spec = match entry? {
+                    Value::Table(entry) => record_spec_from_table(&entry),
+                    _ => None,
+                };
+                match spec {
+                    Some(spec) => specs.push(spec),
+                    None => {
+                        let key = "scan.warning.invalid_records".to_string();
+                        if !meta.warnings.contains(&key) {
+                            meta.warnings.push(key);
+                        }
+                    }
+                }
+            }
+            meta.records = Some(specs);
+        }
+        if let Some(achievements) = table.get::<Option<Table>>("achievements")? {
+            for entry in achievements.sequence_values::<Value>() {
+                let spec = match entry? {
+                    Value::Table(entry) => achievement_spec_from_table(&entry),
+                    _ => None,
+                };
+                match spec {
+                    Some(spec) if !meta.achievements.iter().any(|a| a.id == spec.id) => {
+                        meta.achievements.push(spec);
+                    }
+                    _ => {
+                        let key = "scan.warning.invalid_achievements".to_string();
+                        if !meta.warnings.contains(&key) {
+                            meta.warnings.push(key);
                         }
                     }
-                    // 读取description字段
-                    if let Ok(v) = meta.get::<String>("description") {
-                        if !v.trim().is_empty() {
-                            description = v;
+                }
+            }
+        }
+        if let Some(leaderboard) = table.get::<Option<Table>>("leaderboard")? {
+            meta.leaderboard = leaderboard_spec_from_table(&leaderboard);
+            if meta.leaderboard.is_none() {
+                meta.warnings
+                    .push("scan.warning.invalid_leaderboard".to_string());
+            }
+        }
+        if let Some(actions) = table.get::<Option<Table>>("actions")? {
+            for entry in actions.sequence_values::<Value>() {
+                let spec = match entry? {
+                    Value::Table(entry) => action_spec_from_table(&entry),
+                    _ => None,
+                };
+                match spec {
+                    Some(spec) if !meta.actions.iter().any(|a| a.name == spec.name) => {
+                        meta.actions.push(spec);
+                    }
+                    _ => {
+                        let key = "scan.warning.invalid_actions".to_string();
+                        if !meta.warnings.contains(&key) {
+                            meta.warnings.push(key);
                         }
                     }
                 }
             }
         }
+        if let Some(min_size) = table.get::<Option<Table>>("min_size")? {
+            meta.min_size = min_size_from_table(&min_size);
+            if meta.min_size.is_none() {
+                meta.warnings
+                    .push("scan.warning.invalid_min_size".to_string());
+            }
+        }
+        Ok(())
+    });
 
-        // 把结果添加到列表
-        games.push(GameMeta {
-            id,
-            name,
-            description,
-            script_path: path,
-        });
+    match result {
+        Ok(()) if meta.description.is_none() => {
+            meta.warnings
+                .push("scan.warning.no_description".to_string());
+        }
+        Ok(()) => {}
+        Err(_) => {
+            meta = CachedMeta::default();
+            meta.warnings.push("scan.warning.meta_dynamic".to_string());
+        }
     }
+    meta
+}
 
-    Ok(games)
+// 读取GAME_META.records里的一条记录声明
+// 没有名字或者unit、better写错的返回None
+fn record_spec_from_table(table: &Table) -> Option<RecordSpec> {
+    let text = |key: &str| {
+        table
+            .get::<Option<String>>(key)
+            .ok()
+            .flatten()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let name = text("name")?;
+    let unit = match text("unit") {
+        Some(raw) => RecordUnit::parse(&raw)?,
+        None => RecordUnit::default(),
+    };
+    let better = match text("better") {
+        Some(raw) => RecordBetter::parse(&raw)?,
+        None => RecordBetter::default(),
+    };
+    Some(RecordSpec {
+        name,
+        label: text("label"),
+        unit,
+        better,
+        dimension: text("dimension"),
+    })
+}
+
+// 读取GAME_META.leaderboard
+// unit、better写错的返回None
+fn leaderboard_spec_from_table(table: &Table) -> Option<LeaderboardSpec> {
+    let text = |key: &str| {
+        table
+            .get::<Option<String>>(key)
+            .ok()
+            .flatten()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let unit = match text("unit") {
+        Some(raw) => RecordUnit::parse(&raw)?,
+        None => RecordUnit::default(),
+    };
+    let better = match text("better") {
+        Some(raw) => RecordBetter::parse(&raw)?,
+        None => RecordBetter::default(),
+    };
+    Some(LeaderboardSpec {
+        unit,
+        better,
+        dimension: text("dimension"),
+    })
+}
+
+// 读取GAME_META.achievements里的一条成就声明
+// 没有id或者target不是正整数的返回None
+fn achievement_spec_from_table(table: &Table) -> Option<AchievementSpec> {
+    let text = |key: &str| {
+        table
+            .get::<Option<String>>(key)
+            .ok()
+            .flatten()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let target = match table.get::<Option<i64>>("target").ok()? {
+        Some(target) if target > 0 => Some(target as u64),
+        Some(_) => return None,
+        None => None,
+    };
+    Some(AchievementSpec {
+        id: text("id")?,
+        title: text("title"),
+        description: text("description"),
+        hidden: table.get::<Option<bool>>("hidden").ok()?.unwrap_or(false),
+        target,
+    })
+}
+
+// 读取GAME_META.actions里的一个动作
+// 没有名字或者keys不是字符串列表的返回None
+fn action_spec_from_table(table: &Table) -> Option<ActionSpec> {
+    let text = |key: &str| {
+        table
+            .get::<Option<String>>(key)
+            .ok()
+            .flatten()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    Some(ActionSpec {
+        name: text("name")?,
+        keys: table
+            .get::<Option<Vec<String>>>("keys")
+            .ok()?
+            .unwrap_or_default(),
+        label: text("label"),
+    })
+}
+
+// 读取GAME_META.min_size = { width = 40, height = 20 }
+// 宽高缺一个或者不是正整数的返回None
+fn min_size_from_table(table: &Table) -> Option<MinSize> {
+    let dimension = |key: &str| {
+        table
+            .get::<Option<i64>>(key)
+            .ok()
+            .flatten()
+            .filter(|v| *v > 0)
+            .and_then(|v| u16::try_from(v).ok())
+    };
+    Some(MinSize {
+        width: dimension("width")?,
+        height: dimension("height")?,
+    })
+}
+
+// 找到顶层的GAME_META = { ... }并返回花括号里的完整表构造
+// 会跳过字符串和注释里的花括号
+fn find_game_meta_table(source: &str) -> Option<&str> {
+    let mut search_from = 0;
+    while let Some(found) = source[search_from..].find("GAME_META") {
+        let start = search_from + found;
+        search_from = start + "GAME_META".len();
+
+        // 必须是行首的赋值语句
+        let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        if !source[line_start..start].trim().is_empty() {
+            continue;
+        }
+        let rest = source[search_from..].trim_start();
+        let Some(rest) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        if !rest.starts_with('{') {
+            continue;
+        }
+
+        let table_start = source.len() - rest.len();
+        return table_end(rest).map(|len| &source[table_start..table_start + len]);
+    }
+    None
+}
+
+// 返回从{开始到配对的}结束的字节长度
+fn table_end(src: &str) -> Option<usize> {
+    let bytes = src.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0usize;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'[' if long_bracket_level(&src[i..]).is_some() => {
+                i += skip_long_bracket(&src[i..])?;
+                continue;
+            }
+            b'-' if src[i..].starts_with("--") => {
+                if long_bracket_level(&src[i + 2..]).is_some() {
+                    i += 2 + skip_long_bracket(&src[i + 2..])?;
+                } else {
+                    i += src[i..].find('\n').unwrap_or(src.len() - i);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+// [[ 或 [==[ 返回等号个数
+fn long_bracket_level(src: &str) -> Option<usize> {
+    let rest = src.strip_prefix('[')?;
+    let level = rest.bytes().take_while(|b| *b == b'=').count();
+    (rest.as_bytes().get(level) == Some(&b'[')).then_some(level)
+}
+
+// 跳过整个长括号,返回消耗的字节数
+fn skip_long_bracket(src: &str) -> Option<usize> {
+    let level = long_bracket_level(src)?;
+    let close = format!("]{}]", "=".repeat(level));
+    let body = level + 2;
+    src[body..].find(&close).map(|end| body + end + close.len())
+}
+
+fn load_meta_cache() -> MetaCache {
+    path_utils::scan_cache_file()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str::<MetaCache>(&raw).ok())
+        .filter(|cache| cache.version == META_CACHE_VERSION)
+        .unwrap_or_default()
+}
+
+fn save_meta_cache(cache: &MetaCache) -> Result<()> {
+    let path = path_utils::scan_cache_file()?;
+    path_utils::ensure_parent_dir(&path)?;
+    fs::write(path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{
+        GameManifest, MetaCache, MinSize, find_game_meta_table, load_package, static_game_meta,
+    };
+    use crate::app::stats::{RecordBetter, RecordUnit};
+
+    #[test]
+    fn package_manifest_overrides_script_meta() {
+        let dir = std::env::temp_dir()
+            .join("tui-game-package-test")
+            .join("demo");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("game.json"),
+            r#"{"id": "demo_pkg", "version": "1.2.0", "authors": ["A"], "tags": ["puzzle"]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("main.lua"),
+            r#"GAME_META = { name = "Demo", description = "From script" }"#,
+        )
+        .unwrap();
+
+        let game =
+            load_package(&dir, &mut MetaCache::default(), &mut MetaCache::default()).unwrap();
+        assert_eq!(game.id, "demo_pkg");
+        assert_eq!(game.name, "Demo");
+        assert_eq!(game.version.as_deref(), Some("1.2.0"));
+        assert_eq!(game.tags, vec!["puzzle".to_string()]);
+        assert_eq!(game.script_path, dir.join("main.lua"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn package_manifest_paths_must_stay_inside_the_package() {
+        let dir = std::env::temp_dir()
+            .join("tui-game-package-test")
+            .join("escape");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("main.lua"), "GAME_META = {}").unwrap();
+
+        for manifest in [
+            r#"{"id": "escape", "entry": "../main.lua"}"#,
+            r#"{"id": "escape", "entry": "/tmp/main.lua"}"#,
+            r#"{"id": "escape", "assets": ["assets/../../words.json"]}"#,
+        ] {
+            fs::write(dir.join("game.json"), manifest).unwrap();
+            assert!(GameManifest::load(&dir).is_err(), "{manifest}");
+        }
+
+        fs::write(
+            dir.join("game.json"),
+            r#"{"id": "escape", "entry": "./main.lua", "assets": ["assets/words.json"]}"#,
+        )
+        .unwrap();
+        assert!(GameManifest::load(&dir).is_ok());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn game_meta_table_is_found_without_running_script() {
+        let source = "-- GAME_META = { bad }\nGAME_META = {\n    name = \"A }\", -- }\n    description = [[B]]\n}\nos.exit()\n";
+        assert_eq!(
+            find_game_meta_table(source),
+            Some("{\n    name = \"A }\", -- }\n    description = [[B]]\n}")
+        );
+    }
+
+    #[test]
+    fn game_meta_cannot_reach_base_library() {
+        let meta = static_game_meta(
+            r#"GAME_META = { name = (dofile or print or load) and "leaked" or "safe" }"#,
+        );
+        assert_eq!(meta.name.as_deref(), Some("safe"));
+
+        let meta = static_game_meta(r#"GAME_META = { name = dofile("/etc/passwd") }"#);
+        assert_eq!(meta.name, None);
+    }
+
+    #[test]
+    fn game_meta_records_skip_invalid_entries() {
+        let meta = static_game_meta(
+            r#"GAME_META = { description = "D", records = {
+                { name = "best_time", unit = "seconds", better = "lower", dimension = "difficulty" },
+                { name = "bad", unit = "parsecs" },
+                { label = "no name" }
+            } }"#,
+        );
+        let records = meta.records.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].unit, RecordUnit::Seconds);
+        assert_eq!(records[0].better, RecordBetter::Lower);
+        assert_eq!(records[0].dimension.as_deref(), Some("difficulty"));
+        assert_eq!(meta.warnings, vec!["scan.warning.invalid_records".to_string()]);
+    }
+
+    #[test]
+    fn game_meta_achievements_skip_invalid_entries() {
+        let meta = static_game_meta(
+            r#"GAME_META = { description = "D", achievements = {
+                { id = "tetris_four", hidden = true },
+                { id = "lines", target = 100 },
+                { id = "lines" },
+                { id = "zero", target = 0 },
+                { title = "no id" }
+            } }"#,
+        );
+        let ids: Vec<&str> = meta.achievements.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["tetris_four", "lines"]);
+        assert!(meta.achievements[0].hidden);
+        assert_eq!(meta.achievements[1].target, Some(100));
+        assert_eq!(
+            meta.warnings,
+            vec!["scan.warning.invalid_achievements".to_string()]
+        );
+    }
+
+    #[test]
+    fn game_meta_min_size_needs_both_dimensions() {
+        let meta = static_game_meta(
+            r#"GAME_META = { description = "D", min_size = { width = 36, height = 26 } }"#,
+        );
+        assert_eq!(
+            meta.min_size,
+            Some(MinSize {
+                width: 36,
+                height: 26
+            })
+        );
+
+        let meta =
+            static_game_meta(r#"GAME_META = { description = "D", min_size = { width = 36 } }"#);
+        assert_eq!(meta.min_size, None);
+        assert_eq!(
+            meta.warnings,
+            vec!["scan.warning.invalid_min_size".to_string()]
+        );
+    }
 }
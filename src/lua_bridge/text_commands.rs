@@ -0,0 +1,188 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use mlua::{Function, Lua, Table, Value};
+
+use crate::app::i18n;
+use crate::app::rich_text::{self, CommandEffect, CommandHandler, StyleTarget};
+use crate::lua_bridge::api::register_translate;
+use crate::utils::path_utils;
+
+// 直接借用游戏脚本所在Lua环境的TEXT_COMMANDS表
+pub struct LuaCommands<'a>(pub &'a Lua);
+
+// 独立的文本指令环境
+// 给Rust页面(游戏详情等)使用,不需要启动游戏也能用自定义指令
+pub struct TextCommandRuntime {
+    lua: Lua,
+}
+
+impl TextCommandRuntime {
+    // 按脚本路径加载对应的text_function目录
+    pub fn for_script(script_path: &Path) -> mlua::Result<Self> {
+        let lua = Lua::new();
+        register_translate(&lua)?;
+        load_text_functions(&lua, script_path)?;
+        Ok(Self { lua })
+    }
+}
+
+impl CommandHandler for TextCommandRuntime {
+    fn apply(&self, cmd: &str, params: &[String]) -> Option<Result<CommandEffect, String>> {
+        LuaCommands(&self.lua).apply(cmd, params)
+    }
+}
+
+impl CommandHandler for LuaCommands<'_> {
+    fn apply(&self, cmd: &str, params: &[String]) -> Option<Result<CommandEffect, String>> {
+        // 获取TEXT_COMMANDS表,没有注册任何命令就交给内置指令
+        let commands = self.0.globals().get::<Table>("TEXT_COMMANDS").ok()?;
+        // 没有找到这个指令
+        let func = commands.get::<Function>(cmd).ok()?;
+        Some(call_command(self.0, cmd, &func, params))
+    }
+}
+
+// 调用Lua自定义指令
+fn call_command(
+    lua: &Lua,
+    cmd: &str,
+    func: &Function,
+    params: &[String],
+) -> Result<CommandEffect, String> {
+    let invalid = || i18n::t("rich_text.error.invalid_custom_command");
+
+    // 将参数列表转换为Lua表
+    let ptable = lua.create_table().map_err(|_| invalid())?;
+    for (idx, p) in params.iter().enumerate() {
+        ptable
+            .set((idx + 1) as i64, p.as_str())
+            .map_err(|_| invalid())?;
+    }
+
+    // 调用Lua函数,并验证返回值是否是一个表
+    let t = match func.call::<Value>(ptable) {
+        Ok(Value::Table(t)) => t,
+        Ok(_) => return Err(i18n::t("rich_text.error.invalid_return_value")),
+        Err(_) => return Err(invalid()),
+    };
+
+    // 检查是否有错误
+    if let Ok(msg) = t.get::<String>("error")
+        && !msg.trim().is_empty()
+    {
+        return Err(invalid());
+    }
+
+    // tc/bg以外的指令需要用target声明作用在文字还是背景上
+    let target = match rich_text::builtin_target(cmd) {
+        Some(target) => target,
+        None => match t.get::<String>("target").ok().as_deref() {
+            Some("tc") | Some("fg") => StyleTarget::Fg,
+            Some("bg") => StyleTarget::Bg,
+            _ => return Err(i18n::t("rich_text.error.unknown_command")),
+        },
+    };
+
+    // 解析返回值,颜色复用统一的参数校验
+    if t.get::<bool>("clear").unwrap_or(false) {
+        return rich_text::parse_color_params(target, &["clear".to_string()]);
+    }
+    let color = t
+        .get::<String>("color")
+        .map_err(|_| i18n::t("rich_text.error.invalid_param"))?;
+    let count = t
+        .get::<i64>("count")
+        .ok()
+        .filter(|v| *v > 0)
+        .map(|v| v.to_string())
+        .unwrap_or_default();
+    rich_text::parse_color_params(target, &[color, count])
+}
+
+// 加载并注册所有文本命令函数
+pub fn load_text_functions(lua: &Lua, script_path: &Path) -> mlua::Result<()> {
+    // 获取Lua的全局环境
+    let globals = lua.globals();
+    // 检查是否存在TEXT_COMMANDS表
+    if globals.get::<Table>("TEXT_COMMANDS").is_err() {
+        // 不存在就创建空表
+        globals.set("TEXT_COMMANDS", lua.create_table()?)?;
+    }
+
+    // 给Lua注册函数，用于添加自定文本命令
+    let register = lua.create_function(|lua, (name, func): (String, Function)| {
+        let globals = lua.globals();
+        // 获取 TEXT_COMMANDS 表
+        let table = match globals.get::<Table>("TEXT_COMMANDS") {
+            Ok(t) => t,
+            Err(_) => {
+                let t = lua.create_table()?;
+                globals.set("TEXT_COMMANDS", t.clone())?;
+                t
+            }
+        };
+        // 将函数存入表中
+        table.set(name.trim().to_ascii_lowercase(), func)?;
+        Ok(true)
+    })?;
+    globals.set("register_text_command", register)?;
+
+    // 逐个加载文件并执行代码
+    for file in text_function_files(script_path).map_err(mlua::Error::external)? {
+        let source = fs::read_to_string(&file).map_err(mlua::Error::external)?;
+        let source = source.trim_start_matches('\u{feff}');
+        lua.load(source)
+            .set_name(file.to_string_lossy().as_ref())
+            .exec()?;
+    }
+
+    Ok(())
+}
+
+// 构建搜索路径,收集所有文本指令脚本
+fn text_function_files(script_path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut dirs = Vec::<PathBuf>::new();
+    if let Some(parent) = script_path.parent() {
+        dirs.push(parent.join("text_function"));
+        if parent.file_name().and_then(|s| s.to_str()) == Some("game")
+            && let Some(root) = parent.parent()
+        {
+            dirs.push(root.join("text_function"));
+        }
+    }
+    if let Ok(scripts_dir) = path_utils::scripts_dir() {
+        dirs.push(scripts_dir.join("text_function"));
+    }
+
+    // 移除重复的目录路径
+    let mut unique_dirs = Vec::<PathBuf>::new();
+    for dir in dirs {
+        if !unique_dirs.iter().any(|d| d == &dir) {
+            unique_dirs.push(dir);
+        }
+    }
+
+    let mut files = Vec::new();
+    for dir in unique_dirs {
+        // 不存在就跳过
+        if !dir.is_dir() {
+            continue;
+        }
+
+        // 过滤lua文件并排序
+        let mut entries: Vec<PathBuf> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.eq_ignore_ascii_case("lua"))
+                    .unwrap_or(false)
+            })
+            .collect();
+        entries.sort();
+        files.extend(entries);
+    }
+
+    Ok(files)
+}
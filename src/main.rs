@@ -5,6 +5,7 @@ mod updater;
 mod utils;
 
 use std::io::{self, Stdout};
+use std::path::PathBuf;
 use std::process::Command;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -17,17 +18,28 @@ use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_ra
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 
+use crate::app::achievements_page::{AchievementsAction, AchievementsPage};
+use crate::app::backup_page::{BackupAction, BackupPage};
+use crate::app::game_manager::{GameManager, GameManagerAction};
 use crate::app::game_selection::{GameSelection, GameSelectionAction};
-use crate::app::i18n;
+use crate::app::profiles_page::{ProfilesAction, ProfilesPage};
+use crate::app::statistics::{StatisticsAction, StatisticsPage};
+use crate::app::history::{self, HistoryEntry};
+use crate::app::keymap::Keymap;
+use crate::app::keymap_page::{KeymapAction, KeymapPage};
+use crate::app::{
+    activity, backup, game_library, i18n, i18n_check, profiles, settings_store, stats,
+};
 use crate::app::layout::{MENU_MIN_HEIGHT, MENU_MIN_WIDTH};
 use crate::app::menu::{Menu, MenuAction};
 use crate::app::placeholder_pages::{self, PlaceholderPage};
 use crate::app::settings;
 use crate::lua_bridge::api::{
-    LaunchMode, clear_active_game_save, latest_saved_game_id, run_game_script,
+    GameOutcome, LaunchMode, clear_active_game_save, latest_saved_game_id, run_game_script,
     take_terminal_dirty_from_lua,
 };
-use crate::lua_bridge::script_loader::{GameMeta, scan_scripts};
+use crate::lua_bridge::compat;
+use crate::lua_bridge::script_loader::{GameMeta, scan_games, scan_scripts};
 use crate::terminal::size_watcher;
 use crate::updater::github::{
     CURRENT_VERSION_TAG, UpdateNotification, Updater, UpdaterEvent, run_external_update_script,
@@ -42,10 +54,21 @@ pub enum AppState {
     GameSelection { ui: GameSelection },
     // 设置页
     Settings { ui: settings::SettingsState },
-    // 关于页
-    About,
+    // 游戏管理页
+    GameManager { ui: Box<GameManager> },
+    // 游玩统计页
+    Statistics { ui: Box<StatisticsPage> },
+    Achievements { ui: Box<AchievementsPage> },
+    // 按键设置页
+    Keymap { ui: Box<KeymapPage> },
+    // 备份与恢复页
+    Backup { ui: Box<BackupPage> },
+    // 档案切换页
+    Profiles { ui: Box<ProfilesPage> },
+    // 关于页,按键位在进入时读取
+    About { keymap: Keymap },
     // 游戏继续
-    Continue,
+    Continue { keymap: Keymap },
     // 退出
     Exiting,
 }
@@ -112,13 +135,65 @@ fn main() {
 fn run() -> Result<()> {
     // 安装 panic hook
     install_panic_hook();
-    // 初始化i18n
+    // 数据目录要在读任何数据之前确定并建好,旧版本程序旁边的数据也在这时搬走
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(dir) = take_data_dir_arg(&mut args)? {
+        path_utils::set_data_dir_override(dir);
+    }
+    let data_dir = data_dir_display();
+    let migration = path_utils::migrate_legacy_data_dir().and_then(|migrated| {
+        path_utils::ensure_data_dirs()?;
+        Ok(migrated)
+    });
+    // 新目录用不了时不退出,没有指定目录时这次先用旧目录,都用不了时不保存进度,启动后提示玩家
+    let fallback = migration.is_err().then(path_utils::use_legacy_data_dir);
+
+    // 先选择档案,再按档案里的语言设置初始化i18n
+    let profile = take_profile_arg(&mut args)?;
+    profiles::init(profile.as_deref())?;
     i18n::init("us-en")?;
 
+    let migration_notice = match migration {
+        Ok(None) => None,
+        Ok(Some(legacy)) => Some(i18n::t_args(
+            "menu.data_migrated",
+            &[
+                ("from", legacy.display().to_string().into()),
+                ("to", data_dir.into()),
+            ],
+        )),
+        Err(err) => Some(match fallback {
+            Some(Ok(Some(legacy))) => i18n::t_args(
+                "menu.data_migration_failed",
+                &[
+                    ("from", legacy.display().to_string().into()),
+                    ("to", data_dir.into()),
+                    ("error", format!("{err:#}").into()),
+                ],
+            ),
+            _ => i18n::t_args(
+                "menu.data_dir_unusable",
+                &[
+                    ("dir", data_dir.into()),
+                    ("error", format!("{err:#}").into()),
+                ],
+            ),
+        }),
+    };
+
+    // 命令行子命令,执行完直接退出不进入界面
+    if let Some((command, rest)) = args.split_first() {
+        if let Some(notice) = &migration_notice {
+            eprintln!("{notice}");
+        }
+        return run_cli_command(command, rest);
+    }
+
     // 初始终端会话
     let mut session = TerminalSession::new()?;
-    // 启动更新检查
-    let updater = Updater::spawn(CURRENT_VERSION_TAG);
+    // 启动更新检查,设置里关掉时不联网
+    let updater = settings_store::get_bool("check_updates")
+        .then(|| Updater::spawn(CURRENT_VERSION_TAG));
 
     // 初始化主状态和全局变量
     let mut update_notification: Option<UpdateNotification> = None;
@@ -127,7 +202,11 @@ fn run() -> Result<()> {
     // 处理版本字符串
     let runtime_version = normalized_tag(CURRENT_VERSION_TAG);
     // 主页状态
-    let mut state = AppState::MainMenu { menu: Menu::new() };
+    let mut menu = Menu::new();
+    if let Some(notice) = migration_notice {
+        menu.show_notice(notice);
+    }
+    let mut state = AppState::MainMenu { menu };
     let mut pending_new_game_start: Option<PendingNewGameStart> = None;
     // 是否准备卸载
     let mut should_run_uninstall = false;
@@ -139,7 +218,7 @@ fn run() -> Result<()> {
         let frame_start = Instant::now();
 
         // 更新检查
-        while let Some(event) = updater.try_recv() {
+        while let Some(event) = updater.as_ref().and_then(Updater::try_recv) {
             match event {
                 UpdaterEvent::LatestVersion(latest) => {
                     latest_release_version = latest.latest_version;
@@ -201,18 +280,38 @@ fn run() -> Result<()> {
                 AppState::Settings { ui } => {
                     settings::render(frame, ui);
                 }
-                AppState::About => {
+                AppState::GameManager { ui } => {
+                    ui.render(frame, frame.area());
+                }
+                AppState::Statistics { ui } => {
+                    ui.render(frame, frame.area());
+                }
+                AppState::Achievements { ui } => {
+                    ui.render(frame, frame.area());
+                }
+                AppState::Profiles { ui } => {
+                    ui.render(frame, frame.area());
+                }
+                AppState::Keymap { ui } => {
+                    ui.render(frame, frame.area());
+                }
+                AppState::Backup { ui } => {
+                    ui.render(frame, frame.area());
+                }
+                AppState::About { keymap } => {
                     placeholder_pages::render_placeholder(
                         frame,
                         PlaceholderPage::About,
+                        keymap,
                         runtime_version.as_str(),
                         Some(latest_release_version.as_str()),
                     );
                 }
-                AppState::Continue => {
+                AppState::Continue { keymap } => {
                     placeholder_pages::render_placeholder(
                         frame,
                         PlaceholderPage::Continue,
+                        keymap,
                         runtime_version.as_str(),
                         None,
                     );
@@ -247,7 +346,13 @@ fn minimum_size_for_state(state: &AppState) -> (u16, u16) {
         AppState::MainMenu { .. } => (MENU_MIN_WIDTH, MENU_MIN_HEIGHT),
         AppState::GameSelection { ui } => ui.minimum_size(),
         AppState::Settings { ui } => settings::minimum_size(ui),
-        AppState::About | AppState::Continue => (MENU_MIN_WIDTH, MENU_MIN_HEIGHT),
+        AppState::GameManager { ui } => ui.minimum_size(),
+        AppState::Statistics { ui } => ui.minimum_size(),
+        AppState::Achievements { ui } => ui.minimum_size(),
+        AppState::Profiles { ui } => ui.minimum_size(),
+        AppState::Keymap { ui } => ui.minimum_size(),
+        AppState::Backup { ui } => ui.minimum_size(),
+        AppState::About { .. } | AppState::Continue { .. } => (MENU_MIN_WIDTH, MENU_MIN_HEIGHT),
         AppState::Exiting => (MENU_MIN_WIDTH, MENU_MIN_HEIGHT),
     }
 }
@@ -291,59 +396,59 @@ fn handle_key_event(
         // 逻辑处理还是太权威了
 
         // 主页页面按键处理
-        AppState::MainMenu { menu } => match key.code {
-            KeyCode::Up | KeyCode::Char('k') => menu.previous(),
-            KeyCode::Down | KeyCode::Char('j') => menu.next(),
-            KeyCode::Char(c) if c.is_ascii_digit() => {
-                if let Some(index) = c
-                    .to_digit(10)
-                    .map(|v| v as usize)
-                    .and_then(|v| v.checked_sub(1))
-                {
-                    menu.set_selected(index);
-                }
-            }
-            KeyCode::Esc => {
-                let _ = menu.select_by_shortcut(KeyCode::Esc);
+        // 方向、确认和档案键按键位设置,数字和ESC固定
+        AppState::MainMenu { menu } => {
+            if menu.take_notice() {
+                return Ok(());
             }
-            KeyCode::Enter => {
-                if let Some(action) = menu.selected_action() {
-                    if matches!(action, MenuAction::Continue) && !menu.can_continue() {
-                        return Ok(());
+            match menu.keymap().pick(key, &["up", "down", "confirm", "profiles"]) {
+                Some("up") => menu.previous(),
+                Some("down") => menu.next(),
+                Some("profiles") => {
+                    *state = AppState::Profiles {
+                        ui: Box::new(ProfilesPage::new()),
+                    };
+                }
+                Some(_) => {
+                    if let Some(action) = menu.selected_action() {
+                        if matches!(action, MenuAction::Continue) && !menu.can_continue() {
+                            return Ok(());
+                        }
+                        *state = apply_menu_action(action, menu.continue_game_id());
                     }
-                    *state = apply_menu_action(action, menu.continue_game_id());
                 }
+                None => match key.code {
+                    KeyCode::Char(c) if c.is_ascii_digit() => {
+                        if let Some(index) = c
+                            .to_digit(10)
+                            .map(|v| v as usize)
+                            .and_then(|v| v.checked_sub(1))
+                        {
+                            menu.set_selected(index);
+                        }
+                    }
+                    KeyCode::Esc => {
+                        let _ = menu.select_by_shortcut(KeyCode::Esc);
+                    }
+                    _ => {}
+                },
             }
-            _ => {}
-        },
+        }
         // 游戏列表选择按键处理
         AppState::GameSelection { ui } => {
             // 新游戏存档覆盖确认
             if pending_new_game_start.is_some() {
-                match key.code {
-                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                match ui.keymap().pick(key, &["yes", "no"]) {
+                    Some("yes") => {
                         let pending = pending_new_game_start.take();
                         if let Some(pending) = pending {
                             if let Err(err) = clear_active_game_save() {
                                 eprintln!("Failed to clear active save slot: {err:#}");
                             }
-                            if let Err(err) =
-                                run_game_script(&pending.target_game.script_path, LaunchMode::New)
-                            {
-                                eprintln!(
-                                    "Failed to run game '{}': {err:#}",
-                                    pending.target_game.id
-                                );
-                            }
-                            let games = scan_scripts().unwrap_or_default();
-                            *ui = GameSelection::new(games);
+                            *ui = run_game_and_return(&pending.target_game, LaunchMode::New);
                         }
                     }
-                    KeyCode::Char('n')
-                    | KeyCode::Char('N')
-                    | KeyCode::Char('q')
-                    | KeyCode::Char('Q')
-                    | KeyCode::Esc => {
+                    Some(_) => {
                         *pending_new_game_start = None;
                     }
                     _ => {}
@@ -363,7 +468,7 @@ fn handle_key_event(
                             let saved_game_name =
                                 i18n::t_or(&format!("game.{}.name", saved_game_id), &saved_game_id);
                             *pending_new_game_start = Some(PendingNewGameStart {
-                                target_game: game,
+                                target_game: *game,
                                 saved_game_name,
                             });
                             return Ok(());
@@ -371,11 +476,7 @@ fn handle_key_event(
                         if let Err(err) = clear_active_game_save() {
                             eprintln!("Failed to clear active save slot: {err:#}");
                         }
-                        if let Err(err) = run_game_script(&game.script_path, LaunchMode::New) {
-                            eprintln!("Failed to run game '{}': {err:#}", game.id);
-                        }
-                        let games = scan_scripts().unwrap_or_default();
-                        *ui = GameSelection::new(games);
+                        *ui = run_game_and_return(&game, LaunchMode::New);
                     }
                 }
             }
@@ -383,11 +484,26 @@ fn handle_key_event(
 
         // 设置按键处理
         AppState::Settings { ui } => {
-            match settings::handle_key(ui, key.code) {
+            match settings::handle_key(ui, key) {
                 settings::SettingsAction::None => {}
                 settings::SettingsAction::BackToMenu => {
                     *state = AppState::MainMenu { menu: Menu::new() };
                 }
+                settings::SettingsAction::Controls => {
+                    *state = AppState::Keymap {
+                        ui: Box::new(KeymapPage::new()),
+                    };
+                }
+                settings::SettingsAction::ManageGames => {
+                    *state = AppState::GameManager {
+                        ui: Box::new(GameManager::new()),
+                    };
+                }
+                settings::SettingsAction::Backup => {
+                    *state = AppState::Backup {
+                        ui: Box::new(BackupPage::new()),
+                    };
+                }
                 settings::SettingsAction::RunUninstall => {
                     if has_uninstall_script().unwrap_or(false) {
                         *should_run_uninstall = true;
@@ -397,13 +513,60 @@ fn handle_key_event(
             }
         }
 
+        // 游戏管理按键处理,返回时回到设置页
+        AppState::GameManager { ui } => {
+            if let Some(GameManagerAction::Back) = ui.handle_event(key) {
+                *state = AppState::Settings {
+                    ui: settings::SettingsState::at(settings::HubItem::ManageGames),
+                };
+            }
+        }
+
+        // 按键设置页按键处理,返回时回到设置页
+        AppState::Keymap { ui } => {
+            if let Some(KeymapAction::Back) = ui.handle_event(key) {
+                *state = AppState::Settings {
+                    ui: settings::SettingsState::at(settings::HubItem::Controls),
+                };
+            }
+        }
+
+        // 备份页按键处理,返回时回到设置页
+        AppState::Backup { ui } => {
+            if let Some(BackupAction::Back) = ui.handle_event(key) {
+                *state = AppState::Settings {
+                    ui: settings::SettingsState::at(settings::HubItem::Backup),
+                };
+            }
+        }
+
+        // 统计页按键处理
+        AppState::Statistics { ui } => {
+            if let Some(StatisticsAction::Back) = ui.handle_event(key) {
+                *state = AppState::MainMenu { menu: Menu::new() };
+            }
+        }
+
+        // 成就页按键处理
+        AppState::Achievements { ui } => {
+            if let Some(AchievementsAction::Back) = ui.handle_event(key) {
+                *state = AppState::MainMenu { menu: Menu::new() };
+            }
+        }
+
+        // 档案页按键处理,切换后回到主页
+        AppState::Profiles { ui } => {
+            if let Some(ProfilesAction::Back | ProfilesAction::Switched) = ui.handle_event(key) {
+                *state = AppState::MainMenu { menu: Menu::new() };
+            }
+        }
+
         // 关于按键处理
-        AppState::About | AppState::Continue => match key.code {
-            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+        AppState::About { keymap } | AppState::Continue { keymap } => {
+            if keymap.pick(key, &["close"]).is_some() {
                 *state = AppState::MainMenu { menu: Menu::new() }
             }
-            _ => {}
-        },
+        }
 
         // 退出无额外按键
         AppState::Exiting => {}
@@ -425,15 +588,10 @@ fn render_new_game_confirm(frame: &mut ratatui::Frame<'_>, saved_game_name: &str
     frame.render_widget(Clear, area);
 
     // i18n的键不要调用错了
-    let template = i18n::t(
+    let msg = i18n::t_args(
         "confirm.new_game_overwrite",
+        &[("game", saved_game_name.into())],
     );
-    // 文本
-    let msg = if template.contains("{game}") {
-        template.replace("{game}", saved_game_name)
-    } else {
-        format!("{template} {saved_game_name}")
-    };
 
     // 这里也是,之前就写错了一次
     let yes = i18n::t("confirm.new_game_yes");
@@ -462,15 +620,117 @@ fn render_new_game_confirm(frame: &mut ratatui::Frame<'_>, saved_game_name: &str
     frame.render_widget(p, center[1]);
 }
 
+// 运行游戏,结束后回到重新扫描过的游戏列表
+// 启动失败或者运行出错时在列表上显示原因
+fn run_game_and_return(game: &GameMeta, mode: LaunchMode) -> GameSelection {
+    // 能启动的游戏才记一次启动
+    if compat::launch_block(game).is_none()
+        && let Err(err) = activity::record_launch(&game.id)
+    {
+        eprintln!("Failed to record launch of '{}': {err:#}", game.id);
+    }
+    let result = run_game_script(game, mode);
+    let mut ui = GameSelection::new(scan_games().unwrap_or_default());
+    match result {
+        Ok(outcome) => {
+            record_outcome(game, mode, &outcome);
+            if let Some(error) = outcome.error {
+                ui.show_notice(error);
+            }
+        }
+        Err(err) => ui.show_notice(format!("{err:#}")),
+    }
+    ui
+}
+
+// 一局结束后在这里统一记录统计、累计时长和游玩历史
+// 时长由宿主计时,脚本没有上报也能记下最长时长
+fn record_outcome(game: &GameMeta, mode: LaunchMode, outcome: &GameOutcome) {
+    let score = outcome.score.unwrap_or(0).clamp(0, i64::from(u32::MAX)) as u32;
+    let _ = stats::update_game_stats(&game.id, score, outcome.duration_sec);
+    let _ = activity::record_session(
+        &game.id,
+        outcome.duration_sec,
+        outcome.outcome.is_completed(),
+    );
+    let _ = history::append(&HistoryEntry {
+        game_id: game.id.clone(),
+        mode: outcome.mode.clone(),
+        started_at: outcome.started_at,
+        duration_sec: outcome.duration_sec,
+        score: outcome.score,
+        outcome: outcome.outcome,
+        launch_mode: mode.as_str().to_string(),
+    });
+}
+
+// 处理命令行子命令
+fn run_cli_command(command: &str, args: &[String]) -> Result<()> {
+    match command {
+        // 检查语言包的完整性
+        "i18n-check" => {
+            if !i18n_check::run()? {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        // 安装、卸载和列出游戏
+        "install" | "uninstall" | "list" => game_library::run_cli(command, args),
+        // 导出和导入玩家数据
+        "export" | "import" => backup::run_cli(command, args),
+        _ => Err(anyhow::anyhow!("unknown command: {command}")),
+    }
+}
+
+// 从参数里取出--profile NAME或--profile=NAME
+fn take_profile_arg(args: &mut Vec<String>) -> Result<Option<String>> {
+    let Some(index) = args
+        .iter()
+        .position(|arg| arg == "--profile" || arg.starts_with("--profile="))
+    else {
+        return Ok(None);
+    };
+    let arg = args.remove(index);
+    if let Some(name) = arg.strip_prefix("--profile=") {
+        return Ok(Some(name.to_string()));
+    }
+    if index < args.len() {
+        return Ok(Some(args.remove(index)));
+    }
+    Err(anyhow::anyhow!("--profile needs a profile name"))
+}
+
+// 从参数里取出--data-dir DIR或--data-dir=DIR
+fn take_data_dir_arg(args: &mut Vec<String>) -> Result<Option<PathBuf>> {
+    let Some(index) = args
+        .iter()
+        .position(|arg| arg == "--data-dir" || arg.starts_with("--data-dir="))
+    else {
+        return Ok(None);
+    };
+    let arg = args.remove(index);
+    if let Some(dir) = arg.strip_prefix("--data-dir=") {
+        return Ok(Some(PathBuf::from(dir)));
+    }
+    if index < args.len() {
+        return Ok(Some(PathBuf::from(args.remove(index))));
+    }
+    Err(anyhow::anyhow!("--data-dir needs a directory"))
+}
+
+// 提示里显示的数据目录
+fn data_dir_display() -> String {
+    path_utils::app_data_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default()
+}
+
 // 将玩家的动作处理转换为AppState状态机
 fn apply_menu_action(action: MenuAction, continue_game_id: Option<&str>) -> AppState {
     match action {
         // 进入游戏列表
         MenuAction::Play => {
-            let games = match scan_scripts() {
-                Ok(found) => found,
-                Err(_) => Vec::new(),
-            };
+            let games = scan_games().unwrap_or_default();
             AppState::GameSelection {
                 ui: GameSelection::new(games),
             }
@@ -478,30 +738,38 @@ fn apply_menu_action(action: MenuAction, continue_game_id: Option<&str>) -> AppS
 
         // 继续游戏存档,并将上一级菜单设置为游戏列表
         MenuAction::Continue => {
-            if let Some(game_id) = continue_game_id {
-                let game = scan_scripts()
+            let game = continue_game_id.and_then(|game_id| {
+                scan_scripts()
                     .unwrap_or_default()
                     .into_iter()
-                    .find(|g| g.id.eq_ignore_ascii_case(game_id));
-                if let Some(game) = game {
-                    if let Err(err) = run_game_script(&game.script_path, LaunchMode::Continue) {
-                        eprintln!("Failed to continue game '{}': {err:#}", game.id);
-                    }
-                }
-            }
-            let games = scan_scripts().unwrap_or_default();
-            AppState::GameSelection {
-                ui: GameSelection::new(games),
-            }
+                    .find(|g| g.id.eq_ignore_ascii_case(game_id))
+            });
+            let ui = match game {
+                Some(game) => run_game_and_return(&game, LaunchMode::Continue),
+                None => GameSelection::new(scan_games().unwrap_or_default()),
+            };
+            AppState::GameSelection { ui }
         }
 
+        // 游玩统计页
+        MenuAction::Statistics => AppState::Statistics {
+            ui: Box::new(StatisticsPage::new()),
+        },
+
+        // 成就页
+        MenuAction::Achievements => AppState::Achievements {
+            ui: Box::new(AchievementsPage::new()),
+        },
+
         // 设置页
         MenuAction::Settings => AppState::Settings {
             ui: settings::SettingsState::new(),
         },
 
         // 关于页
-        MenuAction::About => AppState::About,
+        MenuAction::About => AppState::About {
+            keymap: Keymap::menu(),
+        },
 
         // 拜拜了您嘞
         MenuAction::Quit => AppState::Exiting,
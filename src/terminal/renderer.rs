@@ -5,7 +5,7 @@ use crossterm::cursor::MoveTo;
 use crossterm::queue;
 use crossterm::style::Print;
 use crossterm::terminal::{Clear, ClearType};
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use unicode_width::UnicodeWidthChar;
 
 // 清理整个终端并把光标放到0，0的位置
 pub fn clear() -> Result<()> {
@@ -37,41 +37,50 @@ pub fn draw_text(x: u16, y: u16, text: &str) -> Result<()> {
 // 会保留单词完整性避免跨单词换行
 // 用到了unicode_width库
 pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
+    let cells: Vec<(char, ())> = text.chars().map(|ch| (ch, ())).collect();
+    wrap_cells(&cells, max_width)
+        .into_iter()
+        .map(|line| line.into_iter().map(|(ch, _)| ch).collect())
+        .collect()
+}
+
+// 带样式字符的换行
+// 规则和wrap_text完全一致,样式跟着字符走,给Lua的富文本排版用
+pub fn wrap_cells<T: Clone>(cells: &[(char, T)], max_width: usize) -> Vec<Vec<(char, T)>> {
     // 如果最大宽度位0,返回空字符串
     if max_width == 0 {
-        return vec![String::new()];
+        return vec![Vec::new()];
     }
 
     let mut lines = Vec::new();
 
     // 处理每一行(保留原始换行)
-    for raw_line in text.lines() {
+    for raw_line in split_cell_lines(cells) {
         // 如果整行宽度小于最大宽度,直接保留
-        if UnicodeWidthStr::width(raw_line) <= max_width {
-            lines.push(raw_line.to_string());
+        if cells_width(raw_line) <= max_width {
+            lines.push(raw_line.to_vec());
             continue;
         }
 
         // 当前正在构建的行
-        let mut current = String::new();
+        let mut current = Vec::new();
 
         // 当前行的显示宽度
         let mut width = 0;
 
         // 遍历每个字符
-        for ch in raw_line.chars() {
+        for cell in raw_line {
             // 获取字符的显示宽度(这个库汉字=2,字母=1)
-            let w = UnicodeWidthChar::width(ch).unwrap_or(0);
+            let w = UnicodeWidthChar::width(cell.0).unwrap_or(0);
 
             // 如果加上这个字符回超出宽度,且当行不为空
             if width + w > max_width && !current.is_empty() {
-                lines.push(current.clone()); // 保存当前行
-                current.clear(); // 开始新行
+                lines.push(std::mem::take(&mut current)); // 保存当前行并开始新行
                 width = 0;
             }
 
             // 添加字符到当前行
-            current.push(ch);
+            current.push(cell.clone());
             width += w;
         }
 
@@ -83,8 +92,114 @@ pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
 
     // 确保至少有一行
     if lines.is_empty() {
-        lines.push(String::new());
+        lines.push(Vec::new());
     }
 
     lines
 }
+
+// 按换行符拆分,行为和str::lines一致(去掉\r,末尾换行不产生空行)
+pub fn split_cell_lines<T>(cells: &[(char, T)]) -> Vec<&[(char, T)]> {
+    let mut lines: Vec<&[(char, T)]> = cells.split(|(ch, _)| *ch == '\n').collect();
+    if cells.last().is_some_and(|(ch, _)| *ch == '\n') || cells.is_empty() {
+        lines.pop();
+    }
+    lines
+        .into_iter()
+        .map(|line| match line.last() {
+            Some(('\r', _)) => &line[..line.len() - 1],
+            _ => line,
+        })
+        .collect()
+}
+
+// 计算一行带样式字符的显示宽度
+pub fn cells_width<T>(cells: &[(char, T)]) -> usize {
+    cells
+        .iter()
+        .map(|(ch, _)| UnicodeWidthChar::width(*ch).unwrap_or(0))
+        .sum()
+}
+
+// 超出宽度时截断,并在末尾放上省略号
+// 省略号沿用最后一个保留字符的样式
+pub fn truncate_cells<T: Clone>(
+    cells: &[(char, T)],
+    max_width: usize,
+    ellipsis: bool,
+) -> Vec<(char, T)> {
+    if cells_width(cells) <= max_width {
+        return cells.to_vec();
+    }
+
+    let budget = if ellipsis {
+        max_width.saturating_sub(1)
+    } else {
+        max_width
+    };
+    let mut out = Vec::new();
+    let mut width = 0;
+    for cell in cells {
+        let w = UnicodeWidthChar::width(cell.0).unwrap_or(0);
+        if width + w > budget {
+            break;
+        }
+        out.push(cell.clone());
+        width += w;
+    }
+
+    if ellipsis
+        && max_width > 0
+        && let Some(style) = out.last().or(cells.first()).map(|(_, style)| style.clone())
+    {
+        out.push(('\u{2026}', style));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_cell_lines, truncate_cells, wrap_cells};
+
+    fn cells(text: &str, style: u8) -> Vec<(char, u8)> {
+        text.chars().map(|ch| (ch, style)).collect()
+    }
+
+    fn text(line: &[(char, u8)]) -> String {
+        line.iter().map(|(ch, _)| ch).collect()
+    }
+
+    #[test]
+    fn split_cell_lines_matches_str_lines() {
+        for input in ["", "a", "a\n", "a\r\nb", "a\n\nb\n"] {
+            let lines: Vec<String> = split_cell_lines(&cells(input, 0))
+                .into_iter()
+                .map(text)
+                .collect();
+            let expected: Vec<&str> = input.lines().collect();
+            assert_eq!(lines, expected, "input {input:?}");
+        }
+    }
+
+    #[test]
+    fn wrap_cells_counts_wide_characters_and_keeps_styles() {
+        let mut input = cells("ab", 1);
+        input.extend(cells("汉字测试", 2));
+        let lines = wrap_cells(&input, 5);
+        let texts: Vec<String> = lines.iter().map(|line| text(line)).collect();
+        assert_eq!(texts, ["ab汉", "字测", "试"]);
+        assert_eq!(lines[0][2], ('汉', 2));
+        assert_eq!(lines[0][0], ('a', 1));
+
+        assert_eq!(wrap_cells(&cells("abc", 0), 0), vec![Vec::new()]);
+    }
+
+    #[test]
+    fn truncate_cells_never_splits_wide_characters() {
+        let input = cells("汉字测试", 3);
+        assert_eq!(text(&truncate_cells(&input, 5, true)), "汉字…");
+        assert_eq!(text(&truncate_cells(&input, 5, false)), "汉字");
+        assert_eq!(text(&truncate_cells(&input, 8, true)), "汉字测试");
+        assert_eq!(truncate_cells(&input, 4, true).last(), Some(&('…', 3)));
+    }
+}
@@ -1,7 +1,33 @@
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
 
 use anyhow::Result;
+use directories::ProjectDirs;
+
+// 默认档案的名字,旧版本的数据会搬到这个档案里
+pub const DEFAULT_PROFILE: &str = "default";
+
+// 每个档案各自一份的数据文件
+pub const PROFILE_FILES: &[&str] = &[
+    "language_pref.txt",
+    "lua_saves.json",
+    "stats.json",
+    "activity.json",
+    "history.jsonl",
+    "achievements.json",
+    "settings.json",
+    "keymap.json",
+];
+
+// 旧版本放在程序旁边的数据目录名
+pub const LEGACY_DATA_DIR: &str = "tui-game-data";
+
+// 指定数据目录的环境变量,命令行的--data-dir优先
+pub const DATA_DIR_ENV: &str = "TUI_GAME_DATA_DIR";
+
+static ACTIVE_PROFILE: RwLock<String> = RwLock::new(String::new()); // 当前档案,空字符串表示默认档案
+static DATA_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None); // --data-dir指定的目录
 
 // 项目根目录
 pub fn project_root() -> Result<PathBuf> {
@@ -21,15 +47,227 @@ pub fn runtime_dir() -> Result<PathBuf> {
     project_root()
 }
 
-// 程序可执行文件附近的程序数据目录
+// 用命令行参数指定数据目录,设置、缓存和档案都放在这一个目录里
+pub fn set_data_dir_override(dir: PathBuf) {
+    if let Ok(mut data_dir) = DATA_DIR_OVERRIDE.write() {
+        *data_dir = Some(dir);
+    }
+}
+
+// 命令行参数或环境变量指定的数据目录
+fn data_dir_override() -> Option<PathBuf> {
+    if let Ok(data_dir) = DATA_DIR_OVERRIDE.read()
+        && let Some(dir) = data_dir.as_ref()
+    {
+        return Some(dir.clone());
+    }
+    std::env::var_os(DATA_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+// 系统的用户目录,例如Linux上的~/.local/share/tui-game
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "tui-game")
+}
+
+// 按指定目录、系统目录、程序旁边的顺序选目录,目录在启动时由ensure_data_dirs创建
+fn resolve_dir(system_dir: impl FnOnce(&ProjectDirs) -> PathBuf) -> Result<PathBuf> {
+    Ok(match (data_dir_override(), project_dirs()) {
+        (Some(dir), _) => dir,
+        (None, Some(dirs)) => system_dir(&dirs),
+        // 拿不到用户目录时退回程序旁边,和旧版本一样
+        (None, None) => runtime_dir()?.join(LEGACY_DATA_DIR),
+    })
+}
+
+// 程序数据目录,放档案、排行榜和用户游戏库
 pub fn app_data_dir() -> Result<PathBuf> {
-    // 在运行时目录下创建tui-game-data子目录
-    let dir = runtime_dir()?.join("tui-game-data");
-    // 自动创建目录
-    fs::create_dir_all(&dir)?;
+    resolve_dir(|dirs| dirs.data_dir().to_path_buf())
+}
+
+// 配置目录,放不属于某个档案的配置
+pub fn config_dir() -> Result<PathBuf> {
+    resolve_dir(|dirs| dirs.config_dir().to_path_buf())
+}
+
+// 缓存目录,里面的文件删掉后会重新生成
+pub fn cache_dir() -> Result<PathBuf> {
+    resolve_dir(|dirs| dirs.cache_dir().to_path_buf())
+}
+
+// 启动时建好数据、配置、缓存、档案和用户游戏库目录,之后取路径时不再创建
+// 要在migrate_legacy_data_dir之后调用,提前建好档案目录会让旧数据不再搬过来
+pub fn ensure_data_dirs() -> Result<()> {
+    fs::create_dir_all(profiles_dir()?)?;
+    fs::create_dir_all(user_games_dir()?)?;
+    fs::create_dir_all(config_dir()?)?;
+    fs::create_dir_all(cache_dir()?)?;
+    Ok(())
+}
+
+// 新的数据目录建不了或者旧数据搬不动时,这次启动继续用程序旁边的旧目录
+// 玩家用--data-dir或环境变量指定了目录时不换,返回None
+// 新目录还没有档案目录,下次启动会再搬一次
+pub fn use_legacy_data_dir() -> Result<Option<PathBuf>> {
+    if data_dir_override().is_some() {
+        return Ok(None);
+    }
+    let legacy = runtime_dir()?.join(LEGACY_DATA_DIR);
+    set_data_dir_override(legacy.clone());
+    ensure_data_dirs()?;
+    Ok(Some(legacy))
+}
+
+// 旧版本把数据放在程序旁边的tui-game-data里,新的数据目录还没有档案时整个搬过去
+// 搬过来时返回旧目录的路径,用来提示玩家
+pub fn migrate_legacy_data_dir() -> Result<Option<PathBuf>> {
+    let legacy = runtime_dir()?.join(LEGACY_DATA_DIR);
+    let data_dir = app_data_dir()?;
+    if !legacy.is_dir() || same_dir(&legacy, &data_dir) || data_dir.join("profiles").exists() {
+        return Ok(None);
+    }
+
+    // 先全部复制过去,都成功了才删旧目录里的文件
+    // 中途失败时删掉这次复制出来的,旧目录保持原样,这次启动继续用它
+    let mut copied = Vec::new();
+    let mut created = Vec::new();
+    if let Err(err) = copy_legacy_data(&legacy, &data_dir, &mut copied, &mut created) {
+        for target in created.iter().rev() {
+            let _ = remove_path(target);
+        }
+        return Err(err);
+    }
+    // 只读安装删不掉旧文件,数据已经复制过去了
+    for source in &copied {
+        let _ = remove_path(source);
+    }
+    let _ = fs::remove_dir(&legacy);
+    Ok(Some(legacy))
+}
+
+// 把旧目录里新目录还没有的文件复制过去,记下复制了哪些和新建了哪些
+fn copy_legacy_data(
+    legacy: &Path,
+    data_dir: &Path,
+    copied: &mut Vec<PathBuf>,
+    created: &mut Vec<PathBuf>,
+) -> Result<()> {
+    for entry in fs::read_dir(legacy)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let target = match name.to_str() {
+            Some("scan_cache.json" | "updater_cache.json") => cache_dir()?.join(&name),
+            Some("last_profile.txt") => config_dir()?.join(&name),
+            _ => data_dir.join(&name),
+        };
+        if !target.exists() {
+            ensure_parent_dir(&target)?;
+            created.push(target.clone());
+            copy_path(&entry.path(), &target)?;
+            copied.push(entry.path());
+        }
+    }
+    // 很旧的版本没有档案目录,根目录里的文件第一次用默认档案时再搬
+    // 建好档案目录之后,旧目录删不掉也不会再搬一次
+    let profiles = data_dir.join("profiles");
+    if !profiles.exists() {
+        created.push(profiles.clone());
+        fs::create_dir_all(&profiles)?;
+    }
+    Ok(())
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn copy_path(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        copy_dir(from, to)
+    } else {
+        fs::copy(from, to)?;
+        Ok(())
+    }
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+// 递归复制目录
+pub fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        let dest = to.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir(&path, &dest)?;
+        } else {
+            fs::copy(&path, &dest)?;
+        }
+    }
+    Ok(())
+}
+
+// 当前使用的档案名
+pub fn active_profile() -> String {
+    match ACTIVE_PROFILE.read() {
+        Ok(name) if !name.is_empty() => name.clone(),
+        _ => DEFAULT_PROFILE.to_string(),
+    }
+}
+
+// 切换当前档案,之后的存档、统计和语言设置都读写这个档案
+pub fn set_active_profile(name: &str) {
+    if let Ok(mut active) = ACTIVE_PROFILE.write() {
+        *active = name.to_string();
+    }
+}
+
+// 所有档案所在的目录
+pub fn profiles_dir() -> Result<PathBuf> {
+    Ok(app_data_dir()?.join("profiles"))
+}
+
+// 当前档案的数据目录
+// 第一次用到默认档案时,把旧版本放在程序数据目录里的文件搬进来
+pub fn profile_data_dir() -> Result<PathBuf> {
+    let name = active_profile();
+    let dir = profiles_dir()?.join(&name);
+    if !dir.exists() {
+        fs::create_dir_all(&dir)?;
+        if name == DEFAULT_PROFILE {
+            migrate_legacy_data(&dir)?;
+        }
+    }
     Ok(dir)
 }
 
+fn migrate_legacy_data(dir: &Path) -> Result<()> {
+    let data_dir = app_data_dir()?;
+    for name in PROFILE_FILES {
+        let legacy = data_dir.join(name);
+        if legacy.exists() {
+            fs::rename(&legacy, dir.join(name))?;
+        }
+    }
+    Ok(())
+}
+
+// 上次使用的档案名
+pub fn last_profile_file() -> Result<PathBuf> {
+    Ok(config_dir()?.join("last_profile.txt"))
+}
+
 // 脚本目录
 pub fn scripts_dir() -> Result<PathBuf> {
     // 优先检查运行时目录下的scripts
@@ -41,24 +279,65 @@ pub fn scripts_dir() -> Result<PathBuf> {
     Ok(project_root()?.join("scripts"))
 }
 
-// 程序数据中的更新缓存目录
+// 用户游戏库目录
+// 放在程序数据目录下,更新或重装程序时不会被覆盖
+pub fn user_games_dir() -> Result<PathBuf> {
+    Ok(app_data_dir()?.join("games"))
+}
+
+// 缓存目录中的更新检查缓存
 pub fn updater_cache_file() -> Result<PathBuf> {
-    Ok(app_data_dir()?.join("updater_cache.json"))
+    Ok(cache_dir()?.join("updater_cache.json"))
 }
 
-// 程序数据中的语言文件目录
+// 当前档案的语言设置
 pub fn language_pref_file() -> Result<PathBuf> {
-    Ok(app_data_dir()?.join("language_pref.txt"))
+    Ok(profile_data_dir()?.join("language_pref.txt"))
+}
+
+// 当前档案的设置项
+pub fn settings_file() -> Result<PathBuf> {
+    Ok(profile_data_dir()?.join("settings.json"))
+}
+
+// 当前档案改过的按键
+pub fn keymap_file() -> Result<PathBuf> {
+    Ok(profile_data_dir()?.join("keymap.json"))
 }
 
-// 程序数据中的Lua脚本保存目录
+// 当前档案的Lua脚本保存目录
 pub fn lua_saves_file() -> Result<PathBuf> {
-    Ok(app_data_dir()?.join("lua_saves.json"))
+    Ok(profile_data_dir()?.join("lua_saves.json"))
+}
+
+// 缓存目录中的游戏元数据扫描缓存
+pub fn scan_cache_file() -> Result<PathBuf> {
+    Ok(cache_dir()?.join("scan_cache.json"))
 }
 
-// 程序数据中的游戏数据统计目录
+// 当前档案的游戏数据统计
 pub fn stats_file() -> Result<PathBuf> {
-    Ok(app_data_dir()?.join("stats.json"))
+    Ok(profile_data_dir()?.join("stats.json"))
+}
+
+// 当前档案的收藏和启动记录
+pub fn activity_file() -> Result<PathBuf> {
+    Ok(profile_data_dir()?.join("activity.json"))
+}
+
+// 当前档案的游玩历史,每局一行只追加
+pub fn history_file() -> Result<PathBuf> {
+    Ok(profile_data_dir()?.join("history.jsonl"))
+}
+
+// 当前档案的成就进度
+pub fn achievements_file() -> Result<PathBuf> {
+    Ok(profile_data_dir()?.join("achievements.json"))
+}
+
+// 程序数据中的本地排行榜,所有档案共用
+pub fn leaderboard_file() -> Result<PathBuf> {
+    Ok(app_data_dir()?.join("leaderboards.json"))
 }
 
 // 执行文件附近的外部更新脚本文件路径
@@ -74,6 +353,26 @@ pub fn version_script_file() -> Result<PathBuf> {
     }
 }
 
+// 把相对路径接到base下面,绝对路径和带..的路径返回None
+// 路径已经存在时再按真实路径检查一次,防止通过符号链接跑到base外面
+pub fn join_inside(base: &Path, name: &str) -> Option<PathBuf> {
+    let relative = Path::new(name);
+    let plain = relative
+        .components()
+        .all(|part| matches!(part, Component::Normal(_) | Component::CurDir));
+    if name.trim().is_empty() || !plain {
+        return None;
+    }
+
+    let path = base.join(relative);
+    if let (Ok(real), Ok(real_base)) = (path.canonicalize(), base.canonicalize())
+        && !real.starts_with(&real_base)
+    {
+        return None;
+    }
+    Some(path)
+}
+
 // 确保文件路径父目录的存在
 pub fn ensure_parent_dir(path: &Path) -> Result<()> {
     if let Some(parent) = path.parent() {